@@ -0,0 +1,26 @@
+season-spring = Frühling
+season-summer = Sommer
+season-autumn = Herbst
+season-winter = Winter
+
+occasion-casual = Freizeit
+occasion-work = Arbeit
+occasion-formal = Festlich
+occasion-party = Party
+occasion-date = Date
+occasion-sports = Sport
+occasion-travel = Reise
+occasion-outdoor = Outdoor
+occasion-home = Zuhause
+occasion-other = Sonstiges
+
+style-casual = Lässig
+style-smart-casual = Smart Casual
+style-business = Business
+style-formal = Formell
+style-street = Streetwear
+style-minimal = Minimalistisch
+style-classic = Klassisch
+style-sporty = Sportlich
+style-feminine = Feminin
+style-other = Sonstiges
@@ -0,0 +1,26 @@
+season-spring = Spring
+season-summer = Summer
+season-autumn = Autumn
+season-winter = Winter
+
+occasion-casual = Casual
+occasion-work = Work
+occasion-formal = Formal
+occasion-party = Party
+occasion-date = Date
+occasion-sports = Sports
+occasion-travel = Travel
+occasion-outdoor = Outdoor
+occasion-home = Home
+occasion-other = Other
+
+style-casual = Casual
+style-smart-casual = Smart casual
+style-business = Business
+style-formal = Formal
+style-street = Street
+style-minimal = Minimal
+style-classic = Classic
+style-sporty = Sporty
+style-feminine = Feminine
+style-other = Other
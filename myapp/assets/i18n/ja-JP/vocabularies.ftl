@@ -0,0 +1,26 @@
+season-spring = 春
+season-summer = 夏
+season-autumn = 秋
+season-winter = 冬
+
+occasion-casual = 普段着
+occasion-work = 仕事
+occasion-formal = フォーマル
+occasion-party = パーティー
+occasion-date = デート
+occasion-sports = スポーツ
+occasion-travel = 旅行
+occasion-outdoor = アウトドア
+occasion-home = 部屋着
+occasion-other = その他
+
+style-casual = カジュアル
+style-smart-casual = きれいめ
+style-business = ビジネス
+style-formal = フォーマル
+style-street = ストリート
+style-minimal = シンプル
+style-classic = トラッド
+style-sporty = スポーティー
+style-feminine = フェミニン
+style-other = その他
@@ -9,6 +9,27 @@ mod m20250607_000003_clothes_coordinates;
 mod m20250607_154529_remove_verification_columns;
 mod m20250608_041640_passkeys;
 mod m20250608_041833_create_webauthn_sessions;
+mod m20250612_000001_clothes_colors;
+mod m20250613_000001_clothes_sizes;
+mod m20250614_000001_clothes_imports;
+mod m20250615_000001_wardrobe_exports;
+mod m20250616_000001_wear_events;
+mod m20250617_000001_outfit_plans;
+mod m20250618_000001_calendar_feeds;
+mod m20250619_000001_clothes_coordinates_layout;
+mod m20250620_000001_coordinate_rules;
+mod m20250621_000001_coordinate_versions;
+mod m20250622_000001_outfit_feedback;
+mod m20250623_000001_coordinate_vocabularies;
+mod m20250624_000001_trips;
+mod m20250625_000001_clothes_coordinates_unique;
+mod m20250626_000001_coordinate_tombstones;
+mod m20250627_000001_soft_delete;
+mod m20250628_000001_clothes_lifecycle;
+mod m20250629_000001_inventory_movements;
+mod m20250630_000001_currencies;
+mod m20250701_000001_purchases;
+mod m20250702_000001_coordinate_tombstones_links;
 pub struct Migrator;
 
 #[async_trait::async_trait]
@@ -22,6 +43,27 @@ impl MigratorTrait for Migrator {
             Box::new(m20250607_154529_remove_verification_columns::Migration),
             Box::new(m20250608_041640_passkeys::Migration),
             Box::new(m20250608_041833_create_webauthn_sessions::Migration),
+            Box::new(m20250612_000001_clothes_colors::Migration),
+            Box::new(m20250613_000001_clothes_sizes::Migration),
+            Box::new(m20250614_000001_clothes_imports::Migration),
+            Box::new(m20250615_000001_wardrobe_exports::Migration),
+            Box::new(m20250616_000001_wear_events::Migration),
+            Box::new(m20250617_000001_outfit_plans::Migration),
+            Box::new(m20250618_000001_calendar_feeds::Migration),
+            Box::new(m20250619_000001_clothes_coordinates_layout::Migration),
+            Box::new(m20250620_000001_coordinate_rules::Migration),
+            Box::new(m20250621_000001_coordinate_versions::Migration),
+            Box::new(m20250622_000001_outfit_feedback::Migration),
+            Box::new(m20250623_000001_coordinate_vocabularies::Migration),
+            Box::new(m20250624_000001_trips::Migration),
+            Box::new(m20250625_000001_clothes_coordinates_unique::Migration),
+            Box::new(m20250626_000001_coordinate_tombstones::Migration),
+            Box::new(m20250627_000001_soft_delete::Migration),
+            Box::new(m20250628_000001_clothes_lifecycle::Migration),
+            Box::new(m20250629_000001_inventory_movements::Migration),
+            Box::new(m20250630_000001_currencies::Migration),
+            Box::new(m20250701_000001_purchases::Migration),
+            Box::new(m20250702_000001_coordinate_tombstones_links::Migration),
             // inject-above (do not remove this comment)
         ]
     }
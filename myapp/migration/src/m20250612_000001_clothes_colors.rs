@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const COLUMNS: [&str; 4] = [
+    "primary_color_hex",
+    "primary_color_family",
+    "secondary_color_hex",
+    "secondary_color_family",
+];
+
+/// Snapshot of the color dictionary used to map existing free-form `color`
+/// values. Kept local to this migration so later palette changes in the app
+/// do not alter what this migration does.
+const COLOR_NAMES: &[(&[&str], &str, &str)] = &[
+    (&["black", "ブラック", "黒", "くろ"], "#000000", "neutral"),
+    (&["white", "ホワイト", "白", "しろ"], "#FFFFFF", "neutral"),
+    (&["gray", "grey", "グレー", "グレイ", "灰色", "はいいろ"], "#808080", "neutral"),
+    (&["light gray", "light grey", "ライトグレー"], "#D3D3D3", "neutral"),
+    (&["charcoal", "charcoal gray", "チャコール", "チャコールグレー"], "#36454F", "neutral"),
+    (&["beige", "ベージュ"], "#D8C8A8", "neutral"),
+    (&["ivory", "アイボリー"], "#FFFFF0", "neutral"),
+    (&["off white", "off-white", "オフホワイト", "生成り"], "#F8F4E8", "neutral"),
+    (&["cream", "クリーム"], "#FFFDD0", "neutral"),
+    (&["greige", "グレージュ"], "#B5ADA1", "neutral"),
+    (&["navy", "ネイビー", "紺", "紺色"], "#1F2A44", "blue"),
+    (&["blue", "ブルー", "青", "あお"], "#1E56C8", "blue"),
+    (&["light blue", "sky blue", "ライトブルー", "サックス", "水色"], "#87CEEB", "blue"),
+    (&["denim", "indigo", "デニム", "インディゴ", "藍色"], "#3B4F7A", "blue"),
+    (&["turquoise", "ターコイズ"], "#40E0D0", "blue"),
+    (&["red", "レッド", "赤", "あか"], "#D0202E", "red"),
+    (&["burgundy", "bordeaux", "wine", "wine red", "ボルドー", "バーガンディ", "ワインレッド", "えんじ"], "#800020", "red"),
+    (&["pink", "ピンク", "桃色"], "#F4A6C0", "pink"),
+    (&["rose", "ローズ"], "#E0607E", "pink"),
+    (&["orange", "オレンジ", "橙", "橙色"], "#F08A24", "orange"),
+    (&["terracotta", "テラコッタ"], "#C8643C", "orange"),
+    (&["yellow", "イエロー", "黄色", "きいろ"], "#F5D033", "yellow"),
+    (&["mustard", "マスタード", "からし色"], "#D4A017", "yellow"),
+    (&["green", "グリーン", "緑", "みどり"], "#2E8B57", "green"),
+    (&["olive", "オリーブ"], "#708238", "green"),
+    (&["khaki", "カーキ"], "#8A8450", "green"),
+    (&["mint", "ミント"], "#98E0C0", "green"),
+    (&["brown", "ブラウン", "茶色", "茶"], "#6F4E37", "brown"),
+    (&["camel", "キャメル"], "#C19A6B", "brown"),
+    (&["dark brown", "ダークブラウン", "焦げ茶"], "#4A3020", "brown"),
+    (&["purple", "パープル", "紫", "むらさき"], "#7D3C98", "purple"),
+    (&["lavender", "ラベンダー"], "#B9A7DC", "purple"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for column in COLUMNS {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new("clothes"))
+                    .add_column(ColumnDef::new(Alias::new(column)).string())
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_primary_color_family")
+                .table(Alias::new("clothes"))
+                .col(Alias::new("primary_color_family"))
+                .to_owned(),
+        )
+        .await?;
+
+        // Map existing free-form color names onto the palette
+        for (names, hex, family) in COLOR_NAMES {
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("clothes"))
+                    .value(Alias::new("primary_color_hex"), *hex)
+                    .value(Alias::new("primary_color_family"), *family)
+                    .and_where(Expr::cust("LOWER(TRIM(color))").is_in(names.iter().copied()))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_index(
+            Index::drop()
+                .name("idx_clothes_primary_color_family")
+                .table(Alias::new("clothes"))
+                .to_owned(),
+        )
+        .await?;
+
+        for column in COLUMNS {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new("clothes"))
+                    .drop_column(Alias::new(column))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
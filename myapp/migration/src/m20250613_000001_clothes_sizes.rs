@@ -0,0 +1,356 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const CM_PER_INCH: f64 = 2.54;
+
+/// Snapshot of the app's size tables, used to normalize the sizes of
+/// existing clothes. Kept local to this migration so later changes to
+/// sizing in the app do not alter what this migration does.
+const SHOE_WORDS: &[&str] = &[
+    "shoe", "shoes", "sneaker", "sneakers", "boot", "boots", "sandal", "sandals", "loafer",
+    "loafers", "pump", "pumps", "heel", "heels", "footwear",
+];
+const BOTTOM_WORDS: &[&str] = &[
+    "bottom",
+    "bottoms",
+    "pant",
+    "pants",
+    "trouser",
+    "trousers",
+    "jean",
+    "jeans",
+    "shorts",
+    "skirt",
+    "skirts",
+    "slacks",
+    "chino",
+    "chinos",
+    "sweatpants",
+    "joggers",
+    "leggings",
+];
+const SHOE_SUFFIXES: &[&str] = &[
+    "靴",
+    "シューズ",
+    "スニーカー",
+    "ブーツ",
+    "サンダル",
+    "パンプス",
+    "ローファー",
+];
+const BOTTOM_SUFFIXES: &[&str] = &[
+    "ボトム",
+    "ボトムス",
+    "パンツ",
+    "ズボン",
+    "ジーンズ",
+    "デニム",
+    "スカート",
+    "スラックス",
+];
+
+/// Letter sizes, smallest first, with the ways they are written
+const LETTERS: &[(&str, &[&str])] = &[
+    ("XXS", &["XXS", "2XS", "SS"]),
+    ("XS", &["XS"]),
+    ("S", &["S"]),
+    ("M", &["M"]),
+    ("L", &["L"]),
+    ("XL", &["XL", "LL"]),
+    ("XXL", &["XXL", "2XL", "3L"]),
+    ("XXXL", &["XXXL", "3XL", "4L"]),
+];
+
+/// Letter size, JP size, US, UK and EU ranges, and the waist in cm it fits
+/// below
+type ApparelRow = (&'static str, u8, (u8, u8), (u8, u8), (u8, u8), f64);
+
+const APPAREL: &[ApparelRow] = &[
+    ("XXS", 3, (0, 0), (2, 2), (30, 30), 61.0),
+    ("XS", 5, (1, 2), (4, 6), (32, 34), 66.0),
+    ("S", 7, (4, 6), (8, 10), (36, 38), 72.0),
+    ("M", 9, (8, 10), (12, 14), (40, 42), 78.0),
+    ("L", 11, (12, 14), (16, 18), (44, 46), 84.0),
+    ("XL", 13, (16, 18), (20, 22), (48, 50), 90.0),
+    ("XXL", 15, (20, 22), (24, 26), (52, 54), 98.0),
+    ("XXXL", 17, (24, 26), (28, 30), (56, 58), f64::INFINITY),
+];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SizeGroup {
+    Apparel,
+    Bottoms,
+    Shoes,
+}
+
+impl SizeGroup {
+    fn from_category(category: &str) -> Self {
+        category
+            .trim()
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .rev()
+            .find_map(|word| {
+                let ends_with_any = |suffixes: &[&str]| suffixes.iter().any(|s| word.ends_with(s));
+                if SHOE_WORDS.contains(&word) || ends_with_any(SHOE_SUFFIXES) {
+                    Some(Self::Shoes)
+                } else if BOTTOM_WORDS.contains(&word) || ends_with_any(BOTTOM_SUFFIXES) {
+                    Some(Self::Bottoms)
+                } else {
+                    None
+                }
+            })
+            .unwrap_or(Self::Apparel)
+    }
+}
+
+/// The normalized size columns of a garment
+struct NormalizedSize {
+    system: &'static str,
+    letter: Option<&'static str>,
+    cm: Option<f64>,
+    inseam_cm: Option<f64>,
+}
+
+impl NormalizedSize {
+    fn letter(system: &'static str, letter: &'static str) -> Self {
+        Self {
+            system,
+            letter: Some(letter),
+            cm: None,
+            inseam_cm: None,
+        }
+    }
+}
+
+/// Round to the one decimal the `decimal(5, 1)` columns hold
+fn one_decimal(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn parse_letter(value: &str) -> Option<&'static str> {
+    let value = value.trim().to_uppercase();
+    LETTERS
+        .iter()
+        .find(|(_, spellings)| spellings.contains(&value.as_str()))
+        .map(|(letter, _)| *letter)
+}
+
+fn parse(value: &str, group: SizeGroup) -> Option<NormalizedSize> {
+    let value = value.trim().to_uppercase().replace('　', " ");
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Some(letter) = parse_letter(value) {
+        return Some(NormalizedSize::letter("letter", letter));
+    }
+    match group {
+        SizeGroup::Shoes => parse_shoe(value),
+        SizeGroup::Bottoms => parse_bottoms(value),
+        SizeGroup::Apparel => parse_apparel(value),
+    }
+}
+
+fn split_system(value: &str) -> (Option<&'static str>, &str) {
+    for (prefix, system) in [("JP", "jp"), ("US", "us"), ("EU", "eu"), ("UK", "uk")] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (Some(system), rest.trim());
+        }
+    }
+    if let Some(rest) = value.strip_suffix("号") {
+        return (Some("jp"), rest.trim());
+    }
+    if let Some(rest) = value.strip_suffix("CM") {
+        return (Some("cm"), rest.trim());
+    }
+    (None, value)
+}
+
+fn parse_apparel(value: &str) -> Option<NormalizedSize> {
+    let (system, number) = split_system(value);
+    if let Some(letter) = parse_letter(number) {
+        return Some(NormalizedSize::letter(system.unwrap_or("letter"), letter));
+    }
+    let number: u8 = number.parse().ok()?;
+    let system = system.or(match number {
+        3..=19 if number % 2 == 1 => Some("jp"),
+        30..=58 => Some("eu"),
+        _ => None,
+    })?;
+    let in_range = |(low, high): (u8, u8)| (low..=high).contains(&number);
+    let (letter, ..) = APPAREL.iter().find(|(_, jp, us, uk, eu, _)| match system {
+        "jp" => *jp == number,
+        "us" => in_range(*us),
+        "uk" => in_range(*uk),
+        "eu" => in_range(*eu),
+        _ => false,
+    })?;
+    Some(NormalizedSize::letter(system, letter))
+}
+
+fn parse_shoe(value: &str) -> Option<NormalizedSize> {
+    let (system, number) = split_system(value);
+    let number: f64 = number.parse().ok()?;
+    let system = system.or(if (20.0..=32.0).contains(&number) {
+        Some("cm")
+    } else if (34.0..=50.0).contains(&number) {
+        Some("eu")
+    } else if (3.0..=16.0).contains(&number) {
+        Some("us")
+    } else {
+        None
+    })?;
+    let cm = match system {
+        "cm" | "jp" => number,
+        "us" => number + 18.0,
+        "uk" => number + 19.0,
+        "eu" => number / 1.5 - 1.5,
+        _ => return None,
+    };
+    Some(NormalizedSize {
+        system,
+        letter: None,
+        cm: Some((cm * 2.0).round() / 2.0),
+        inseam_cm: None,
+    })
+}
+
+fn parse_bottoms(value: &str) -> Option<NormalizedSize> {
+    let parts: Vec<&str> = value
+        .split(|c: char| c == '/' || c == 'X' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let waist_part = parts.first()?.trim_start_matches('W');
+    let inseam_part = parts.get(1).map(|part| part.trim_start_matches('L'));
+
+    if let (Some(_), Some(size)) = (split_system(value).0, parse_apparel(value)) {
+        return Some(size);
+    }
+
+    let (system, waist) = match waist_part.strip_suffix("CM") {
+        Some(waist) => ("cm", waist.parse::<f64>().ok()?),
+        None => {
+            let waist = waist_part.parse::<f64>().ok()?;
+            if waist >= 50.0 {
+                ("cm", waist)
+            } else {
+                ("inch", waist * CM_PER_INCH)
+            }
+        }
+    };
+    let inseam = match inseam_part {
+        Some(part) => {
+            let inseam = part.trim_end_matches("CM").parse::<f64>().ok()?;
+            Some(if system == "inch" {
+                inseam * CM_PER_INCH
+            } else {
+                inseam
+            })
+        }
+        None => None,
+    };
+    let letter = APPAREL
+        .iter()
+        .find(|(.., waist_cm_below)| waist < *waist_cm_below)
+        .map_or("XXXL", |(letter, ..)| *letter);
+
+    Some(NormalizedSize {
+        system,
+        letter: Some(letter),
+        cm: Some(waist),
+        inseam_cm: inseam,
+    })
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Normalized size derived from the free-form `size` column
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(ColumnDef::new(Alias::new("size_system")).string())
+                .to_owned(),
+        )
+        .await?;
+
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(ColumnDef::new(Alias::new("size_letter")).string())
+                .to_owned(),
+        )
+        .await?;
+
+        // Foot length for shoes, waist for bottoms
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(ColumnDef::new(Alias::new("size_cm")).decimal_len(5, 1))
+                .to_owned(),
+        )
+        .await?;
+
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(ColumnDef::new(Alias::new("inseam_cm")).decimal_len(5, 1))
+                .to_owned(),
+        )
+        .await?;
+
+        // Normalize the sizes of existing clothes, so they show up in size
+        // filters without running the `normalize_sizes` task
+        let db = m.get_connection();
+        let rows = db
+            .query_all(
+                db.get_database_backend().build(
+                    Query::select()
+                        .columns([Alias::new("id"), Alias::new("category"), Alias::new("size")])
+                        .from(Alias::new("clothes")),
+                ),
+            )
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let category: String = row.try_get("", "category")?;
+            let size: String = row.try_get("", "size")?;
+            let Some(normalized) = parse(&size, SizeGroup::from_category(&category)) else {
+                continue;
+            };
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("clothes"))
+                    .value(Alias::new("size_system"), normalized.system)
+                    .value(Alias::new("size_letter"), normalized.letter)
+                    .value(Alias::new("size_cm"), normalized.cm.map(one_decimal))
+                    .value(
+                        Alias::new("inseam_cm"),
+                        normalized.inseam_cm.map(one_decimal),
+                    )
+                    .and_where(Expr::col(Alias::new("id")).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for column in ["size_system", "size_letter", "size_cm", "inseam_cm"] {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new("clothes"))
+                    .drop_column(Alias::new(column))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
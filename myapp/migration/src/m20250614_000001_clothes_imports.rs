@@ -0,0 +1,49 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "clothes_imports",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::Uuid),
+                // csv or json
+                ("format", ColType::String),
+                // queued, running, completed or failed
+                ("status", ColType::String),
+                ("total_rows", ColType::Integer),
+                ("processed_rows", ColType::IntegerWithDefault(0)),
+                ("imported_rows", ColType::IntegerWithDefault(0)),
+                // Raw uploaded data and column mapping, cleared once processed
+                ("source", ColType::TextNull),
+                ("mapping", ColType::JsonNull),
+                // Per-row error report
+                ("errors", ColType::JsonNull),
+            ],
+            &[],
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_imports_pid")
+                .table(Alias::new("clothes_imports"))
+                .col(Alias::new("pid"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "clothes_imports").await
+    }
+}
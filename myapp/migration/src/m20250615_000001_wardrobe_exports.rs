@@ -0,0 +1,63 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "wardrobe_exports",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::Uuid),
+                // csv, json or zip
+                ("format", ColType::String),
+                // queued, running, completed or failed
+                ("status", ColType::String),
+                // Storage path of the finished file
+                ("file_path", ColType::StringNull),
+                // Token of the emailed download link and its expiry
+                ("download_token", ColType::StringNull),
+                ("expires_at", ColType::TimestampWithTimeZoneNull),
+                ("error", ColType::TextNull),
+            ],
+            &[("user", "")],
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_wardrobe_exports_pid")
+                .table(Alias::new("wardrobe_exports"))
+                .col(Alias::new("pid"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        // Owner of the coordinates restored from a wardrobe backup
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes_imports"))
+                .add_column(ColumnDef::new(Alias::new("user_id")).integer())
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes_imports"))
+                .drop_column(Alias::new("user_id"))
+                .to_owned(),
+        )
+        .await?;
+        drop_table(m, "wardrobe_exports").await
+    }
+}
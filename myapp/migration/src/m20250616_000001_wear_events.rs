@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.create_table(
+            Table::create()
+                .table(Alias::new("wear_events"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("pid"))
+                        .uuid()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("clothes_id"))
+                        .integer()
+                        .not_null(),
+                )
+                // Set when the garment was worn as part of a whole coordinate
+                .col(ColumnDef::new(Alias::new("coordinate_id")).integer())
+                .col(ColumnDef::new(Alias::new("worn_on")).date().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_wear_events_user_id")
+                        .from(Alias::new("wear_events"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_wear_events_clothes_id")
+                        .from(Alias::new("wear_events"), Alias::new("clothes_id"))
+                        .to(Alias::new("clothes"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_wear_events_coordinate_id")
+                        .from(Alias::new("wear_events"), Alias::new("coordinate_id"))
+                        .to(Alias::new("coordinates"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_wear_events_user_id_worn_on")
+                .table(Alias::new("wear_events"))
+                .col(Alias::new("user_id"))
+                .col(Alias::new("worn_on"))
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_wear_events_clothes_id")
+                .table(Alias::new("wear_events"))
+                .col(Alias::new("clothes_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(Table::drop().table(Alias::new("wear_events")).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "outfit_plans",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::Uuid),
+                ("planned_on", ColType::Date),
+                ("occasion", ColType::StringNull),
+                ("note", ColType::TextNull),
+                // Set once the day is marked as done and wear is logged
+                ("done_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("user", ""), ("coordinate", "")],
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_outfit_plans_pid")
+                .table(Alias::new("outfit_plans"))
+                .col(Alias::new("pid"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_outfit_plans_user_id_planned_on")
+                .table(Alias::new("outfit_plans"))
+                .col(Alias::new("user_id"))
+                .col(Alias::new("planned_on"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "outfit_plans").await
+    }
+}
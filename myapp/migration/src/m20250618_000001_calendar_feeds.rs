@@ -0,0 +1,50 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "calendar_feeds",
+            &[
+                ("id", ColType::PkAuto),
+                ("pid", ColType::Uuid),
+                // Secret part of the subscription URL
+                ("token", ColType::String),
+                ("revoked_at", ColType::TimestampWithTimeZoneNull),
+            ],
+            &[("user", "")],
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_calendar_feeds_pid")
+                .table(Alias::new("calendar_feeds"))
+                .col(Alias::new("pid"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_calendar_feeds_token")
+                .table(Alias::new("calendar_feeds"))
+                .col(Alias::new("token"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "calendar_feeds").await
+    }
+}
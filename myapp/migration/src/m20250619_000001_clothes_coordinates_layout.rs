@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const SLOTS: &str = "'head', 'top', 'outer', 'bottom', 'shoes', 'accessory', 'bag'";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Outfit slot plus the item's placement on the outfit board canvas
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes_coordinates"))
+                .add_column(ColumnDef::new(Alias::new("slot")).string())
+                .add_column(ColumnDef::new(Alias::new("x")).double())
+                .add_column(ColumnDef::new(Alias::new("y")).double())
+                .add_column(ColumnDef::new(Alias::new("scale")).double())
+                .add_column(ColumnDef::new(Alias::new("rotation")).double())
+                .add_column(ColumnDef::new(Alias::new("z_index")).integer())
+                .to_owned(),
+        )
+        .await?;
+
+        // Keep free-form positions that name a slot
+        let db = m.get_connection();
+        db.execute_unprepared(
+            "UPDATE clothes_coordinates SET slot = 'outer' \
+             WHERE lower(trim(position)) IN ('outer', 'outerwear')",
+        )
+        .await?;
+        db.execute_unprepared(&format!(
+            "UPDATE clothes_coordinates SET slot = lower(trim(position)) \
+             WHERE slot IS NULL AND lower(trim(position)) IN ({SLOTS})"
+        ))
+        .await?;
+
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes_coordinates"))
+                .drop_column(Alias::new("position"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes_coordinates"))
+                .add_column(ColumnDef::new(Alias::new("position")).string())
+                .to_owned(),
+        )
+        .await?;
+        m.get_connection()
+            .execute_unprepared("UPDATE clothes_coordinates SET position = slot")
+            .await?;
+
+        for column in ["slot", "x", "y", "scale", "rotation", "z_index"] {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new("clothes_coordinates"))
+                    .drop_column(Alias::new(column))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "coordinate_rules",
+            &[
+                ("id", ColType::PkAuto),
+                // Required slots, max items per slot and forbidden
+                // combinations
+                ("rules", ColType::Json),
+            ],
+            &[("user", "")],
+        )
+        .await?;
+
+        // One rule set per user
+        m.create_index(
+            Index::create()
+                .name("idx_coordinate_rules_user_id")
+                .table(Alias::new("coordinate_rules"))
+                .col(Alias::new("user_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "coordinate_rules").await
+    }
+}
@@ -0,0 +1,44 @@
+use loco_rs::schema::*;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        create_table(
+            m,
+            "coordinate_versions",
+            &[
+                ("id", ColType::PkAuto),
+                // Numbered from 1 per coordinate
+                ("version", ColType::Integer),
+                // created, updated, clothes_added, clothes_removed,
+                // layout_updated, cloned or restored
+                ("change", ColType::String),
+                // Metadata and garment membership with layout and notes
+                ("snapshot", ColType::Json),
+            ],
+            &[("coordinate", "")],
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_coordinate_versions_coordinate_id_version")
+                .table(Alias::new("coordinate_versions"))
+                .col(Alias::new("coordinate_id"))
+                .col(Alias::new("version"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        drop_table(m, "coordinate_versions").await
+    }
+}
@@ -0,0 +1,94 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.create_table(
+            Table::create()
+                .table(Alias::new("outfit_feedback"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("pid"))
+                        .uuid()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("coordinate_id"))
+                        .integer()
+                        .not_null(),
+                )
+                // One of the wear events logged when the coordinate was worn
+                .col(ColumnDef::new(Alias::new("wear_event_id")).integer())
+                .col(ColumnDef::new(Alias::new("worn_on")).date().not_null())
+                // Ratings from 1 to 5
+                .col(ColumnDef::new(Alias::new("comfort")).small_integer())
+                .col(ColumnDef::new(Alias::new("weather_fit")).small_integer())
+                .col(ColumnDef::new(Alias::new("compliments")).small_integer())
+                .col(ColumnDef::new(Alias::new("note")).text())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_outfit_feedback_user_id")
+                        .from(Alias::new("outfit_feedback"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_outfit_feedback_coordinate_id")
+                        .from(Alias::new("outfit_feedback"), Alias::new("coordinate_id"))
+                        .to(Alias::new("coordinates"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_outfit_feedback_wear_event_id")
+                        .from(Alias::new("outfit_feedback"), Alias::new("wear_event_id"))
+                        .to(Alias::new("wear_events"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::SetNull),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_outfit_feedback_coordinate_id")
+                .table(Alias::new("outfit_feedback"))
+                .col(Alias::new("coordinate_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(Table::drop().table(Alias::new("outfit_feedback")).to_owned())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, JsonValue},
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// Keys and known names as of this migration. The app's vocabularies may grow
+// later; this copy must not change.
+const SEASONS: &[(&str, &[&str])] = &[
+    ("spring", &["春"]),
+    ("summer", &["夏"]),
+    ("autumn", &["fall", "秋"]),
+    ("winter", &["冬"]),
+];
+
+const ALL_SEASONS: &[&str] = &[
+    "all",
+    "all season",
+    "all seasons",
+    "all-season",
+    "year-round",
+    "通年",
+    "オールシーズン",
+];
+
+const OCCASIONS: &[(&str, &[&str])] = &[
+    (
+        "casual",
+        &[
+            "everyday",
+            "daily",
+            "weekend",
+            "カジュアル",
+            "普段",
+            "普段着",
+            "休日",
+        ],
+    ),
+    (
+        "work",
+        &["office", "business", "仕事", "オフィス", "ビジネス", "通勤"],
+    ),
+    (
+        "formal",
+        &["ceremony", "wedding", "フォーマル", "冠婚葬祭", "結婚式"],
+    ),
+    ("party", &["パーティー", "パーティ"]),
+    ("date", &["デート"]),
+    ("sports", &["sport", "gym", "workout", "スポーツ", "ジム"]),
+    ("travel", &["trip", "vacation", "旅行"]),
+    ("outdoor", &["outdoors", "hiking", "camping", "アウトドア"]),
+    ("home", &["lounge", "loungewear", "部屋着", "家"]),
+    ("other", &["その他"]),
+];
+
+const STYLES: &[(&str, &[&str])] = &[
+    ("casual", &["カジュアル"]),
+    ("smart_casual", &["smart", "きれいめ", "キレイめ"]),
+    ("business", &["ビジネス"]),
+    ("formal", &["フォーマル"]),
+    ("street", &["streetwear", "ストリート"]),
+    ("minimal", &["minimalist", "simple", "シンプル", "ミニマル"]),
+    (
+        "classic",
+        &["traditional", "trad", "preppy", "クラシック", "トラッド"],
+    ),
+    ("sporty", &["athleisure", "スポーティ", "スポーティー"]),
+    ("feminine", &["girly", "フェミニン", "ガーリー"]),
+    ("other", &["その他"]),
+];
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+fn key(vocabulary: &[(&'static str, &[&str])], value: &str) -> Option<&'static str> {
+    let value = normalize(value);
+    vocabulary
+        .iter()
+        .find(|(key, names)| *key == value || names.iter().any(|name| normalize(name) == value))
+        .map(|(key, _)| *key)
+}
+
+/// Keys of a free-form season such as `Spring/Summer` or `秋冬`, in season
+/// order
+fn season_keys(value: &str) -> Vec<&'static str> {
+    let value = value.trim().to_lowercase();
+    if ALL_SEASONS.contains(&value.as_str()) {
+        return SEASONS.iter().map(|(key, _)| *key).collect();
+    }
+    let found: Vec<&'static str> = value
+        .split(|c: char| c.is_whitespace() || [',', '/', '&', '+', '・', '、'].contains(&c))
+        .flat_map(|part| match key(SEASONS, part) {
+            Some(key) => vec![key],
+            None => part
+                .chars()
+                .filter_map(|c| key(SEASONS, c.encode_utf8(&mut [0; 4])))
+                .collect(),
+        })
+        .collect();
+    SEASONS
+        .iter()
+        .map(|(key, _)| *key)
+        .filter(|key| found.contains(key))
+        .collect()
+}
+
+/// Known occasion or style key, `other` for unknown values
+fn vocabulary_key(vocabulary: &[(&'static str, &[&str])], value: Option<&str>) -> Option<String> {
+    let value = value.filter(|value| !value.trim().is_empty())?;
+    Some(key(vocabulary, value).unwrap_or("other").to_string())
+}
+
+fn season_list(value: Option<&str>) -> JsonValue {
+    JsonValue::Array(
+        season_keys(value.unwrap_or_default())
+            .into_iter()
+            .map(|key| JsonValue::String(key.to_string()))
+            .collect(),
+    )
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // A coordinate can belong to several seasons
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("coordinates"))
+                .add_column(
+                    ColumnDef::new(Alias::new("seasons"))
+                        .json()
+                        .not_null()
+                        .default(Expr::cust("'[]'")),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        let db = m.get_connection();
+        let backend = db.get_database_backend();
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([
+                            Alias::new("id"),
+                            Alias::new("season"),
+                            Alias::new("occasion"),
+                            Alias::new("style"),
+                        ])
+                        .from(Alias::new("coordinates")),
+                ),
+            )
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let season: Option<String> = row.try_get("", "season")?;
+            let occasion: Option<String> = row.try_get("", "occasion")?;
+            let style: Option<String> = row.try_get("", "style")?;
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("coordinates"))
+                    .values([
+                        (Alias::new("seasons"), season_list(season.as_deref()).into()),
+                        (
+                            Alias::new("occasion"),
+                            vocabulary_key(OCCASIONS, occasion.as_deref()).into(),
+                        ),
+                        (
+                            Alias::new("style"),
+                            vocabulary_key(STYLES, style.as_deref()).into(),
+                        ),
+                    ])
+                    .and_where(Expr::col(Alias::new("id")).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        // Keep recorded versions restorable
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Alias::new("id"), Alias::new("snapshot")])
+                        .from(Alias::new("coordinate_versions")),
+                ),
+            )
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let mut snapshot: JsonValue = row.try_get("", "snapshot")?;
+            let Some(fields) = snapshot.as_object_mut() else {
+                continue;
+            };
+            let season = fields.remove("season");
+            fields.insert(
+                "seasons".to_string(),
+                season_list(season.as_ref().and_then(JsonValue::as_str)),
+            );
+            for (field, vocabulary) in [("occasion", OCCASIONS), ("style", STYLES)] {
+                let value = fields.get(field).and_then(JsonValue::as_str);
+                let normalized =
+                    vocabulary_key(vocabulary, value).map_or(JsonValue::Null, JsonValue::String);
+                fields.insert(field.to_string(), normalized);
+            }
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("coordinate_versions"))
+                    .value(Alias::new("snapshot"), snapshot)
+                    .and_where(Expr::col(Alias::new("id")).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("coordinates"))
+                .drop_column(Alias::new("season"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Normalized occasions and styles are kept; of several seasons only
+        // the first is restored
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("coordinates"))
+                .add_column(ColumnDef::new(Alias::new("season")).string())
+                .to_owned(),
+        )
+        .await?;
+
+        let db = m.get_connection();
+        let backend = db.get_database_backend();
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Alias::new("id"), Alias::new("seasons")])
+                        .from(Alias::new("coordinates")),
+                ),
+            )
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let seasons: JsonValue = row.try_get("", "seasons")?;
+            let season = seasons
+                .as_array()
+                .and_then(|seasons| seasons.first())
+                .and_then(JsonValue::as_str)
+                .map(ToString::to_string);
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("coordinates"))
+                    .value(Alias::new("season"), season)
+                    .and_where(Expr::col(Alias::new("id")).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        let rows = db
+            .query_all(
+                backend.build(
+                    Query::select()
+                        .columns([Alias::new("id"), Alias::new("snapshot")])
+                        .from(Alias::new("coordinate_versions")),
+                ),
+            )
+            .await?;
+        for row in rows {
+            let id: i32 = row.try_get("", "id")?;
+            let mut snapshot: JsonValue = row.try_get("", "snapshot")?;
+            let Some(fields) = snapshot.as_object_mut() else {
+                continue;
+            };
+            let season = fields
+                .remove("seasons")
+                .and_then(|seasons| seasons.as_array()?.first().cloned())
+                .unwrap_or(JsonValue::Null);
+            fields.insert("season".to_string(), season);
+            m.exec_stmt(
+                Query::update()
+                    .table(Alias::new("coordinate_versions"))
+                    .value(Alias::new("snapshot"), snapshot)
+                    .and_where(Expr::col(Alias::new("id")).eq(id))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("coordinates"))
+                .drop_column(Alias::new("seasons"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Garments that cannot be packed right now
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(
+                    ColumnDef::new(Alias::new("in_wash"))
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .add_column(ColumnDef::new(Alias::new("lent_to")).string())
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_table(
+            Table::create()
+                .table(Alias::new("trips"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("pid"))
+                        .uuid()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                .col(ColumnDef::new(Alias::new("destination")).string())
+                // `latitude,longitude` of the destination, for its seasons
+                .col(ColumnDef::new(Alias::new("location")).string())
+                .col(ColumnDef::new(Alias::new("starts_on")).date().not_null())
+                .col(ColumnDef::new(Alias::new("ends_on")).date().not_null())
+                // Planned activities: [{"on": date or null, "occasion": key, "note": text}]
+                .col(
+                    ColumnDef::new(Alias::new("activities"))
+                        .json()
+                        .not_null()
+                        .default(Expr::cust("'[]'")),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_trips_user_id")
+                        .from(Alias::new("trips"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // The days of a trip are outfit plans, so they show in the calendar
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("outfit_plans"))
+                .add_column(ColumnDef::new(Alias::new("trip_id")).integer())
+                .to_owned(),
+        )
+        .await?;
+        m.create_foreign_key(
+            ForeignKey::create()
+                .name("fk_outfit_plans_trip_id")
+                .from(Alias::new("outfit_plans"), Alias::new("trip_id"))
+                .to(Alias::new("trips"), Alias::new("id"))
+                .on_delete(ForeignKeyAction::Cascade)
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_outfit_plans_trip_id")
+                .table(Alias::new("outfit_plans"))
+                .col(Alias::new("trip_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        // Packed state of the garments on a trip's packing list
+        m.create_table(
+            Table::create()
+                .table(Alias::new("trip_packing_items"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Alias::new("trip_id")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("clothes_id")).integer().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("packed"))
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_trip_packing_items_trip_id")
+                        .from(Alias::new("trip_packing_items"), Alias::new("trip_id"))
+                        .to(Alias::new("trips"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_trip_packing_items_clothes_id")
+                        .from(Alias::new("trip_packing_items"), Alias::new("clothes_id"))
+                        .to(Alias::new("clothes"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_trip_packing_items_unique")
+                .table(Alias::new("trip_packing_items"))
+                .col(Alias::new("trip_id"))
+                .col(Alias::new("clothes_id"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(
+            Table::drop()
+                .table(Alias::new("trip_packing_items"))
+                .to_owned(),
+        )
+        .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("outfit_plans"))
+                .drop_foreign_key(Alias::new("fk_outfit_plans_trip_id"))
+                .drop_column(Alias::new("trip_id"))
+                .to_owned(),
+        )
+        .await?;
+        m.drop_table(Table::drop().table(Alias::new("trips")).to_owned())
+            .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .drop_column(Alias::new("in_wash"))
+                .drop_column(Alias::new("lent_to"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Keep the oldest link of every duplicated clothes-coordinate pair
+        m.get_connection()
+            .execute_unprepared(
+                "DELETE FROM clothes_coordinates a USING clothes_coordinates b \
+                 WHERE a.clothes_id = b.clothes_id \
+                 AND a.coordinate_id = b.coordinate_id AND a.id > b.id",
+            )
+            .await?;
+
+        // Databases created before the pair was unique get the constraint now
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_coordinates_unique")
+                .table(Alias::new("clothes_coordinates"))
+                .col(Alias::new("clothes_id"))
+                .col(Alias::new("coordinate_id"))
+                .unique()
+                .if_not_exists()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _m: &SchemaManager) -> Result<(), DbErr> {
+        // The index belongs to the table's own migration
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Placeholders for garments deleted while they were part of an outfit
+        m.create_table(
+            Table::create()
+                .table(Alias::new("coordinate_tombstones"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("coordinate_id"))
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Alias::new("clothes_pid")).uuid().not_null())
+                .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                .col(ColumnDef::new(Alias::new("category")).string().not_null())
+                .col(ColumnDef::new(Alias::new("slot")).string())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_coordinate_tombstones_coordinate_id")
+                        .from(
+                            Alias::new("coordinate_tombstones"),
+                            Alias::new("coordinate_id"),
+                        )
+                        .to(Alias::new("coordinates"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_coordinate_tombstones_coordinate_id")
+                .table(Alias::new("coordinate_tombstones"))
+                .col(Alias::new("coordinate_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(
+            Table::drop()
+                .table(Alias::new("coordinate_tombstones"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const TABLES: [&str; 2] = ["clothes", "coordinates"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Deleted rows stay in the trash until they are purged
+        for table in TABLES {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new(table))
+                    .add_column(ColumnDef::new(Alias::new("deleted_at")).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+            m.create_index(
+                Index::create()
+                    .name(format!("idx_{table}_deleted_at"))
+                    .table(Alias::new(table))
+                    .col(Alias::new("deleted_at"))
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new(table))
+                    .drop_column(Alias::new("deleted_at"))
+                    .to_owned(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
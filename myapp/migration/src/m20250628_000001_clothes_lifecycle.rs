@@ -0,0 +1,149 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(
+                    ColumnDef::new(Alias::new("lifecycle_state"))
+                        .string()
+                        .not_null()
+                        .default("active"),
+                )
+                .add_column(ColumnDef::new(Alias::new("condition")).string())
+                .add_column(
+                    ColumnDef::new(Alias::new("state_changed_at")).timestamp_with_time_zone(),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // The wash flag and lending become lifecycle states; `lent_to` keeps
+        // who has a lent item
+        let db = m.get_connection();
+        db.execute_unprepared(
+            "UPDATE clothes SET lifecycle_state = 'lent', state_changed_at = updated_at \
+             WHERE lent_to IS NOT NULL",
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE clothes SET lifecycle_state = 'in_laundry', state_changed_at = updated_at \
+             WHERE in_wash AND lent_to IS NULL",
+        )
+        .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .drop_column(Alias::new("in_wash"))
+                .to_owned(),
+        )
+        .await?;
+
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_lifecycle_state")
+                .table(Alias::new("clothes"))
+                .col(Alias::new("lifecycle_state"))
+                .to_owned(),
+        )
+        .await?;
+
+        // Every transition, with the condition the garment was in
+        m.create_table(
+            Table::create()
+                .table(Alias::new("clothes_state_changes"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("clothes_id"))
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Alias::new("from_state")).string().not_null())
+                .col(ColumnDef::new(Alias::new("to_state")).string().not_null())
+                .col(ColumnDef::new(Alias::new("condition")).string())
+                .col(ColumnDef::new(Alias::new("note")).string())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_clothes_state_changes_clothes_id")
+                        .from(
+                            Alias::new("clothes_state_changes"),
+                            Alias::new("clothes_id"),
+                        )
+                        .to(Alias::new("clothes"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_state_changes_clothes_id")
+                .table(Alias::new("clothes_state_changes"))
+                .col(Alias::new("clothes_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(
+            Table::drop()
+                .table(Alias::new("clothes_state_changes"))
+                .to_owned(),
+        )
+        .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(
+                    ColumnDef::new(Alias::new("in_wash"))
+                        .boolean()
+                        .not_null()
+                        .default(false),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.get_connection()
+            .execute_unprepared(
+                "UPDATE clothes SET in_wash = lifecycle_state IN ('in_laundry', 'at_cleaner')",
+            )
+            .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .drop_column(Alias::new("lifecycle_state"))
+                .drop_column(Alias::new("condition"))
+                .drop_column(Alias::new("state_changed_at"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Append-only ledger of stock changes, `quantity` is signed and
+        // `balance` is the stock after the movement
+        m.create_table(
+            Table::create()
+                .table(Alias::new("inventory_movements"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("clothes_id"))
+                        .integer()
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Alias::new("reason")).string().not_null())
+                .col(ColumnDef::new(Alias::new("quantity")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("balance")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("note")).string())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_inventory_movements_clothes_id")
+                        .from(Alias::new("inventory_movements"), Alias::new("clothes_id"))
+                        .to(Alias::new("clothes"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_inventory_movements_clothes_id")
+                .table(Alias::new("inventory_movements"))
+                .col(Alias::new("clothes_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        // Open the ledger with the current stock, negative quantities are
+        // clamped to zero, and make `in_stock` agree with it
+        let db = m.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO inventory_movements (clothes_id, reason, quantity, balance, note) \
+             SELECT id, 'adjustment', stock_quantity, stock_quantity, 'opening balance' \
+             FROM clothes WHERE stock_quantity > 0",
+        )
+        .await?;
+        db.execute_unprepared(
+            "UPDATE clothes SET stock_quantity = GREATEST(stock_quantity, 0), \
+             in_stock = stock_quantity > 0",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(
+            Table::drop()
+                .table(Alias::new("inventory_movements"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
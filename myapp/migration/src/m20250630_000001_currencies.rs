@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Existing prices were entered without a currency
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(
+                    ColumnDef::new(Alias::new("currency"))
+                        .string_len(3)
+                        .not_null()
+                        .default("USD"),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("users"))
+                .add_column(
+                    ColumnDef::new(Alias::new("home_currency"))
+                        .string_len(3)
+                        .not_null()
+                        .default("USD"),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // One unit of `base` is worth `rate` units of `quote`
+        m.create_table(
+            Table::create()
+                .table(Alias::new("exchange_rates"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(ColumnDef::new(Alias::new("base")).string_len(3).not_null())
+                .col(ColumnDef::new(Alias::new("quote")).string_len(3).not_null())
+                .col(ColumnDef::new(Alias::new("rate")).decimal().not_null())
+                .col(ColumnDef::new(Alias::new("source")).string().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_exchange_rates_pair")
+                .table(Alias::new("exchange_rates"))
+                .col(Alias::new("base"))
+                .col(Alias::new("quote"))
+                .unique()
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(Table::drop().table(Alias::new("exchange_rates")).to_owned())
+            .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("users"))
+                .drop_column(Alias::new("home_currency"))
+                .to_owned(),
+        )
+        .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .drop_column(Alias::new("currency"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
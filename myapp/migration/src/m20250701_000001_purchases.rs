@@ -0,0 +1,157 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .add_column(ColumnDef::new(Alias::new("purchase_date")).date())
+                .add_column(ColumnDef::new(Alias::new("store")).string())
+                .add_column(ColumnDef::new(Alias::new("order_number")).string())
+                // Storage path and content type of the uploaded receipt
+                .add_column(ColumnDef::new(Alias::new("receipt_path")).string())
+                .add_column(ColumnDef::new(Alias::new("receipt_content_type")).string())
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_clothes_purchase_date")
+                .table(Alias::new("clothes"))
+                .col(Alias::new("purchase_date"))
+                .to_owned(),
+        )
+        .await?;
+
+        // Monthly spending limits, for all purchases or a single category.
+        // `alerted_for` is the month an overrun was last reported for.
+        m.create_table(
+            Table::create()
+                .table(Alias::new("budgets"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("pid"))
+                        .uuid()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("category")).string())
+                .col(ColumnDef::new(Alias::new("amount")).decimal().not_null())
+                .col(
+                    ColumnDef::new(Alias::new("currency"))
+                        .string_len(3)
+                        .not_null(),
+                )
+                .col(ColumnDef::new(Alias::new("alerted_for")).date())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_budgets_user_id")
+                        .from(Alias::new("budgets"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+
+        // In-app notifications, unread until `read_at` is set
+        m.create_table(
+            Table::create()
+                .table(Alias::new("notifications"))
+                .if_not_exists()
+                .col(
+                    ColumnDef::new(Alias::new("id"))
+                        .integer()
+                        .not_null()
+                        .auto_increment()
+                        .primary_key(),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("pid"))
+                        .uuid()
+                        .not_null()
+                        .unique_key(),
+                )
+                .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
+                .col(ColumnDef::new(Alias::new("kind")).string().not_null())
+                .col(ColumnDef::new(Alias::new("title")).string().not_null())
+                .col(ColumnDef::new(Alias::new("body")).text().not_null())
+                .col(ColumnDef::new(Alias::new("data")).json())
+                .col(ColumnDef::new(Alias::new("read_at")).timestamp_with_time_zone())
+                .col(
+                    ColumnDef::new(Alias::new("created_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .col(
+                    ColumnDef::new(Alias::new("updated_at"))
+                        .timestamp_with_time_zone()
+                        .not_null()
+                        .default(Expr::current_timestamp()),
+                )
+                .foreign_key(
+                    ForeignKey::create()
+                        .name("fk_notifications_user_id")
+                        .from(Alias::new("notifications"), Alias::new("user_id"))
+                        .to(Alias::new("users"), Alias::new("id"))
+                        .on_delete(ForeignKeyAction::Cascade),
+                )
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_notifications_user_id")
+                .table(Alias::new("notifications"))
+                .col(Alias::new("user_id"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_table(Table::drop().table(Alias::new("notifications")).to_owned())
+            .await?;
+        m.drop_table(Table::drop().table(Alias::new("budgets")).to_owned())
+            .await?;
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("clothes"))
+                .drop_column(Alias::new("purchase_date"))
+                .drop_column(Alias::new("store"))
+                .drop_column(Alias::new("order_number"))
+                .drop_column(Alias::new("receipt_path"))
+                .drop_column(Alias::new("receipt_content_type"))
+                .to_owned(),
+        )
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        // Tombstones keep the whole link so restoring a garment brings back
+        // its place in every outfit, not only the "item removed" ones
+        m.alter_table(
+            Table::alter()
+                .table(Alias::new("coordinate_tombstones"))
+                .add_column(
+                    ColumnDef::new(Alias::new("placeholder"))
+                        .boolean()
+                        .not_null()
+                        .default(true),
+                )
+                .add_column(ColumnDef::new(Alias::new("notes")).string())
+                .add_column(ColumnDef::new(Alias::new("x")).double())
+                .add_column(ColumnDef::new(Alias::new("y")).double())
+                .add_column(ColumnDef::new(Alias::new("scale")).double())
+                .add_column(ColumnDef::new(Alias::new("rotation")).double())
+                .add_column(ColumnDef::new(Alias::new("z_index")).integer())
+                .to_owned(),
+        )
+        .await?;
+        m.create_index(
+            Index::create()
+                .name("idx_coordinate_tombstones_clothes_pid")
+                .table(Alias::new("coordinate_tombstones"))
+                .col(Alias::new("clothes_pid"))
+                .to_owned(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, m: &SchemaManager) -> Result<(), DbErr> {
+        m.drop_index(
+            Index::drop()
+                .name("idx_coordinate_tombstones_clothes_pid")
+                .table(Alias::new("coordinate_tombstones"))
+                .to_owned(),
+        )
+        .await?;
+        // Links that were only kept for a restore have no placeholder to show
+        m.get_connection()
+            .execute_unprepared("DELETE FROM coordinate_tombstones WHERE NOT placeholder")
+            .await?;
+        for column in [
+            "placeholder",
+            "notes",
+            "x",
+            "y",
+            "scale",
+            "rotation",
+            "z_index",
+        ] {
+            m.alter_table(
+                Table::alter()
+                    .table(Alias::new("coordinate_tombstones"))
+                    .drop_column(Alias::new(column))
+                    .to_owned(),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
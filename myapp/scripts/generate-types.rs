@@ -38,10 +38,15 @@ export interface ClothesItem {
   size: string;
   color: string;
   material?: string;
-  price: number;
+  price: string;
+  currency: string;
   in_stock: boolean;
   stock_quantity: number;
   image_url?: string;
+  purchase_date?: string;
+  store?: string;
+  order_number?: string;
+  receipt_content_type?: string;
   created_at: string;
   updated_at: string;
 }
@@ -54,9 +59,13 @@ export interface CreateClothesRequest {
   size: string;
   color: string;
   material?: string;
-  price: number;
+  price: string;
+  currency?: string;
   stock_quantity: number;
   image_url?: string;
+  purchase_date?: string;
+  store?: string;
+  order_number?: string;
 }
 
 export interface UpdateClothesRequest {
@@ -67,9 +76,12 @@ export interface UpdateClothesRequest {
   size?: string;
   color?: string;
   material?: string;
-  price?: number;
-  stock_quantity?: number;
+  price?: string;
+  currency?: string;
   image_url?: string;
+  purchase_date?: string;
+  store?: string;
+  order_number?: string;
 }
 
 export interface Coordinate {
@@ -3,10 +3,12 @@ use loco_rs::{
     app::{AppContext, Hooks, Initializer},
     bgworker::{BackgroundWorker, Queue},
     boot::{create_app, BootResult, StartMode},
+    cache::{self, Cache},
     config::Config,
     controller::AppRoutes,
     db::{self, truncate_table},
     environment::Environment,
+    storage::{self, Storage},
     task::Tasks,
     Result,
 };
@@ -15,7 +17,15 @@ use std::path::Path;
 
 #[allow(unused_imports)]
 use crate::{
-    controllers, initializers, models::_entities::users, tasks, workers::downloader::DownloadWorker,
+    common::settings::Settings,
+    controllers, initializers,
+    models::_entities::users,
+    tasks,
+    workers::{
+        budget_alert::BudgetAlertWorker, clothes_import::ClothesImportWorker,
+        downloader::DownloadWorker, outfit_image::OutfitImageWorker,
+        wardrobe_export::WardrobeExportWorker,
+    },
 };
 
 pub struct App;
@@ -50,21 +60,59 @@ impl Hooks for App {
         ])
     }
 
+    async fn after_context(ctx: AppContext) -> Result<AppContext> {
+        // loco starts with the null storage driver, which fails every upload
+        // and download. Exports, receipts and outfit images need a real one:
+        // the directory in `settings.storage.path`, or memory without it.
+        let driver = match Settings::from_context(&ctx)?.storage.path {
+            Some(path) => {
+                std::fs::create_dir_all(&path)?;
+                storage::drivers::local::new_with_prefix(path)?
+            }
+            None => storage::drivers::mem::new(),
+        };
+        Ok(AppContext {
+            storage: Storage::single(driver).into(),
+            // Weather lookups are cached per location and hour
+            cache: Cache::new(cache::drivers::inmem::new()).into(),
+            ..ctx
+        })
+    }
+
     fn routes(_ctx: &AppContext) -> AppRoutes {
         AppRoutes::with_default_routes() // controller routes below
             .add_route(controllers::auth::routes())
+            .add_route(controllers::calendar::routes())
+            .add_route(controllers::capsules::routes())
             .add_route(controllers::clothes::routes())
             .add_route(controllers::coordinates::routes())
+            .add_route(controllers::currencies::routes())
+            .add_route(controllers::exports::routes())
+            .add_route(controllers::feedback::routes())
             .add_route(controllers::forms::routes())
+            .add_route(controllers::notifications::routes())
+            .add_route(controllers::spending::routes())
+            .add_route(controllers::trash::routes())
+            .add_route(controllers::trips::routes())
+            .add_route(controllers::vocabularies::routes())
+            .add_route(controllers::wears::routes())
     }
 
     async fn connect_workers(ctx: &AppContext, queue: &Queue) -> Result<()> {
         queue.register(DownloadWorker::build(ctx)).await?;
+        queue.register(ClothesImportWorker::build(ctx)).await?;
+        queue.register(WardrobeExportWorker::build(ctx)).await?;
+        queue.register(OutfitImageWorker::build(ctx)).await?;
+        queue.register(BudgetAlertWorker::build(ctx)).await?;
         Ok(())
     }
 
-    #[allow(unused_variables)]
     fn register_tasks(tasks: &mut Tasks) {
+        tasks.register(tasks::normalize_sizes::NormalizeSizes);
+        tasks.register(tasks::import_clothes::ImportClothes);
+        tasks.register(tasks::purge_trash::PurgeTrash);
+        tasks.register(tasks::exchange_rates::ExchangeRates);
+        tasks.register(tasks::budget_alerts::BudgetAlerts);
         // tasks-inject (do not remove)
     }
     async fn truncate(ctx: &AppContext) -> Result<()> {
@@ -0,0 +1,76 @@
+//! Translated labels from the Fluent files in `assets/i18n`.
+//!
+//! The files are loaded once, on first use. Messages missing from a
+//! language fall back to English, and to the message ID when English lacks
+//! them as well.
+
+use std::sync::OnceLock;
+
+use fluent_templates::{ArcLoader, Loader};
+use unic_langid::LanguageIdentifier;
+
+pub const I18N_DIR: &str = "assets/i18n";
+pub const I18N_SHARED: &str = "assets/i18n/shared.ftl";
+
+/// Language used when none is requested or the requested one is unknown
+pub const DEFAULT_LANGUAGE: LanguageIdentifier = unic_langid::langid!("en-US");
+
+pub struct Labels {
+    loader: ArcLoader,
+}
+
+impl Labels {
+    /// Load every language under `dir`
+    ///
+    /// # Errors
+    ///
+    /// When a Fluent file cannot be read or parsed
+    pub fn load(dir: &str, shared: &str) -> Result<Self, String> {
+        let loader = ArcLoader::builder(dir, DEFAULT_LANGUAGE)
+            .shared_resources(Some(&[shared.into()]))
+            .customize(|bundle| bundle.set_use_isolating(false))
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { loader })
+    }
+
+    /// Labels of the app's assets, loaded on first use
+    ///
+    /// # Errors
+    ///
+    /// When the Fluent files cannot be loaded
+    pub fn get() -> Result<&'static Self, String> {
+        static LABELS: OnceLock<Result<Labels, String>> = OnceLock::new();
+        LABELS
+            .get_or_init(|| Self::load(I18N_DIR, I18N_SHARED))
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    /// Loaded language best matching `requested`, such as `de` for `de-DE`
+    #[must_use]
+    pub fn language(&self, requested: Option<&str>) -> LanguageIdentifier {
+        let Some(requested) = requested.and_then(|value| value.parse::<LanguageIdentifier>().ok())
+        else {
+            return DEFAULT_LANGUAGE;
+        };
+        let loaded: Vec<&LanguageIdentifier> = self.loader.locales().collect();
+        loaded
+            .iter()
+            .find(|lang| **lang == &requested)
+            .or_else(|| {
+                loaded
+                    .iter()
+                    .find(|lang| lang.language == requested.language)
+            })
+            .map_or(DEFAULT_LANGUAGE, |lang| (*lang).clone())
+    }
+
+    /// Label of message `id` in `lang`
+    #[must_use]
+    pub fn label(&self, lang: &LanguageIdentifier, id: &str) -> String {
+        self.loader
+            .lookup(lang, id)
+            .unwrap_or_else(|| id.to_string())
+    }
+}
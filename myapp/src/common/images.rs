@@ -0,0 +1,120 @@
+//! Reading garment images for exports and outfit renders.
+//!
+//! `image_url` is supplied by clients, so it is never fetched blindly.
+//! Paths are read from the app storage, but only under [`STORAGE_PREFIX`]
+//! where rendered outfit images are written, so exports and receipts stay
+//! out of reach. `http(s)` URLs are only fetched from
+//! the hosts in `settings.images.allowed_hosts`, without following
+//! redirects, within a timeout and up to a maximum size. With no allowed
+//! hosts, remote images are not fetched at all.
+
+use std::{path::Path, time::Duration};
+
+use loco_rs::storage::Storage;
+use reqwest::{Url, redirect};
+
+use super::settings::ImageSettings;
+
+/// The storage prefix images may be read from
+pub const STORAGE_PREFIX: &str = "coordinates/";
+
+/// Reads images from the app storage and from allowed hosts
+#[derive(Debug, Clone)]
+pub struct ImageFetcher {
+    client: reqwest::Client,
+    allowed_hosts: Vec<String>,
+    max_bytes: usize,
+}
+
+impl ImageFetcher {
+    #[must_use]
+    pub fn new(settings: &ImageSettings) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(settings.timeout_secs))
+            .redirect(redirect::Policy::none())
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            allowed_hosts: settings
+                .allowed_hosts
+                .iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .collect(),
+            max_bytes: settings.max_bytes,
+        }
+    }
+
+    /// The URL of a remote image when it may be fetched
+    ///
+    /// # Errors
+    ///
+    /// Why the URL is refused
+    pub fn allowed_url(&self, source: &str) -> Result<Url, String> {
+        let url = Url::parse(source).map_err(|e| format!("invalid image URL: {e}"))?;
+        let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+        if !matches!(url.scheme(), "http" | "https") || !self.allowed_hosts.contains(&host) {
+            return Err(format!("images from '{host}' are not allowed"));
+        }
+        Ok(url)
+    }
+
+    /// The storage key of a stored image when it may be read
+    ///
+    /// # Errors
+    ///
+    /// Why the path is refused
+    pub fn allowed_storage_key(source: &str) -> Result<&str, String> {
+        let key = source.trim().trim_start_matches('/');
+        if !key.starts_with(STORAGE_PREFIX)
+            || key.contains('\\')
+            || key.split('/').any(|segment| segment == "..")
+        {
+            return Err(format!(
+                "images can only be read from '{STORAGE_PREFIX}' in storage"
+            ));
+        }
+        Ok(key)
+    }
+
+    /// Read one image: `http(s)` URLs from an allowed host, anything else
+    /// from [`STORAGE_PREFIX`] in the app storage
+    ///
+    /// # Errors
+    ///
+    /// The reason the image could not be read
+    pub async fn fetch(&self, source: &str, storage: &Storage) -> Result<Vec<u8>, String> {
+        let source = source.trim();
+        if !(source.starts_with("http://") || source.starts_with("https://")) {
+            let key = Self::allowed_storage_key(source)?;
+            return storage
+                .download::<Vec<u8>>(Path::new(key))
+                .await
+                .map_err(|e| e.to_string());
+        }
+
+        let url = self.allowed_url(source)?;
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| e.to_string())?;
+        let too_large = || format!("image is larger than {} bytes", self.max_bytes);
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.max_bytes as u64)
+        {
+            return Err(too_large());
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            if data.len() + chunk.len() > self.max_bytes {
+                return Err(too_large());
+            }
+            data.extend_from_slice(&chunk);
+        }
+        Ok(data)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod i18n;
+pub mod images;
+pub mod settings;
+pub mod weather;
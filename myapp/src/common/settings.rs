@@ -0,0 +1,189 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{clothes::DeletePolicy, outfit_images::OutfitImageFormat};
+
+/// Application specific settings, read from the `settings:` section of the
+/// config file. Every section is optional and falls back to its defaults.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct Settings {
+    #[serde(default)]
+    pub app: AppSettings,
+    #[serde(default)]
+    pub import: ImportSettings,
+    #[serde(default)]
+    pub export: ExportSettings,
+    #[serde(default)]
+    pub storage: StorageSettings,
+    #[serde(default)]
+    pub images: ImageSettings,
+    #[serde(default)]
+    pub weather: WeatherSettings,
+    #[serde(default)]
+    pub outfit_image: OutfitImageSettings,
+    #[serde(default)]
+    pub clothes: ClothesSettings,
+    #[serde(default)]
+    pub trash: TrashSettings,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AppSettings {
+    /// Base URL of the frontend, used for links back to the app such as
+    /// calendar events
+    pub url: String,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:3000".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ImportSettings {
+    /// Imports with more rows than this run in a background worker
+    pub background_threshold: usize,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            background_threshold: 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportSettings {
+    /// Exports with more clothes and coordinates than this run in a
+    /// background worker and are delivered by email
+    pub background_threshold: usize,
+    /// How long an emailed download link stays valid
+    pub link_ttl_hours: i64,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            background_threshold: 200,
+            link_ttl_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct StorageSettings {
+    /// Directory for stored files (exports, images). Files are kept in memory
+    /// when unset.
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImageSettings {
+    /// Hosts remote garment images may be fetched from for exports and
+    /// outfit images. Remote images are skipped when empty.
+    pub allowed_hosts: Vec<String>,
+    pub timeout_secs: u64,
+    /// Larger images are skipped
+    pub max_bytes: usize,
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            timeout_secs: 10,
+            max_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherProviderKind {
+    /// Deterministic weather from a local fixture file
+    #[default]
+    Fixture,
+    /// Forecasts from the Open-Meteo API
+    OpenMeteo,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct WeatherSettings {
+    #[serde(default)]
+    pub provider: WeatherProviderKind,
+    /// JSON fixture file for the fixture provider. A mild, dry default is
+    /// used when unset.
+    pub fixture_path: Option<String>,
+    /// Overrides the API endpoint of the selected provider
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OutfitImageSettings {
+    pub format: OutfitImageFormat,
+    pub width: u32,
+    pub height: u32,
+    /// Hex color behind the garments
+    pub background: String,
+}
+
+impl Default for OutfitImageSettings {
+    fn default() -> Self {
+        Self {
+            format: OutfitImageFormat::default(),
+            width: 1024,
+            height: 1024,
+            background: "#FFFFFF".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct ClothesSettings {
+    /// What deleting a garment does to the coordinates that include it,
+    /// unless the request picks a policy
+    #[serde(default)]
+    pub delete_policy: DeletePolicy,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrashSettings {
+    /// Days deleted clothes and coordinates stay restorable before the
+    /// `purge_trash` task deletes them for good
+    pub retention_days: i64,
+}
+
+impl Default for TrashSettings {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
+
+impl Settings {
+    /// Parse settings from the raw config value
+    ///
+    /// # Errors
+    ///
+    /// When the settings section does not match the expected structure
+    pub fn from_json(value: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(value.clone())?)
+    }
+
+    /// Read settings from the application context, using defaults when the
+    /// config file has no `settings:` section
+    ///
+    /// # Errors
+    ///
+    /// When the settings section does not match the expected structure
+    pub fn from_context(ctx: &AppContext) -> Result<Self> {
+        ctx.config
+            .settings
+            .as_ref()
+            .map_or_else(|| Ok(Self::default()), Self::from_json)
+    }
+}
@@ -0,0 +1,315 @@
+//! Weather lookups for weather-aware suggestions.
+//!
+//! The provider is selected by the `settings.weather` config section. The
+//! fixture provider returns deterministic weather from a local JSON file (or
+//! a mild, dry default) and is meant for development and tests; the
+//! Open-Meteo provider queries the public forecast API. Lookups are cached in
+//! the app cache per location and hour.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Timelike, Utc};
+use loco_rs::{cache::Cache, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::settings::{WeatherProviderKind, WeatherSettings};
+
+/// How long a cached lookup is kept. Keys already include the hour.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A location given as `latitude,longitude` in decimal degrees
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Location {
+    /// Parse `latitude,longitude`
+    ///
+    /// # Errors
+    ///
+    /// When the value is not two coordinates in range
+    pub fn parse(value: &str) -> Result<Self> {
+        let invalid = || Error::BadRequest(format!("invalid location: {value}"));
+        let (latitude, longitude) = value.split_once(',').ok_or_else(invalid)?;
+        let latitude: f64 = latitude.trim().parse().map_err(|_| invalid())?;
+        let longitude: f64 = longitude.trim().parse().map_err(|_| invalid())?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+            return Err(invalid());
+        }
+        Ok(Self {
+            latitude,
+            longitude,
+        })
+    }
+
+    /// Location rounded to two decimals (about 1 km), used for caching and
+    /// fixture lookups
+    #[must_use]
+    pub fn key(&self) -> String {
+        format!("{:.2},{:.2}", self.latitude, self.longitude)
+    }
+}
+
+/// Weather at a location for one hour
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Weather {
+    pub temperature_c: f64,
+    pub precipitation_mm: f64,
+    /// Chance of precipitation in percent, when the provider forecasts it
+    pub precipitation_probability: Option<u8>,
+}
+
+impl Weather {
+    /// Below this temperature light garments such as linen are left out
+    pub const COLD_BELOW_C: f64 = 10.0;
+    /// From this temperature warm garments such as wool are left out
+    pub const HOT_FROM_C: f64 = 25.0;
+    /// Below this temperature outfits include outerwear by default
+    pub const OUTERWEAR_BELOW_C: f64 = 15.0;
+
+    #[must_use]
+    pub fn is_cold(&self) -> bool {
+        self.temperature_c < Self::COLD_BELOW_C
+    }
+
+    #[must_use]
+    pub fn is_hot(&self) -> bool {
+        self.temperature_c >= Self::HOT_FROM_C
+    }
+
+    #[must_use]
+    pub fn is_rainy(&self) -> bool {
+        self.precipitation_mm >= 0.5 || self.precipitation_probability.is_some_and(|p| p >= 50)
+    }
+
+    #[must_use]
+    pub fn needs_outerwear(&self) -> bool {
+        self.temperature_c < Self::OUTERWEAR_BELOW_C
+    }
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            temperature_c: 20.0,
+            precipitation_mm: 0.0,
+            precipitation_probability: None,
+        }
+    }
+}
+
+/// Source of weather forecasts
+#[async_trait]
+pub trait WeatherProvider: Send + Sync {
+    /// Provider name, part of the cache key
+    fn name(&self) -> &'static str;
+
+    /// Weather at `location` during the hour starting at `at` (UTC)
+    async fn weather(&self, location: Location, at: NaiveDateTime) -> Result<Weather>;
+}
+
+/// One fixture entry. Entries without a date apply to every date.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixtureEntry {
+    pub location: String,
+    pub date: Option<NaiveDate>,
+    #[serde(flatten)]
+    pub weather: Weather,
+}
+
+/// Deterministic weather from fixture entries, falling back to a default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FixtureProvider {
+    #[serde(default)]
+    pub default: Weather,
+    #[serde(default)]
+    pub entries: Vec<FixtureEntry>,
+}
+
+impl FixtureProvider {
+    /// Load fixtures from a JSON file
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be read or parsed
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    #[must_use]
+    pub fn lookup(&self, location: Location, date: NaiveDate) -> Weather {
+        let key = location.key();
+        let matching = |entry: &&FixtureEntry| {
+            Location::parse(&entry.location).is_ok_and(|loc| loc.key() == key)
+        };
+        self.entries
+            .iter()
+            .filter(matching)
+            .find(|entry| entry.date == Some(date))
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .filter(matching)
+                    .find(|entry| entry.date.is_none())
+            })
+            .map_or(self.default, |entry| entry.weather)
+    }
+}
+
+#[async_trait]
+impl WeatherProvider for FixtureProvider {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    async fn weather(&self, location: Location, at: NaiveDateTime) -> Result<Weather> {
+        Ok(self.lookup(location, at.date()))
+    }
+}
+
+/// Hourly forecasts from <https://open-meteo.com>
+#[derive(Debug, Clone)]
+pub struct OpenMeteoProvider {
+    pub base_url: String,
+    client: reqwest::Client,
+}
+
+impl OpenMeteoProvider {
+    pub const DEFAULT_URL: &'static str = "https://api.open-meteo.com/v1/forecast";
+
+    #[must_use]
+    pub fn new(base_url: Option<String>) -> Self {
+        Self {
+            base_url: base_url.unwrap_or_else(|| Self::DEFAULT_URL.to_string()),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    hourly: OpenMeteoHourly,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoHourly {
+    time: Vec<String>,
+    temperature_2m: Vec<Option<f64>>,
+    precipitation: Vec<Option<f64>>,
+    #[serde(default)]
+    precipitation_probability: Vec<Option<u8>>,
+}
+
+#[async_trait]
+impl WeatherProvider for OpenMeteoProvider {
+    fn name(&self) -> &'static str {
+        "open_meteo"
+    }
+
+    async fn weather(&self, location: Location, at: NaiveDateTime) -> Result<Weather> {
+        let date = at.date().format("%Y-%m-%d").to_string();
+        let body = self
+            .client
+            .get(&self.base_url)
+            .query(&[
+                ("latitude", location.latitude.to_string()),
+                ("longitude", location.longitude.to_string()),
+                (
+                    "hourly",
+                    "temperature_2m,precipitation,precipitation_probability".to_string(),
+                ),
+                ("timezone", "UTC".to_string()),
+                ("start_date", date.clone()),
+                ("end_date", date),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| Error::string(&format!("weather request failed: {e}")))?
+            .text()
+            .await
+            .map_err(|e| Error::string(&format!("weather request failed: {e}")))?;
+        let response: OpenMeteoResponse = serde_json::from_str(&body)?;
+
+        let hour = at.format("%Y-%m-%dT%H:00").to_string();
+        let index = response
+            .hourly
+            .time
+            .iter()
+            .position(|time| *time == hour)
+            .ok_or_else(|| Error::string(&format!("no forecast for {hour}")))?;
+        let value = |values: &[Option<f64>]| values.get(index).copied().flatten();
+        Ok(Weather {
+            temperature_c: value(&response.hourly.temperature_2m)
+                .ok_or_else(|| Error::string(&format!("no temperature for {hour}")))?,
+            precipitation_mm: value(&response.hourly.precipitation).unwrap_or(0.0),
+            precipitation_probability: response
+                .hourly
+                .precipitation_probability
+                .get(index)
+                .copied()
+                .flatten(),
+        })
+    }
+}
+
+/// Build the provider selected in the settings
+///
+/// # Errors
+///
+/// When the fixture file cannot be loaded
+pub fn provider(settings: &WeatherSettings) -> Result<Box<dyn WeatherProvider>> {
+    Ok(match settings.provider {
+        WeatherProviderKind::Fixture => Box::new(match &settings.fixture_path {
+            Some(path) => FixtureProvider::from_file(path)?,
+            None => FixtureProvider::default(),
+        }),
+        WeatherProviderKind::OpenMeteo => Box::new(OpenMeteoProvider::new(settings.url.clone())),
+    })
+}
+
+/// Hour a forecast is looked up for: the current hour for today, noon for
+/// other dates
+#[must_use]
+pub fn lookup_hour(date: Option<NaiveDate>, now: NaiveDateTime) -> NaiveDateTime {
+    match date {
+        Some(date) if date != now.date() => {
+            date.and_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default())
+        }
+        _ => now.date().and_hms_opt(now.hour(), 0, 0).unwrap_or(now),
+    }
+}
+
+/// Weather for `location` and `date`, cached per location and hour
+///
+/// # Errors
+///
+/// When the provider fails
+pub async fn cached_weather(
+    cache: &Cache,
+    provider: &dyn WeatherProvider,
+    location: Location,
+    date: Option<NaiveDate>,
+) -> Result<Weather> {
+    let at = lookup_hour(date, Utc::now().naive_utc());
+    let key = format!(
+        "weather:{}:{}:{}",
+        provider.name(),
+        location.key(),
+        at.format("%Y-%m-%dT%H")
+    );
+
+    let cached = cache.get(&key).await?;
+    if let Some(weather) = cached.and_then(|cached| serde_json::from_str(&cached).ok()) {
+        return Ok(weather);
+    }
+    let weather = provider.weather(location, at).await?;
+    cache
+        .insert_with_expiry(&key, &serde_json::to_string(&weather)?, CACHE_TTL)
+        .await?;
+    Ok(weather)
+}
@@ -0,0 +1,166 @@
+use crate::{
+    common::settings::Settings,
+    models::{
+        calendar_feeds::{self, OutfitCalendar},
+        outfit_plans::{self, CalendarQuery, CreatePlanParams, UpdatePlanParams, find_conflicts},
+    },
+    views::calendar::{CalendarResponse, FeedResponse, PlanDoneResponse, PlanResponse},
+};
+use axum::{body::Body, debug_handler, extract::Query, http::header};
+use loco_rs::prelude::*;
+use serde_json::json;
+
+async fn plan_response(ctx: &AppContext, plan: outfit_plans::Model) -> Result<Response> {
+    let warnings = plan.conflicts(&ctx.db).await?;
+    let plan = outfit_plans::Model::with_coordinates(&ctx.db, vec![plan])
+        .await?
+        .pop()
+        .ok_or_else(|| Error::NotFound)?
+        .without_unavailable();
+    format::json(PlanResponse { plan, warnings })
+}
+
+/// Plan a coordinate on a date
+#[debug_handler]
+async fn create(
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreatePlanParams>,
+) -> Result<Response> {
+    let plan = outfit_plans::Model::create(&ctx.db, &params).await?;
+    plan_response(&ctx, plan).await
+}
+
+/// Get the plans of a user for a week or month, with conflicts
+#[debug_handler]
+async fn list_by_user(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<CalendarQuery>,
+) -> Result<Response> {
+    let (from, to) = query
+        .range(chrono::Utc::now().date_naive())
+        .map_err(|e| Error::BadRequest(e.to_string()))?;
+    let plans = outfit_plans::Model::find_in_range(&ctx.db, user_id, from, to).await?;
+    let conflicts = find_conflicts(&plans);
+    format::json(CalendarResponse {
+        from,
+        to,
+        plans,
+        conflicts,
+    })
+}
+
+/// Get plan by PID
+#[debug_handler]
+async fn get_one(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let plan = outfit_plans::Model::find_by_pid(&ctx.db, &pid).await?;
+    plan_response(&ctx, plan).await
+}
+
+/// Reschedule or edit a plan
+#[debug_handler]
+async fn update(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<UpdatePlanParams>,
+) -> Result<Response> {
+    let plan = outfit_plans::Model::update_by_pid(&ctx.db, &pid, &params).await?;
+    plan_response(&ctx, plan).await
+}
+
+/// Delete plan by PID
+#[debug_handler]
+async fn delete_plan(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    outfit_plans::Model::delete_by_pid(&ctx.db, &pid).await?;
+    format::json(json!({"msg": "Plan deleted successfully"}))
+}
+
+/// Mark a planned day as done and log wear for its garments
+#[debug_handler]
+async fn mark_done(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let plan = outfit_plans::Model::find_by_pid(&ctx.db, &pid).await?;
+    let Some((plan, wear_events)) = plan.mark_done(&ctx.db).await? else {
+        return bad_request("plan is already done");
+    };
+    let plan = outfit_plans::Model::with_coordinates(&ctx.db, vec![plan])
+        .await?
+        .pop()
+        .ok_or_else(|| Error::NotFound)?;
+    format::json(PlanDoneResponse { plan, wear_events })
+}
+
+fn feed_response(ctx: &AppContext, feed: calendar_feeds::Model) -> FeedResponse {
+    let url = feed.url(&ctx.config.server.full_url());
+    FeedResponse { feed, url }
+}
+
+/// Create a calendar subscription feed for a user
+#[debug_handler]
+async fn create_feed(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let feed = calendar_feeds::Model::create(&ctx.db, user_id).await?;
+    format::json(feed_response(&ctx, feed))
+}
+
+/// List the active calendar subscription feeds of a user
+#[debug_handler]
+async fn list_feeds(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let feeds = calendar_feeds::Model::find_active_by_user(&ctx.db, user_id).await?;
+    format::json(
+        feeds
+            .into_iter()
+            .map(|feed| feed_response(&ctx, feed))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Revoke a calendar subscription feed
+#[debug_handler]
+async fn revoke_feed(
+    State(ctx): State<AppContext>,
+    Path((user_id, pid)): Path<(i32, String)>,
+) -> Result<Response> {
+    let feed = calendar_feeds::Model::find_by_pid(&ctx.db, &pid).await?;
+    if feed.user_id != user_id {
+        return not_found();
+    }
+    feed.revoke(&ctx.db).await?;
+    format::json(json!({"msg": "Feed revoked successfully"}))
+}
+
+/// iCalendar feed of planned outfits, authorized by the feed token
+#[debug_handler]
+async fn feed_ics(State(ctx): State<AppContext>, Path(token): Path<String>) -> Result<Response> {
+    let Ok(feed) = calendar_feeds::Model::find_active_by_token(&ctx.db, &token).await else {
+        return not_found();
+    };
+    let plans = feed.plans(&ctx.db).await?;
+    let settings = Settings::from_context(&ctx)?;
+    let body = OutfitCalendar {
+        app_url: &settings.app.url,
+        plans: &plans,
+    }
+    .render();
+    Ok(format::render()
+        .header(header::CONTENT_TYPE, "text/calendar; charset=utf-8")
+        .header(
+            header::CONTENT_DISPOSITION,
+            "inline; filename=\"outfits.ics\"",
+        )
+        .response()
+        .body(Body::from(body))?)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/calendar")
+        .add("/", post(create))
+        .add("/user/{user_id}", get(list_by_user))
+        .add("/user/{user_id}/feeds", post(create_feed))
+        .add("/user/{user_id}/feeds", get(list_feeds))
+        .add("/user/{user_id}/feeds/{pid}", delete(revoke_feed))
+        .add("/feeds/{token}/outfits.ics", get(feed_ics))
+        .add("/{pid}", get(get_one))
+        .add("/{pid}", put(update))
+        .add("/{pid}", delete(delete_plan))
+        .add("/{pid}/done", post(mark_done))
+}
@@ -0,0 +1,19 @@
+use crate::models::capsules::{self, CapsuleParams};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+
+/// Count the outfits a set of garments makes, find orphans and the garment
+/// that would add the most outfits
+#[debug_handler]
+async fn analyze(
+    State(ctx): State<AppContext>,
+    Json(params): Json<CapsuleParams>,
+) -> Result<Response> {
+    format::json(capsules::analyze_for_user(&ctx.db, &params).await?)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/capsules")
+        .add("/analysis", post(analyze))
+}
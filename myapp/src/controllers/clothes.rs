@@ -1,33 +1,87 @@
-use crate::models::{
-    clothes::{CreateClothesParams, UpdateClothesParams},
-    _entities::clothes,
+use super::coordinates::unprocessable;
+use crate::{
+    common::settings::Settings,
+    models::{
+        clothes::{
+            ColorQuery, CreateClothesParams, DeletePolicy, DeleteQuery, SizeQuery,
+            UpdateClothesParams,
+        },
+        clothes_imports::{self, ImportClothesParams},
+        clothes_state_changes::{self, StateChange, TransitionParams},
+        coordinate_rules::FieldError,
+        inventory_movements::{self, Movement, MovementParams},
+        purchases::{self, ReceiptType},
+        _entities::clothes,
+    },
+    views::clothes::{ClothesImportResponse, ClothesResponse},
+    workers::{
+        budget_alert::{BudgetAlertWorker, BudgetAlertWorkerArgs},
+        clothes_import::{ClothesImportWorker, ClothesImportWorkerArgs},
+        outfit_image::{OutfitImageWorker, OutfitImageWorkerArgs},
+    },
+};
+use axum::{
+    body::{Body, Bytes},
+    debug_handler,
+    extract::Query,
+    http::{HeaderMap, StatusCode, header},
 };
-use axum::debug_handler;
 use loco_rs::prelude::*;
 use serde_json::json;
 
+/// Queue a check of the budgets of the month a purchased item was bought in
+async fn queue_budget_alert(ctx: &AppContext, item: &clothes::Model) -> Result<()> {
+    if let Some(month) = item.purchase_date {
+        BudgetAlertWorker::perform_later(ctx, BudgetAlertWorkerArgs { month }).await?;
+    }
+    Ok(())
+}
+
 /// Create a new clothes item
 #[debug_handler]
 async fn create(
     State(ctx): State<AppContext>,
     Json(params): Json<CreateClothesParams>,
 ) -> Result<Response> {
+    if let Err(message) = params.money() {
+        return unprocessable(&[FieldError::new("price", message)]);
+    }
+    let problems = params.problems();
+    if !problems.is_empty() {
+        return unprocessable(&problems);
+    }
     let clothes = clothes::Model::create(&ctx.db, &params).await?;
-    format::json(clothes)
+    queue_budget_alert(&ctx, &clothes).await?;
+    format::json(ClothesResponse::new(clothes, None))
 }
 
-/// Get all clothes items
+/// Get all clothes items, optionally filtered by color and size
 #[debug_handler]
-async fn list(State(ctx): State<AppContext>) -> Result<Response> {
-    let clothes = clothes::Model::find_all(&ctx.db).await?;
-    format::json(clothes)
+async fn list(
+    State(ctx): State<AppContext>,
+    Query(color): Query<ColorQuery>,
+    Query(size): Query<SizeQuery>,
+) -> Result<Response> {
+    if let Err(err) = color.near_color() {
+        return bad_request(err.to_string());
+    }
+    let clothes = if color.family.is_none() && color.near.is_none() && size.size.is_none() {
+        clothes::Model::find_all(&ctx.db).await?
+    } else {
+        clothes::Model::find_by_color(&ctx.db, &color, &size).await?
+    };
+    format::json(ClothesResponse::list(clothes, size.size_system))
 }
 
 /// Get clothes item by PID
 #[debug_handler]
-async fn get_one(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+async fn get_one(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(size): Query<SizeQuery>,
+) -> Result<Response> {
     let clothes = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
-    format::json(clothes)
+    format::json(ClothesResponse::new(clothes, size.size_system))
 }
 
 /// Update clothes item by PID
@@ -37,15 +91,182 @@ async fn update(
     Path(pid): Path<String>,
     Json(params): Json<UpdateClothesParams>,
 ) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    if let Err(message) = params.money(&item) {
+        return unprocessable(&[FieldError::new("price", message)]);
+    }
+    let problems = params.problems();
+    if !problems.is_empty() {
+        return unprocessable(&problems);
+    }
     let clothes = clothes::Model::update_by_pid(&ctx.db, &pid, &params).await?;
-    format::json(clothes)
+    queue_budget_alert(&ctx, &clothes).await?;
+    format::json(ClothesResponse::new(clothes, None))
 }
 
-/// Delete clothes item by PID
+/// The policy picked by the request, or the configured one
+fn delete_policy(ctx: &AppContext, query: &DeleteQuery) -> Result<DeletePolicy> {
+    match query.policy {
+        Some(policy) => Ok(policy),
+        None => Ok(Settings::from_context(ctx)?.clothes.delete_policy),
+    }
+}
+
+/// Move clothes item to the trash by PID under the requested or configured
+/// delete policy. A blocked delete answers `409 Conflict` with the
+/// coordinates that include the item.
 #[debug_handler]
-async fn delete_clothes(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
-    clothes::Model::delete_by_pid(&ctx.db, &pid).await?;
-    format::json(json!({"msg": "Deleted successfully"}))
+async fn delete_clothes(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<Response> {
+    let policy = delete_policy(&ctx, &query)?;
+    let impact = clothes::Model::delete_by_pid(&ctx.db, &pid, policy).await?;
+    if impact.blocked {
+        return format::render().status(StatusCode::CONFLICT).json(impact);
+    }
+    for coordinate in &impact.coordinates {
+        OutfitImageWorker::perform_later(
+            &ctx,
+            OutfitImageWorkerArgs {
+                coordinate_pid: coordinate.pid.to_string(),
+                replace: false,
+            },
+        )
+        .await?;
+    }
+    format::json(json!({"msg": "Deleted successfully", "impact": impact}))
+}
+
+/// Preview what deleting a clothes item does to the coordinates that include
+/// it, without deleting anything
+#[debug_handler]
+async fn delete_preview(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(query): Query<DeleteQuery>,
+) -> Result<Response> {
+    let policy = delete_policy(&ctx, &query)?;
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(item.delete_impact(&ctx.db, policy).await?)
+}
+
+/// Move clothes item to another lifecycle state
+#[debug_handler]
+async fn transition(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<TransitionParams>,
+) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    let errors = params.problems(item.lifecycle());
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let (item, change) = clothes_state_changes::Model::transition(&ctx.db, &item, &params).await?;
+    format::json(json!({
+        "clothes": ClothesResponse::new(item, None),
+        "change": StateChange::from(&change),
+    }))
+}
+
+/// Get the lifecycle history of a clothes item
+#[debug_handler]
+async fn lifecycle_history(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(clothes_state_changes::Model::history(&ctx.db, &item).await?)
+}
+
+/// Record a stock movement for a clothes item
+#[debug_handler]
+async fn record_movement(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<MovementParams>,
+) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    let errors = params.problems(item.stock_quantity);
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let (item, movement) = inventory_movements::Model::record(&ctx.db, &item, &params).await?;
+    queue_budget_alert(&ctx, &item).await?;
+    format::json(json!({
+        "clothes": ClothesResponse::new(item, None),
+        "movement": Movement::from(&movement),
+    }))
+}
+
+/// Get the inventory history of a clothes item
+#[debug_handler]
+async fn inventory_history(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(inventory_movements::Model::history(&ctx.db, &item).await?)
+}
+
+/// Attach the receipt of a clothes item, sent as the raw request body with
+/// its `Content-Type`. A new receipt replaces the previous one.
+#[debug_handler]
+async fn upload_receipt(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    let kind = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(ReceiptType::from_content_type);
+    let Some(kind) = kind else {
+        return unprocessable(&[FieldError::new(
+            "receipt",
+            "must be a PDF, JPEG, PNG or WebP file",
+        )]);
+    };
+    if body.is_empty() {
+        return unprocessable(&[FieldError::new("receipt", "must not be empty")]);
+    }
+
+    let path = purchases::receipt_path(&item.pid, kind);
+    ctx.storage
+        .upload(std::path::Path::new(&path), &body)
+        .await?;
+    if let Some(previous) = item
+        .receipt_path
+        .as_deref()
+        .filter(|previous| *previous != path)
+    {
+        ctx.storage.delete(std::path::Path::new(previous)).await?;
+    }
+    let item = item
+        .attach_receipt(&ctx.db, &path, kind.content_type())
+        .await?;
+    format::json(ClothesResponse::new(item, None))
+}
+
+/// Download the receipt of a clothes item
+#[debug_handler]
+async fn get_receipt(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let item = clothes::Model::find_by_pid(&ctx.db, &pid).await?;
+    let Some(path) = item.receipt_path else {
+        return not_found();
+    };
+    let content_type = item
+        .receipt_content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    let data: Vec<u8> = ctx.storage.download(std::path::Path::new(&path)).await?;
+    Ok(format::render()
+        .header(header::CONTENT_TYPE, content_type)
+        .response()
+        .body(Body::from(data))?)
 }
 
 /// Get clothes by category
@@ -53,9 +274,69 @@ async fn delete_clothes(State(ctx): State<AppContext>, Path(pid): Path<String>)
 async fn get_by_category(
     State(ctx): State<AppContext>,
     Path(category): Path<String>,
+    Query(color): Query<ColorQuery>,
+    Query(size): Query<SizeQuery>,
+) -> Result<Response> {
+    if let Err(err) = color.near_color() {
+        return bad_request(err.to_string());
+    }
+    let clothes = clothes::Model::find_by_category(&ctx.db, &category, &color, &size).await?;
+    format::json(ClothesResponse::list(clothes, size.size_system))
+}
+
+/// Import clothes from CSV or JSON.
+///
+/// Every row is validated first; with `dry_run` only the per-row report is
+/// returned. Invalid uploads are rejected as a whole, small ones are imported
+/// in a single transaction and large ones are queued for a background worker
+/// whose progress can be polled. A JSON wardrobe export also restores its
+/// coordinates for `user_id`.
+#[debug_handler]
+async fn import(
+    State(ctx): State<AppContext>,
+    Json(params): Json<ImportClothesParams>,
 ) -> Result<Response> {
-    let clothes = clothes::Model::find_by_category(&ctx.db, &category).await?;
-    format::json(clothes)
+    let parsed = clothes_imports::parse(params.format, &params.data, &params.mapping)
+        .map_err(|e| Error::BadRequest(e.to_string()))?;
+
+    if params.dry_run {
+        return format::json(parsed.report(true, 0));
+    }
+    if !parsed.errors().is_empty() {
+        return format::render()
+            .status(StatusCode::UNPROCESSABLE_ENTITY)
+            .json(parsed.report(false, 0));
+    }
+
+    if !parsed.coordinates.is_empty() && params.user_id.is_none() {
+        return bad_request("user_id is required to import coordinates");
+    }
+
+    let settings = Settings::from_context(&ctx)?;
+    if parsed.rows.len() > settings.import.background_threshold {
+        let import =
+            clothes_imports::Model::create_queued(&ctx.db, &params, parsed.rows.len()).await?;
+        ClothesImportWorker::perform_later(
+            &ctx,
+            ClothesImportWorkerArgs {
+                import_pid: import.pid.to_string(),
+            },
+        )
+        .await?;
+        return format::render()
+            .status(StatusCode::ACCEPTED)
+            .json(ClothesImportResponse::new(&import));
+    }
+
+    let imported = clothes_imports::import(&ctx.db, &parsed, params.user_id).await?;
+    format::json(parsed.report(false, imported))
+}
+
+/// Get the progress of a background import
+#[debug_handler]
+async fn import_status(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let import = clothes_imports::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(ClothesImportResponse::new(&import))
 }
 
 pub fn routes() -> Routes {
@@ -63,8 +344,17 @@ pub fn routes() -> Routes {
         .prefix("/api/clothes")
         .add("/", post(create))
         .add("/", get(list))
+        .add("/import", post(import))
+        .add("/imports/{pid}", get(import_status))
         .add("/{pid}", get(get_one))
         .add("/{pid}", put(update))
         .add("/{pid}", delete(delete_clothes))
+        .add("/{pid}/delete-preview", get(delete_preview))
+        .add("/{pid}/lifecycle", get(lifecycle_history))
+        .add("/{pid}/lifecycle", post(transition))
+        .add("/{pid}/inventory", get(inventory_history))
+        .add("/{pid}/inventory", post(record_movement))
+        .add("/{pid}/receipt", get(get_receipt))
+        .add("/{pid}/receipt", put(upload_receipt))
         .add("/category/{category}", get(get_by_category))
 }
\ No newline at end of file
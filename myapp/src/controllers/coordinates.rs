@@ -1,32 +1,118 @@
-use crate::models::{
-    coordinates::{
-        AddClothesToCoordinateParams, ClothesPositionParams, CreateCoordinateParams,
-        UpdateCoordinateParams,
+use crate::{
+    common::{
+        settings::Settings,
+        weather::{self, Location},
     },
-    _entities::coordinates,
+    data::seasons::Season,
+    models::{
+        coordinates::{
+            AddClothesToCoordinateParams, ClothesLayoutParams, CloneCoordinateParams,
+            CoordinateFilter, CreateCoordinateParams, PageQuery,
+            ReorderClothesParams, UpdateCoordinateParams, clothes_coordinates,
+        },
+        _entities::coordinates,
+        coordinate_rules::{self, FieldError, SlotRules},
+        coordinate_versions::{self, DiffQuery},
+        outfit_images::{self, OutfitImageFormat},
+        suggestions::{self, HarmonyScorer, SuggestionQuery},
+    },
+    workers::outfit_image::{OutfitImageWorker, OutfitImageWorkerArgs},
+};
+use axum::{
+    body::Body,
+    debug_handler,
+    extract::Query,
+    http::{StatusCode, header},
 };
-use axum::debug_handler;
 use loco_rs::prelude::*;
 use serde_json::json;
 
+/// Respond with `422 Unprocessable Entity` and the field errors
+pub(crate) fn unprocessable(errors: &[FieldError]) -> Result<Response> {
+    format::render()
+        .status(StatusCode::UNPROCESSABLE_ENTITY)
+        .json(json!({ "errors": errors }))
+}
+
+/// Queue a re-render of the composite outfit image
+async fn queue_outfit_image(ctx: &AppContext, pid: &str, replace: bool) -> Result<()> {
+    OutfitImageWorker::perform_later(
+        ctx,
+        OutfitImageWorkerArgs {
+            coordinate_pid: pid.to_string(),
+            replace,
+        },
+    )
+    .await
+}
+
 /// Create a new coordinate with clothes
 #[debug_handler]
 async fn create(
     State(ctx): State<AppContext>,
     Json(params): Json<CreateCoordinateParams>,
 ) -> Result<Response> {
+    let errors = coordinates::Model::create_problems(&ctx.db, &params).await?;
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
     let coordinate = coordinates::Model::create_with_clothes(&ctx.db, &params).await?;
+    if !params.clothes_ids.is_empty() {
+        queue_outfit_image(&ctx, &coordinate.coordinate.pid.to_string(), false).await?;
+    }
     format::json(coordinate)
 }
 
-/// Get all coordinates for a user
+/// Suggest new coordinates built from the user's clothes, for the weather
+/// at `location` when given
+#[debug_handler]
+async fn get_suggestions(
+    State(ctx): State<AppContext>,
+    Query(query): Query<SuggestionQuery>,
+) -> Result<Response> {
+    query
+        .season()
+        .and_then(|_| query.slots(false))
+        .map_err(|e| Error::BadRequest(e.to_string()))?;
+
+    let weather = match query.location.as_deref() {
+        Some(location) => {
+            let location = Location::parse(location)?;
+            let provider = weather::provider(&Settings::from_context(&ctx)?.weather)?;
+            Some(
+                weather::cached_weather(&ctx.cache, provider.as_ref(), location, query.date)
+                    .await?,
+            )
+        }
+        None => None,
+    };
+    let suggestions =
+        suggestions::suggest_for_user(&ctx.db, &query, weather, &HarmonyScorer::default()).await?;
+    format::json(suggestions)
+}
+
+/// A page of a user's coordinates with their clothes
+async fn coordinate_page(
+    ctx: &AppContext,
+    user_id: i32,
+    filter: CoordinateFilter,
+    page: &PageQuery,
+) -> Result<Response> {
+    if let Err(e) = page.after() {
+        return bad_request(e.to_string());
+    }
+    let page = coordinates::Model::find_page_by_user(&ctx.db, user_id, filter, page).await?;
+    format::json(page)
+}
+
+/// Get the coordinates of a user, a page at a time
 #[debug_handler]
 async fn list_by_user(
     State(ctx): State<AppContext>,
     Path(user_id): Path<i32>,
+    Query(page): Query<PageQuery>,
 ) -> Result<Response> {
-    let coordinates = coordinates::Model::find_by_user_with_clothes(&ctx.db, user_id).await?;
-    format::json(coordinates)
+    coordinate_page(&ctx, user_id, CoordinateFilter::All, &page).await
 }
 
 /// Get coordinate by PID with clothes
@@ -43,11 +129,15 @@ async fn update(
     Path(pid): Path<String>,
     Json(params): Json<UpdateCoordinateParams>,
 ) -> Result<Response> {
+    let errors = params.problems();
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
     let coordinate = coordinates::Model::update_by_pid(&ctx.db, &pid, &params).await?;
     format::json(coordinate)
 }
 
-/// Delete coordinate by PID
+/// Move coordinate to the trash by PID
 #[debug_handler]
 async fn delete_coordinate(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
     coordinates::Model::delete_by_pid(&ctx.db, &pid).await?;
@@ -61,7 +151,15 @@ async fn add_clothes(
     Path(pid): Path<String>,
     Json(params): Json<AddClothesToCoordinateParams>,
 ) -> Result<Response> {
-    coordinates::Model::add_clothes(&ctx.db, &pid, &params).await?;
+    let errors = coordinates::Model::add_clothes_problems(&ctx.db, &pid, &params).await?;
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let errors = coordinates::Model::add_clothes(&ctx.db, &pid, &params).await?;
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    queue_outfit_image(&ctx, &pid, false).await?;
     format::json(json!({"msg": "Clothes added to coordinate successfully"}))
 }
 
@@ -72,18 +170,166 @@ async fn remove_clothes_from_coordinate(
     Path((pid, clothes_id)): Path<(String, i32)>,
 ) -> Result<Response> {
     coordinates::Model::remove_clothes(&ctx.db, &pid, clothes_id).await?;
+    queue_outfit_image(&ctx, &pid, false).await?;
     format::json(json!({"msg": "Clothes removed from coordinate successfully"}))
 }
 
-/// Update clothes position in coordinate
+/// Validate and apply a layout update, `Err` with the field errors when it
+/// is invalid
+async fn apply_layout(
+    ctx: &AppContext,
+    pid: &str,
+    items: &[ClothesLayoutParams],
+) -> Result<std::result::Result<Vec<clothes_coordinates::Model>, Vec<FieldError>>> {
+    let errors = coordinates::Model::layout_problems(&ctx.db, pid, items).await?;
+    if !errors.is_empty() {
+        return Ok(Err(errors));
+    }
+    let updated = coordinates::Model::update_layout(&ctx.db, pid, items).await?;
+    queue_outfit_image(ctx, pid, false).await?;
+    Ok(Ok(updated))
+}
+
+/// Update the slot, canvas placement and notes of one garment in a coordinate
 #[debug_handler]
 async fn update_clothes_position(
     State(ctx): State<AppContext>,
     Path(pid): Path<String>,
-    Json(params): Json<ClothesPositionParams>,
+    Json(params): Json<ClothesLayoutParams>,
+) -> Result<Response> {
+    match apply_layout(&ctx, &pid, std::slice::from_ref(&params)).await? {
+        Ok(mut updated) => format::json(updated.pop()),
+        Err(errors) => unprocessable(&errors),
+    }
+}
+
+/// Update the layout of several garments of a coordinate in one transaction
+#[debug_handler]
+async fn reorder_clothes(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<ReorderClothesParams>,
+) -> Result<Response> {
+    if params.items.is_empty() {
+        return bad_request("items must not be empty");
+    }
+    match apply_layout(&ctx, &pid, &params.items).await? {
+        Ok(updated) => format::json(updated),
+        Err(errors) => unprocessable(&errors),
+    }
+}
+
+/// Render the composite outfit image in the background, replacing any image
+/// supplied by the user
+#[debug_handler]
+async fn render_image(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    queue_outfit_image(&ctx, &coordinate.pid.to_string(), true).await?;
+    format::render()
+        .status(StatusCode::ACCEPTED)
+        .json(json!({"msg": "Outfit image rendering queued"}))
+}
+
+/// Get the rendered outfit image of a coordinate
+#[debug_handler]
+async fn get_image(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    if !outfit_images::is_rendered(&coordinate) {
+        return not_found();
+    }
+    let Some(path) = coordinate.image_url else {
+        return not_found();
+    };
+    let format = OutfitImageFormat::from_path(&path).unwrap_or_default();
+    let data: Vec<u8> = ctx.storage.download(std::path::Path::new(&path)).await?;
+    Ok(format::render()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .response()
+        .body(Body::from(data))?)
+}
+
+/// Duplicate a coordinate along with its garments and their layout
+#[debug_handler]
+async fn clone_coordinate(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<CloneCoordinateParams>,
+) -> Result<Response> {
+    let copy = coordinates::Model::clone_by_pid(&ctx.db, &pid, &params).await?;
+    queue_outfit_image(&ctx, &copy.coordinate.pid.to_string(), false).await?;
+    format::json(copy)
+}
+
+/// Get the version history of a coordinate, oldest first
+#[debug_handler]
+async fn list_versions(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    let versions = coordinate_versions::Model::find_by_coordinate(&ctx.db, coordinate.id).await?;
+    format::json(versions)
+}
+
+/// Get one version of a coordinate
+#[debug_handler]
+async fn get_version(
+    State(ctx): State<AppContext>,
+    Path((pid, version)): Path<(String, i32)>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    let version = coordinate_versions::Model::find_version(&ctx.db, coordinate.id, version).await?;
+    format::json(version)
+}
+
+/// Compare two versions of a coordinate: metadata changes and clothes
+/// added, removed or repositioned
+#[debug_handler]
+async fn diff_versions(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(query): Query<DiffQuery>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    let from = coordinate_versions::Model::find_version(&ctx.db, coordinate.id, query.from).await?;
+    let to = match query.to {
+        Some(to) => coordinate_versions::Model::find_version(&ctx.db, coordinate.id, to).await?,
+        None => coordinate_versions::Model::find_latest(&ctx.db, coordinate.id).await?,
+    };
+    format::json(from.diff(&to)?)
+}
+
+/// Restore an earlier version of a coordinate
+#[debug_handler]
+async fn restore_version(
+    State(ctx): State<AppContext>,
+    Path((pid, version)): Path<(String, i32)>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::restore_version(&ctx.db, &pid, version).await?;
+    queue_outfit_image(&ctx, &pid, false).await?;
+    format::json(coordinate)
+}
+
+/// Get the slot rules of a user, the defaults when none were saved
+#[debug_handler]
+async fn get_rules(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let rules = SlotRules::for_user(&ctx.db, user_id).await?;
+    format::json(rules)
+}
+
+/// Replace the slot rules of a user
+#[debug_handler]
+async fn update_rules(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Json(rules): Json<SlotRules>,
 ) -> Result<Response> {
-    coordinates::Model::update_clothes_position(&ctx.db, &pid, &params).await?;
-    format::json(json!({"msg": "Clothes position updated successfully"}))
+    let errors = rules.problems();
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let saved = coordinate_rules::Model::upsert(&ctx.db, user_id, &rules).await?;
+    format::json(saved.slot_rules()?)
 }
 
 /// Get coordinates by season for a user
@@ -91,9 +337,12 @@ async fn update_clothes_position(
 async fn get_by_season(
     State(ctx): State<AppContext>,
     Path((user_id, season)): Path<(i32, String)>,
+    Query(page): Query<PageQuery>,
 ) -> Result<Response> {
-    let coordinates = coordinates::Model::find_by_season(&ctx.db, user_id, &season).await?;
-    format::json(coordinates)
+    let Some(season) = Season::parse(&season) else {
+        return bad_request(format!("unknown season: {season}"));
+    };
+    coordinate_page(&ctx, user_id, CoordinateFilter::Season(season), &page).await
 }
 
 /// Get favorite coordinates for a user
@@ -101,9 +350,9 @@ async fn get_by_season(
 async fn get_favorites(
     State(ctx): State<AppContext>,
     Path(user_id): Path<i32>,
+    Query(page): Query<PageQuery>,
 ) -> Result<Response> {
-    let coordinates = coordinates::Model::find_favorites(&ctx.db, user_id).await?;
-    format::json(coordinates)
+    coordinate_page(&ctx, user_id, CoordinateFilter::Favorites, &page).await
 }
 
 pub fn routes() -> Routes {
@@ -111,12 +360,23 @@ pub fn routes() -> Routes {
         .prefix("/api/coordinates")
         .add("/", post(create))
         .add("/user/{user_id}", get(list_by_user))
+        .add("/suggestions", get(get_suggestions))
         .add("/{pid}", get(get_one))
         .add("/{pid}", put(update))
         .add("/{pid}", delete(delete_coordinate))
         .add("/{pid}/clothes", post(add_clothes))
         .add("/{pid}/clothes/{clothes_id}", delete(remove_clothes_from_coordinate))
         .add("/{pid}/clothes/position", put(update_clothes_position))
+        .add("/{pid}/clothes/layout", put(reorder_clothes))
+        .add("/{pid}/image", get(get_image))
+        .add("/{pid}/image", post(render_image))
+        .add("/{pid}/clone", post(clone_coordinate))
+        .add("/{pid}/versions", get(list_versions))
+        .add("/{pid}/versions/diff", get(diff_versions))
+        .add("/{pid}/versions/{version}", get(get_version))
+        .add("/{pid}/versions/{version}/restore", post(restore_version))
         .add("/user/{user_id}/season/{season}", get(get_by_season))
         .add("/user/{user_id}/favorites", get(get_favorites))
+        .add("/user/{user_id}/rules", get(get_rules))
+        .add("/user/{user_id}/rules", put(update_rules))
 }
\ No newline at end of file
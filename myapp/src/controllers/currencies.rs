@@ -0,0 +1,63 @@
+use crate::{
+    data::money::Currency,
+    models::{
+        _entities::users,
+        exchange_rates::{self, WardrobeTotals},
+    },
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct TotalsQuery {
+    /// Report in this currency instead of the user's home currency
+    pub currency: Option<Currency>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HomeCurrencyParams {
+    pub currency: Currency,
+}
+
+/// List the exchange rates
+#[debug_handler]
+async fn list_rates(State(ctx): State<AppContext>) -> Result<Response> {
+    format::json(exchange_rates::Model::list(&ctx.db).await?)
+}
+
+/// Total value of the wardrobe in the user's home currency
+#[debug_handler]
+async fn totals(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<TotalsQuery>,
+) -> Result<Response> {
+    format::json(WardrobeTotals::for_user(&ctx.db, user_id, query.currency).await?)
+}
+
+/// Set the currency totals are reported in for the user
+#[debug_handler]
+async fn set_home_currency(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Json(params): Json<HomeCurrencyParams>,
+) -> Result<Response> {
+    let user = users::Entity::find_by_id(user_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let user = user
+        .into_active_model()
+        .set_home_currency(&ctx.db, params.currency)
+        .await?;
+    format::json(serde_json::json!({ "home_currency": user.home_currency }))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/currencies")
+        .add("/rates", get(list_rates))
+        .add("/user/{user_id}/totals", get(totals))
+        .add("/user/{user_id}/home", put(set_home_currency))
+}
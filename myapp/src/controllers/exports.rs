@@ -0,0 +1,102 @@
+use std::path::Path as StoragePath;
+
+use crate::{
+    common::{images::ImageFetcher, settings::Settings},
+    models::wardrobe_exports::{self, ExportFormat, ExportWardrobeParams, WardrobeDocument},
+    views::exports::WardrobeExportResponse,
+    workers::wardrobe_export::{WardrobeExportWorker, WardrobeExportWorkerArgs},
+};
+use axum::{
+    body::Body,
+    debug_handler,
+    extract::Query,
+    http::{StatusCode, header},
+};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadParams {
+    pub token: String,
+}
+
+fn attachment(format: ExportFormat, data: Vec<u8>) -> Result<Response> {
+    Ok(format::render()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", format.file_name()),
+        )
+        .response()
+        .body(Body::from(data))?)
+}
+
+/// Export the wardrobe of a user: their coordinates and the clothes in
+/// them. Clothes in none of the user's coordinates are left out.
+///
+/// Small wardrobes are returned directly as a file download. Larger ones are
+/// built by a background worker which emails a time-limited download link;
+/// the queued export is returned with `202 Accepted`.
+#[debug_handler]
+async fn create(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Json(params): Json<ExportWardrobeParams>,
+) -> Result<Response> {
+    let settings = Settings::from_context(&ctx)?;
+    let items = WardrobeDocument::count(&ctx.db, user_id).await?;
+    if items > u64::try_from(settings.export.background_threshold).unwrap_or(u64::MAX) {
+        let export =
+            wardrobe_exports::Model::create_queued(&ctx.db, user_id, params.format).await?;
+        WardrobeExportWorker::perform_later(
+            &ctx,
+            WardrobeExportWorkerArgs {
+                export_pid: export.pid.to_string(),
+            },
+        )
+        .await?;
+        return format::render()
+            .status(StatusCode::ACCEPTED)
+            .json(WardrobeExportResponse::new(&export));
+    }
+
+    let document = WardrobeDocument::collect(&ctx.db, user_id).await?;
+    let fetcher = ImageFetcher::new(&settings.images);
+    let data = document
+        .render(params.format, &ctx.storage, &fetcher)
+        .await?;
+    attachment(params.format, data)
+}
+
+/// Get the status of a background export
+#[debug_handler]
+async fn get_one(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let export = wardrobe_exports::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(WardrobeExportResponse::new(&export))
+}
+
+/// Download a finished export with the token from the emailed link
+#[debug_handler]
+async fn download(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(params): Query<DownloadParams>,
+) -> Result<Response> {
+    let export = wardrobe_exports::Model::find_by_pid(&ctx.db, &pid).await?;
+    if !export.is_downloadable(&params.token, chrono::Utc::now()) {
+        return unauthorized("download link is invalid or has expired");
+    }
+    let (Some(format), Some(path)) = (export.export_format(), &export.file_path) else {
+        return not_found();
+    };
+    let data: Vec<u8> = ctx.storage.download(StoragePath::new(path)).await?;
+    attachment(format, data)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/exports")
+        .add("/user/{user_id}", post(create))
+        .add("/{pid}", get(get_one))
+        .add("/{pid}/download", get(download))
+}
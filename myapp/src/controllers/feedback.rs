@@ -0,0 +1,65 @@
+use super::coordinates::unprocessable;
+use crate::models::{
+    coordinates,
+    outfit_feedback::{self, CreateFeedbackParams, FeedbackRatings, RatingSummary},
+};
+use axum::{debug_handler, http::StatusCode};
+use loco_rs::prelude::*;
+use serde_json::json;
+
+/// Record how a worn coordinate went
+#[debug_handler]
+async fn create(
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateFeedbackParams>,
+) -> Result<Response> {
+    let errors = params.problems();
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let feedback = outfit_feedback::Model::create(&ctx.db, &params).await?;
+    format::render().status(StatusCode::CREATED).json(feedback)
+}
+
+/// Get the feedback on a coordinate with its averages
+#[debug_handler]
+async fn list_by_coordinate(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::find_by_pid(&ctx.db, &pid).await?;
+    let feedback = outfit_feedback::Model::find_by_coordinate(&ctx.db, &coordinate).await?;
+    let summary = RatingSummary::from_feedback(&feedback);
+    format::json(json!({"ratings": summary, "feedback": feedback}))
+}
+
+/// Change the ratings or note of a feedback
+#[debug_handler]
+async fn update(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(ratings): Json<FeedbackRatings>,
+) -> Result<Response> {
+    let errors = ratings.problems();
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let feedback = outfit_feedback::Model::update_by_pid(&ctx.db, &pid, &ratings).await?;
+    format::json(feedback)
+}
+
+/// Delete a feedback
+#[debug_handler]
+async fn remove(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    outfit_feedback::Model::delete_by_pid(&ctx.db, &pid).await?;
+    format::json(json!({"msg": "Feedback deleted successfully"}))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/feedback")
+        .add("/", post(create))
+        .add("/coordinate/{pid}", get(list_by_coordinate))
+        .add("/{pid}", put(update))
+        .add("/{pid}", delete(remove))
+}
@@ -1,7 +1,10 @@
-use crate::models::{
-    clothes::{CreateClothesParams, UpdateClothesParams},
-    coordinates::{CreateCoordinateParams, UpdateCoordinateParams},
-    _entities::{clothes, coordinates},
+use crate::{
+    models::{
+        clothes::{CreateClothesParams, UpdateClothesParams},
+        coordinates::{CreateCoordinateParams, UpdateCoordinateParams},
+        _entities::{clothes, coordinates},
+    },
+    views::clothes::ClothesResponse,
 };
 use axum::debug_handler;
 use loco_rs::prelude::*;
@@ -17,7 +20,7 @@ async fn submit_clothes_form(
     format::json(json!({
         "success": true,
         "message": "Clothes item created successfully",
-        "data": clothes
+        "data": ClothesResponse::new(clothes, None)
     }))
 }
 
@@ -46,7 +49,7 @@ async fn update_clothes_form(
     format::json(json!({
         "success": true,
         "message": "Clothes item updated successfully",
-        "data": clothes
+        "data": ClothesResponse::new(clothes, None)
     }))
 }
 
@@ -2,3 +2,14 @@ pub mod auth;
 pub mod clothes;
 pub mod coordinates;
 pub mod forms;
+pub mod exports;
+pub mod wears;
+pub mod calendar;
+pub mod feedback;
+pub mod vocabularies;
+pub mod trips;
+pub mod capsules;
+pub mod trash;
+pub mod currencies;
+pub mod spending;
+pub mod notifications;
@@ -0,0 +1,26 @@
+use crate::models::notifications::{self, NotificationQuery};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+
+/// List the notifications of the user, latest first
+#[debug_handler]
+async fn list(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Response> {
+    format::json(notifications::Model::find_by_user(&ctx.db, user_id, &query).await?)
+}
+
+/// Mark a notification read
+#[debug_handler]
+async fn mark_read(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    format::json(notifications::Model::mark_read_by_pid(&ctx.db, &pid).await?)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/notifications")
+        .add("/user/{user_id}", get(list))
+        .add("/{pid}/read", post(mark_read))
+}
@@ -0,0 +1,71 @@
+use super::coordinates::unprocessable;
+use crate::{
+    models::{
+        _entities::users,
+        budgets::{self, BudgetParams},
+        purchases::{SpendingQuery, SpendingReport},
+    },
+    workers::budget_alert::{BudgetAlertWorker, BudgetAlertWorkerArgs},
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+
+/// Spending of the user grouped by month, brand, category and store, with
+/// how their budgets stand in each month
+#[debug_handler]
+async fn report(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<SpendingQuery>,
+) -> Result<Response> {
+    format::json(SpendingReport::for_user(&ctx.db, user_id, &query).await?)
+}
+
+/// List the budgets of the user
+#[debug_handler]
+async fn list_budgets(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    format::json(budgets::Model::find_by_user(&ctx.db, user_id).await?)
+}
+
+/// Set the user's monthly budget for all purchases or a category, and check
+/// it against this month's purchases
+#[debug_handler]
+async fn set_budget(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Json(params): Json<BudgetParams>,
+) -> Result<Response> {
+    let user = users::Entity::find_by_id(user_id)
+        .one(&ctx.db)
+        .await?
+        .ok_or_else(|| Error::NotFound)?;
+    let errors = params.problems(user.home_currency());
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let budget = budgets::Model::set(&ctx.db, &user, &params).await?;
+    BudgetAlertWorker::perform_later(
+        &ctx,
+        BudgetAlertWorkerArgs {
+            month: chrono::Utc::now().date_naive(),
+        },
+    )
+    .await?;
+    format::json(budget)
+}
+
+/// Remove a budget
+#[debug_handler]
+async fn delete_budget(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    budgets::Model::delete_by_pid(&ctx.db, &pid).await?;
+    format::json(serde_json::json!({"msg": "Budget deleted successfully"}))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/spending")
+        .add("/user/{user_id}", get(report))
+        .add("/user/{user_id}/budgets", get(list_budgets))
+        .add("/user/{user_id}/budgets", put(set_budget))
+        .add("/budgets/{pid}", delete(delete_budget))
+}
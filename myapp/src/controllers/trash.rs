@@ -0,0 +1,44 @@
+use crate::{
+    common::settings::Settings,
+    models::{_entities::clothes, coordinates, trash::Trash},
+    views::clothes::ClothesResponse,
+};
+use axum::debug_handler;
+use loco_rs::prelude::*;
+
+/// List the deleted clothes and coordinates a user can still restore
+#[debug_handler]
+async fn list_by_user(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let retention_days = Settings::from_context(&ctx)?.trash.retention_days;
+    format::json(Trash::for_user(&ctx.db, user_id, retention_days).await?)
+}
+
+/// Take a clothes item out of the trash
+#[debug_handler]
+async fn restore_clothes(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let clothes = clothes::Model::restore_by_pid(&ctx.db, &pid).await?;
+    format::json(ClothesResponse::new(clothes, None))
+}
+
+/// Take a coordinate out of the trash
+#[debug_handler]
+async fn restore_coordinate(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+) -> Result<Response> {
+    let coordinate = coordinates::Model::restore_by_pid(&ctx.db, &pid).await?;
+    format::json(
+        coordinates::Model::find_by_pid_with_clothes(&ctx.db, &coordinate.pid.to_string()).await?,
+    )
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/trash")
+        .add("/user/{user_id}", get(list_by_user))
+        .add("/clothes/{pid}/restore", post(restore_clothes))
+        .add("/coordinates/{pid}/restore", post(restore_coordinate))
+}
@@ -0,0 +1,119 @@
+use super::coordinates::unprocessable;
+use crate::models::trips::{self, ChecklistQuery, CreateTripParams, PackParams, UpdateTripParams};
+use axum::{
+    body::Body,
+    debug_handler,
+    extract::Query,
+    http::{StatusCode, header},
+};
+use loco_rs::prelude::*;
+use serde_json::json;
+
+/// Create a trip
+#[debug_handler]
+async fn create(
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateTripParams>,
+) -> Result<Response> {
+    let errors = params.problems();
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let trip = trips::Model::create(&ctx.db, &params).await?;
+    format::render()
+        .status(StatusCode::CREATED)
+        .json(trip.details(&ctx.db).await?)
+}
+
+/// List the trips of a user
+#[debug_handler]
+async fn list_by_user(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    format::json(trips::Model::find_by_user(&ctx.db, user_id).await?)
+}
+
+/// Get a trip with its planned outfits and packing list
+#[debug_handler]
+async fn get_one(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let trip = trips::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(trip.details(&ctx.db).await?)
+}
+
+/// Change the details of a trip
+#[debug_handler]
+async fn update(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Json(params): Json<UpdateTripParams>,
+) -> Result<Response> {
+    let trip = trips::Model::find_by_pid(&ctx.db, &pid).await?;
+    let errors = trip.update_problems(&params)?;
+    if !errors.is_empty() {
+        return unprocessable(&errors);
+    }
+    let trip = trips::Model::update_by_pid(&ctx.db, &pid, &params).await?;
+    format::json(trip.details(&ctx.db).await?)
+}
+
+/// Delete a trip and its planned outfits
+#[debug_handler]
+async fn remove(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    trips::Model::delete_by_pid(&ctx.db, &pid).await?;
+    format::json(json!({"msg": "Trip deleted successfully"}))
+}
+
+/// Pick outfits for every day of a trip and rebuild its packing list
+#[debug_handler]
+async fn plan(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let trip = trips::Model::find_by_pid(&ctx.db, &pid).await?;
+    format::json(trip.plan(&ctx.db).await?)
+}
+
+/// Mark a garment of the packing list as packed or not
+#[debug_handler]
+async fn pack(
+    State(ctx): State<AppContext>,
+    Path((pid, clothes_pid)): Path<(String, String)>,
+    Json(params): Json<PackParams>,
+) -> Result<Response> {
+    let trip = trips::Model::find_by_pid(&ctx.db, &pid).await?;
+    let item = trip
+        .set_packed(&ctx.db, &clothes_pid, params.packed)
+        .await?;
+    format::json(item)
+}
+
+/// Download the packing list as a Markdown (default) or CSV checklist
+#[debug_handler]
+async fn checklist(
+    State(ctx): State<AppContext>,
+    Path(pid): Path<String>,
+    Query(query): Query<ChecklistQuery>,
+) -> Result<Response> {
+    let format = query.format.unwrap_or_default();
+    let trip = trips::Model::find_by_pid(&ctx.db, &pid).await?;
+    let data = trip.details(&ctx.db).await?.checklist(format)?;
+    Ok(format::render()
+        .header(header::CONTENT_TYPE, format.content_type())
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"packing-list.{}\"",
+                format.extension()
+            ),
+        )
+        .response()
+        .body(Body::from(data))?)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/trips")
+        .add("/", post(create))
+        .add("/user/{user_id}", get(list_by_user))
+        .add("/{pid}", get(get_one))
+        .add("/{pid}", put(update))
+        .add("/{pid}", delete(remove))
+        .add("/{pid}/plan", post(plan))
+        .add("/{pid}/packing/{clothes_pid}", put(pack))
+        .add("/{pid}/checklist", get(checklist))
+}
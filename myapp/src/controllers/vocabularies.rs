@@ -0,0 +1,36 @@
+use crate::{common::i18n::Labels, data::vocabularies::Vocabularies};
+use axum::{
+    debug_handler,
+    extract::Query,
+    http::{HeaderMap, header},
+};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageQuery {
+    /// Language of the labels, such as `ja-JP`. Defaults to the first
+    /// language of the `Accept-Language` header, then to English.
+    pub lang: Option<String>,
+}
+
+/// Get the keys of every season, occasion and style with translated labels
+#[debug_handler]
+async fn list(headers: HeaderMap, Query(query): Query<LanguageQuery>) -> Result<Response> {
+    let labels = Labels::get().map_err(|e| Error::string(&e))?;
+    let accepted = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split([',', ';']).next())
+        .map(str::trim);
+    let lang = labels.language(query.lang.as_deref().or(accepted));
+    format::json(Vocabularies::new(&lang.to_string(), |id| {
+        labels.label(&lang, id)
+    }))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/vocabularies")
+        .add("/", get(list))
+}
@@ -0,0 +1,82 @@
+use super::coordinates::unprocessable;
+use crate::models::{
+    coordinate_rules::FieldError,
+    wear_events::{self, LogWearParams, NotWornQuery, WearRangeQuery},
+};
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde_json::json;
+
+/// Log a wear of a garment or of a whole coordinate of the user. A garment
+/// in none of the user's coordinates, or a coordinate of someone else, is
+/// not found.
+#[debug_handler]
+async fn log(State(ctx): State<AppContext>, Json(params): Json<LogWearParams>) -> Result<Response> {
+    let problems = params.problems();
+    if !problems.is_empty() {
+        return unprocessable(&problems);
+    }
+    match wear_events::Model::log(&ctx.db, &params).await? {
+        None => not_found(),
+        Some(events) if events.is_empty() => unprocessable(&[FieldError::new(
+            "coordinate_pid",
+            "has no clothes that can be worn",
+        )]),
+        Some(events) => format::json(events),
+    }
+}
+
+/// Undo a logged wear
+#[debug_handler]
+async fn undo(State(ctx): State<AppContext>, Path(pid): Path<String>) -> Result<Response> {
+    let removed = wear_events::Model::undo(&ctx.db, &pid).await?;
+    format::json(json!({"msg": "Wear removed successfully", "removed": removed}))
+}
+
+/// Get the wear history of a user, optionally within `from`..=`to`
+#[debug_handler]
+async fn list_by_user(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(range): Query<WearRangeQuery>,
+) -> Result<Response> {
+    let events = wear_events::Model::find_by_user(&ctx.db, user_id, &range).await?;
+    format::json(events)
+}
+
+/// Get wear count, last worn date and cost-per-wear of every clothes item
+#[debug_handler]
+async fn stats(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let stats = wear_events::Model::clothes_stats(&ctx.db, user_id).await?;
+    format::json(stats)
+}
+
+/// Get clothes the user has never worn
+#[debug_handler]
+async fn never_worn(State(ctx): State<AppContext>, Path(user_id): Path<i32>) -> Result<Response> {
+    let stats = wear_events::Model::never_worn(&ctx.db, user_id).await?;
+    format::json(stats)
+}
+
+/// Get clothes not worn in the last `months` months (default 6)
+#[debug_handler]
+async fn not_worn(
+    State(ctx): State<AppContext>,
+    Path(user_id): Path<i32>,
+    Query(query): Query<NotWornQuery>,
+) -> Result<Response> {
+    let months = query.months.unwrap_or(NotWornQuery::DEFAULT_MONTHS);
+    let stats = wear_events::Model::not_worn_in_months(&ctx.db, user_id, months).await?;
+    format::json(stats)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("/api/wears")
+        .add("/", post(log))
+        .add("/{pid}", delete(undo))
+        .add("/user/{user_id}", get(list_by_user))
+        .add("/user/{user_id}/stats", get(stats))
+        .add("/user/{user_id}/never-worn", get(never_worn))
+        .add("/user/{user_id}/not-worn", get(not_worn))
+}
@@ -0,0 +1,331 @@
+//! Structured color representation for clothes.
+//!
+//! Colors are stored as a normalized `#RRGGBB` hex value plus a coarse
+//! [`ColorFamily`] used for searching. Free-form color names (English and
+//! Japanese) are resolved through a built-in palette, and "similar color"
+//! searches use the CIEDE2000 perceptual distance.
+
+use serde::{Deserialize, Serialize};
+
+/// Coarse color family used for filtering clothes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFamily {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Pink,
+    Brown,
+    Neutral,
+}
+
+impl ColorFamily {
+    pub const ALL: [Self; 9] = [
+        Self::Red,
+        Self::Orange,
+        Self::Yellow,
+        Self::Green,
+        Self::Blue,
+        Self::Purple,
+        Self::Pink,
+        Self::Brown,
+        Self::Neutral,
+    ];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Red => "red",
+            Self::Orange => "orange",
+            Self::Yellow => "yellow",
+            Self::Green => "green",
+            Self::Blue => "blue",
+            Self::Purple => "purple",
+            Self::Pink => "pink",
+            Self::Brown => "brown",
+            Self::Neutral => "neutral",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        Self::ALL.into_iter().find(|family| family.as_str() == value)
+    }
+}
+
+/// A resolved color: normalized hex value and its family
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Color {
+    pub hex: String,
+    pub family: ColorFamily,
+}
+
+/// Built-in palette of named colors: (names, hex, family).
+///
+/// Names are matched case-insensitively after trimming. Both English and
+/// Japanese names are listed so that existing free-form `color` values can be
+/// mapped without user intervention.
+const PALETTE: &[(&[&str], &str, ColorFamily)] = &[
+    (&["black", "ブラック", "黒", "くろ"], "#000000", ColorFamily::Neutral),
+    (&["white", "ホワイト", "白", "しろ"], "#FFFFFF", ColorFamily::Neutral),
+    (&["gray", "grey", "グレー", "グレイ", "灰色", "はいいろ"], "#808080", ColorFamily::Neutral),
+    (&["light gray", "light grey", "ライトグレー"], "#D3D3D3", ColorFamily::Neutral),
+    (&["charcoal", "charcoal gray", "チャコール", "チャコールグレー"], "#36454F", ColorFamily::Neutral),
+    (&["beige", "ベージュ"], "#D8C8A8", ColorFamily::Neutral),
+    (&["ivory", "アイボリー"], "#FFFFF0", ColorFamily::Neutral),
+    (&["off white", "off-white", "オフホワイト", "生成り"], "#F8F4E8", ColorFamily::Neutral),
+    (&["cream", "クリーム"], "#FFFDD0", ColorFamily::Neutral),
+    (&["greige", "グレージュ"], "#B5ADA1", ColorFamily::Neutral),
+    (&["navy", "ネイビー", "紺", "紺色"], "#1F2A44", ColorFamily::Blue),
+    (&["blue", "ブルー", "青", "あお"], "#1E56C8", ColorFamily::Blue),
+    (&["light blue", "sky blue", "ライトブルー", "サックス", "水色"], "#87CEEB", ColorFamily::Blue),
+    (&["denim", "indigo", "デニム", "インディゴ", "藍色"], "#3B4F7A", ColorFamily::Blue),
+    (&["turquoise", "ターコイズ"], "#40E0D0", ColorFamily::Blue),
+    (&["red", "レッド", "赤", "あか"], "#D0202E", ColorFamily::Red),
+    (&["burgundy", "bordeaux", "wine", "wine red", "ボルドー", "バーガンディ", "ワインレッド", "えんじ"], "#800020", ColorFamily::Red),
+    (&["pink", "ピンク", "桃色"], "#F4A6C0", ColorFamily::Pink),
+    (&["rose", "ローズ"], "#E0607E", ColorFamily::Pink),
+    (&["orange", "オレンジ", "橙", "橙色"], "#F08A24", ColorFamily::Orange),
+    (&["terracotta", "テラコッタ"], "#C8643C", ColorFamily::Orange),
+    (&["yellow", "イエロー", "黄色", "きいろ"], "#F5D033", ColorFamily::Yellow),
+    (&["mustard", "マスタード", "からし色"], "#D4A017", ColorFamily::Yellow),
+    (&["green", "グリーン", "緑", "みどり"], "#2E8B57", ColorFamily::Green),
+    (&["olive", "オリーブ"], "#708238", ColorFamily::Green),
+    (&["khaki", "カーキ"], "#8A8450", ColorFamily::Green),
+    (&["mint", "ミント"], "#98E0C0", ColorFamily::Green),
+    (&["brown", "ブラウン", "茶色", "茶"], "#6F4E37", ColorFamily::Brown),
+    (&["camel", "キャメル"], "#C19A6B", ColorFamily::Brown),
+    (&["dark brown", "ダークブラウン", "焦げ茶"], "#4A3020", ColorFamily::Brown),
+    (&["purple", "パープル", "紫", "むらさき"], "#7D3C98", ColorFamily::Purple),
+    (&["lavender", "ラベンダー"], "#B9A7DC", ColorFamily::Purple),
+];
+
+/// Look up a palette entry by its English or Japanese name
+#[must_use]
+pub fn lookup_name(name: &str) -> Option<Color> {
+    let name = name.trim().to_lowercase();
+    PALETTE.iter().find_map(|(names, hex, family)| {
+        names.iter().any(|n| *n == name).then(|| Color {
+            hex: (*hex).to_string(),
+            family: *family,
+        })
+    })
+}
+
+/// Resolve a user supplied color, given either as a hex value (`#RRGGBB`,
+/// `#RGB`, with or without the leading `#`) or as a palette name.
+#[must_use]
+pub fn resolve(value: &str) -> Option<Color> {
+    if let Some(rgb) = parse_hex(value) {
+        return Some(Color {
+            hex: to_hex(rgb),
+            family: family_of(rgb),
+        });
+    }
+    lookup_name(value)
+}
+
+/// Parse a hex color string into its RGB components
+#[must_use]
+pub fn parse_hex(value: &str) -> Option<[u8; 3]> {
+    let value = value.trim();
+    let digits = value.strip_prefix('#').unwrap_or(value);
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded = match digits.len() {
+        3 => digits.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 => digits.to_string(),
+        _ => return None,
+    };
+    let channel = |i: usize| u8::from_str_radix(&expanded[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+#[must_use]
+pub fn to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", rgb[0], rgb[1], rgb[2])
+}
+
+/// Classify an arbitrary RGB value into a color family using its hue,
+/// saturation and lightness.
+#[must_use]
+pub fn family_of(rgb: [u8; 3]) -> ColorFamily {
+    let [r, g, b] = rgb.map(|c| f64::from(c) / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta < f64::EPSILON || !(0.1..=0.95).contains(&lightness) {
+        return ColorFamily::Neutral;
+    }
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    if saturation < 0.15 {
+        return ColorFamily::Neutral;
+    }
+
+    let hue = if (max - r).abs() < f64::EPSILON {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if (max - g).abs() < f64::EPSILON {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    match hue {
+        h if !(15.0..345.0).contains(&h) => {
+            if lightness > 0.7 {
+                ColorFamily::Pink
+            } else {
+                ColorFamily::Red
+            }
+        }
+        h if h < 45.0 => {
+            if lightness < 0.4 {
+                ColorFamily::Brown
+            } else if saturation < 0.45 && lightness > 0.6 {
+                ColorFamily::Neutral
+            } else {
+                ColorFamily::Orange
+            }
+        }
+        h if h < 70.0 => {
+            if lightness < 0.3 {
+                ColorFamily::Brown
+            } else {
+                ColorFamily::Yellow
+            }
+        }
+        h if h < 170.0 => ColorFamily::Green,
+        h if h < 260.0 => ColorFamily::Blue,
+        h if h < 300.0 => ColorFamily::Purple,
+        _ => ColorFamily::Pink,
+    }
+}
+
+/// A color in the CIE L*a*b* space (D65 reference white)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab {
+    pub l: f64,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl Lab {
+    /// Convert an sRGB color to CIE L*a*b*
+    #[must_use]
+    pub fn from_rgb(rgb: [u8; 3]) -> Self {
+        let [r, g, b] = rgb.map(|c| {
+            let c = f64::from(c) / 255.0;
+            if c <= 0.040_45 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        });
+
+        let x = (0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b) / 0.950_47;
+        let y = 0.212_672_9 * r + 0.715_152_2 * g + 0.072_175_0 * b;
+        let z = (0.019_333_9 * r + 0.119_192_0 * g + 0.950_304_1 * b) / 1.088_83;
+
+        let f = |t: f64| {
+            if t > 0.008_856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+}
+
+/// CIEDE2000 color difference between two L*a*b* colors.
+///
+/// A value below ~2 is barely perceptible, while values above ~10 are
+/// clearly different colors.
+#[must_use]
+pub fn ciede2000(lab1: Lab, lab2: Lab) -> f64 {
+    const POW25_7: f64 = 6_103_515_625.0; // 25^7
+
+    let c1 = lab1.a.hypot(lab1.b);
+    let c2 = lab2.a.hypot(lab2.b);
+    let c_bar7 = ((c1 + c2) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + POW25_7)).sqrt());
+
+    let a1p = (1.0 + g) * lab1.a;
+    let a2p = (1.0 + g) * lab2.a;
+    let c1p = a1p.hypot(lab1.b);
+    let c2p = a2p.hypot(lab2.b);
+
+    let hue = |b: f64, a: f64| {
+        if b == 0.0 && a == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let h1p = hue(lab1.b, a1p);
+    let h2p = hue(lab2.b, a2p);
+
+    let delta_l = lab2.l - lab1.l;
+    let delta_c = c2p - c1p;
+    let delta_h_angle = if c1p * c2p == 0.0 {
+        0.0
+    } else {
+        let d = h2p - h1p;
+        if d.abs() <= 180.0 {
+            d
+        } else if d > 180.0 {
+            d - 360.0
+        } else {
+            d + 360.0
+        }
+    };
+    let delta_h = 2.0 * (c1p * c2p).sqrt() * (delta_h_angle.to_radians() / 2.0).sin();
+
+    let l_bar = (lab1.l + lab2.l) / 2.0;
+    let c_bar_p = (c1p + c2p) / 2.0;
+    let h_bar_p = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_bar_p - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * h_bar_p).to_radians().cos()
+        + 0.32 * (3.0 * h_bar_p + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * h_bar_p - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_bar_p - 275.0) / 25.0).powi(2)).exp();
+    let c_bar_p7 = c_bar_p.powi(7);
+    let r_c = 2.0 * (c_bar_p7 / (c_bar_p7 + POW25_7)).sqrt();
+    let s_l = 1.0 + (0.015 * (l_bar - 50.0).powi(2)) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar_p;
+    let s_h = 1.0 + 0.015 * c_bar_p * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let (dl, dc, dh) = (delta_l / s_l, delta_c / s_c, delta_h / s_h);
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh).sqrt()
+}
+
+/// CIEDE2000 distance between two hex colors, `None` when either is invalid
+#[must_use]
+pub fn distance(hex1: &str, hex2: &str) -> Option<f64> {
+    let lab1 = Lab::from_rgb(parse_hex(hex1)?);
+    let lab2 = Lab::from_rgb(parse_hex(hex2)?);
+    Some(ciede2000(lab1, lab2))
+}
@@ -0,0 +1,154 @@
+//! Garment lifecycle.
+//!
+//! A garment is in exactly one lifecycle state. Only active garments can be
+//! worn; the others are away for care, lent out, put away or gone for good.
+//! Moves between states follow a fixed set of transitions, so a sold item
+//! cannot come back from the laundry.
+
+use serde::{Deserialize, Serialize};
+
+/// Where a garment is in its life
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleState {
+    #[default]
+    Active,
+    InLaundry,
+    AtCleaner,
+    InRepair,
+    Lent,
+    Stored,
+    Retired,
+    Donated,
+    Sold,
+}
+
+impl LifecycleState {
+    pub const ALL: [Self; 9] = [
+        Self::Active,
+        Self::InLaundry,
+        Self::AtCleaner,
+        Self::InRepair,
+        Self::Lent,
+        Self::Stored,
+        Self::Retired,
+        Self::Donated,
+        Self::Sold,
+    ];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::InLaundry => "in_laundry",
+            Self::AtCleaner => "at_cleaner",
+            Self::InRepair => "in_repair",
+            Self::Lent => "lent",
+            Self::Stored => "stored",
+            Self::Retired => "retired",
+            Self::Donated => "donated",
+            Self::Sold => "sold",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase().replace([' ', '-'], "_");
+        Self::ALL.into_iter().find(|state| state.as_str() == value)
+    }
+
+    /// Whether the garment can be worn right now
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        *self == Self::Active
+    }
+
+    /// Whether the garment has left the wardrobe for good
+    #[must_use]
+    pub fn is_final(&self) -> bool {
+        matches!(self, Self::Donated | Self::Sold)
+    }
+
+    /// States a garment in this state can move to
+    #[must_use]
+    pub fn next(&self) -> &'static [Self] {
+        match self {
+            Self::Active => &[
+                Self::InLaundry,
+                Self::AtCleaner,
+                Self::InRepair,
+                Self::Lent,
+                Self::Stored,
+                Self::Retired,
+                Self::Donated,
+                Self::Sold,
+            ],
+            Self::InLaundry | Self::AtCleaner | Self::Lent => &[Self::Active],
+            Self::InRepair => &[Self::Active, Self::Retired],
+            Self::Stored | Self::Retired => &[
+                Self::Active,
+                Self::Stored,
+                Self::Retired,
+                Self::Donated,
+                Self::Sold,
+            ],
+            Self::Donated | Self::Sold => &[],
+        }
+    }
+
+    /// Whether a garment in this state can move to `to`
+    #[must_use]
+    pub fn can_become(&self, to: Self) -> bool {
+        *self != to && self.next().contains(&to)
+    }
+
+    /// Short description for checklists and warnings
+    #[must_use]
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Active => "available",
+            Self::InLaundry => "in the laundry",
+            Self::AtCleaner => "at the cleaner",
+            Self::InRepair => "in repair",
+            Self::Lent => "lent out",
+            Self::Stored => "in storage",
+            Self::Retired => "retired",
+            Self::Donated => "donated",
+            Self::Sold => "sold",
+        }
+    }
+}
+
+/// How worn a garment is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    New,
+    LikeNew,
+    Good,
+    Fair,
+    Poor,
+}
+
+impl Condition {
+    pub const ALL: [Self; 5] = [Self::New, Self::LikeNew, Self::Good, Self::Fair, Self::Poor];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::LikeNew => "like_new",
+            Self::Good => "good",
+            Self::Fair => "fair",
+            Self::Poor => "poor",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase().replace([' ', '-'], "_");
+        Self::ALL
+            .into_iter()
+            .find(|condition| condition.as_str() == value)
+    }
+}
@@ -1 +1,7 @@
-
+pub mod colors;
+pub mod lifecycle;
+pub mod money;
+pub mod seasons;
+pub mod sizes;
+pub mod slots;
+pub mod vocabularies;
@@ -0,0 +1,272 @@
+//! Seasons and weather, and how well a garment suits them.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::vocabularies::Vocabulary;
+
+/// Season of a coordinate or suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Self; 4] = [Self::Spring, Self::Summer, Self::Autumn, Self::Winter];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spring => "spring",
+            Self::Summer => "summer",
+            Self::Autumn => "autumn",
+            Self::Winter => "winter",
+        }
+    }
+
+    /// Parse an English or Japanese season name. `fall` is accepted for
+    /// autumn.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "spring" | "春" => Some(Self::Spring),
+            "summer" | "夏" => Some(Self::Summer),
+            "autumn" | "fall" | "秋" => Some(Self::Autumn),
+            "winter" | "冬" => Some(Self::Winter),
+            _ => None,
+        }
+    }
+
+    /// Parse a list of seasons such as `spring/summer`, `春夏` or
+    /// `all season`. Unknown parts are ignored; the result is in season
+    /// order without duplicates.
+    #[must_use]
+    pub fn parse_list(value: &str) -> Vec<Self> {
+        let value = value.trim().to_lowercase();
+        if ALL_SEASONS.contains(&value.as_str()) {
+            return Self::ALL.to_vec();
+        }
+
+        let mut seasons: Vec<Self> = value
+            .split(|c: char| c.is_whitespace() || SEPARATORS.contains(&c))
+            .flat_map(|part| match Self::parse(part) {
+                Some(season) => vec![season],
+                // Japanese names are often written together, as in 秋冬
+                None => part
+                    .chars()
+                    .filter_map(|c| Self::parse(c.encode_utf8(&mut [0; 4])))
+                    .collect(),
+            })
+            .collect();
+        seasons.sort();
+        seasons.dedup();
+        seasons
+    }
+
+    /// Meteorological season of a date. Seasons are flipped on the southern
+    /// hemisphere.
+    #[must_use]
+    pub fn from_date(date: NaiveDate, southern: bool) -> Self {
+        let season = match date.month() {
+            3..=5 => Self::Spring,
+            6..=8 => Self::Summer,
+            9..=11 => Self::Autumn,
+            _ => Self::Winter,
+        };
+        if southern {
+            match season {
+                Self::Spring => Self::Autumn,
+                Self::Summer => Self::Winter,
+                Self::Autumn => Self::Spring,
+                Self::Winter => Self::Summer,
+            }
+        } else {
+            season
+        }
+    }
+
+    /// Whether outfits for this season should include outerwear
+    #[must_use]
+    pub fn needs_outerwear(&self) -> bool {
+        matches!(self, Self::Autumn | Self::Winter)
+    }
+}
+
+impl TryFrom<String> for Season {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("unknown season: {value}"))
+    }
+}
+
+impl Vocabulary for Season {
+    const NAME: &'static str = "season";
+
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn key(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::parse(value)
+    }
+}
+
+/// Deserialize seasons given as a list or as a single free-form value such
+/// as `spring/summer`, as found in older wardrobe documents. Unknown values
+/// are ignored.
+///
+/// # Errors
+///
+/// When the value is neither a string nor a list of strings
+pub fn deserialize_list<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Season>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Option<String>),
+        Many(Vec<String>),
+    }
+
+    let values = match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => value.into_iter().collect(),
+        OneOrMany::Many(values) => values,
+    };
+    let mut seasons: Vec<Season> = values
+        .iter()
+        .flat_map(|value| Season::parse_list(value))
+        .collect();
+    seasons.sort();
+    seasons.dedup();
+    Ok(seasons)
+}
+
+/// Values meaning every season
+const ALL_SEASONS: &[&str] = &[
+    "all",
+    "all season",
+    "all seasons",
+    "all-season",
+    "year-round",
+    "通年",
+    "オールシーズン",
+];
+
+/// Separators of a season list besides whitespace
+const SEPARATORS: &[char] = &[',', '/', '&', '+', '・', '、'];
+
+const WARM_MATERIALS: &[&str] = &[
+    "wool",
+    "cashmere",
+    "fleece",
+    "down",
+    "corduroy",
+    "tweed",
+    "flannel",
+    "mohair",
+    "ウール",
+    "カシミヤ",
+    "フリース",
+    "ダウン",
+    "コーデュロイ",
+];
+
+const COOL_MATERIALS: &[&str] = &["linen", "seersucker", "mesh", "リネン", "麻", "メッシュ"];
+
+const LIGHT_CATEGORIES: &[&str] = &[
+    "short",
+    "sandal",
+    "tank",
+    "camisole",
+    "ショート",
+    "サンダル",
+    "タンク",
+    "キャミ",
+];
+
+const WARM_CATEGORIES: &[&str] = &["down", "parka", "coat", "ダウン", "コート"];
+
+const RAIN_PROOF: &[&str] = &[
+    "boot",
+    "rubber",
+    "gore-tex",
+    "goretex",
+    "waterproof",
+    "レイン",
+    "ブーツ",
+    "ゴム",
+    "防水",
+];
+
+const RAIN_SENSITIVE: &[&str] = &[
+    "suede",
+    "canvas",
+    "sandal",
+    "スエード",
+    "キャンバス",
+    "サンダル",
+];
+
+fn mentions(category: &str, material: Option<&str>, words: &[&str]) -> bool {
+    let text = format!("{} {}", category, material.unwrap_or_default()).to_lowercase();
+    words.iter().any(|w| text.contains(w))
+}
+
+/// Light garments that are only worn in warm weather, such as linen,
+/// shorts or sandals
+#[must_use]
+pub fn is_light(category: &str, material: Option<&str>) -> bool {
+    material.is_some_and(|material| mentions("", Some(material), COOL_MATERIALS))
+        || mentions(category, None, LIGHT_CATEGORIES)
+}
+
+/// Warm garments such as wool, down or coats
+#[must_use]
+pub fn is_warm(category: &str, material: Option<&str>) -> bool {
+    material.is_some_and(|material| mentions("", Some(material), WARM_MATERIALS))
+        || mentions(category, None, WARM_CATEGORIES)
+}
+
+/// Garments that stand up to rain, such as rain boots or waterproof shoes
+#[must_use]
+pub fn is_rain_proof(category: &str, material: Option<&str>) -> bool {
+    mentions(category, material, RAIN_PROOF)
+}
+
+/// Garments that rain damages, such as suede or canvas
+#[must_use]
+pub fn is_rain_sensitive(category: &str, material: Option<&str>) -> bool {
+    mentions(category, material, RAIN_SENSITIVE)
+}
+
+/// How a material suits a season: `Some(true)` when it suits it, `Some(false)`
+/// when it is clearly out of season and `None` when it is neutral or unknown
+#[must_use]
+pub fn material_suits(material: &str, season: Season) -> Option<bool> {
+    let material = material.trim().to_lowercase();
+    let contains_any = |words: &[&str]| words.iter().any(|w| material.contains(w));
+    if contains_any(WARM_MATERIALS) {
+        match season {
+            Season::Autumn | Season::Winter => Some(true),
+            Season::Summer => Some(false),
+            Season::Spring => None,
+        }
+    } else if contains_any(COOL_MATERIALS) {
+        match season {
+            Season::Summer => Some(true),
+            Season::Winter => Some(false),
+            Season::Spring | Season::Autumn => None,
+        }
+    } else {
+        None
+    }
+}
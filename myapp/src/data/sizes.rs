@@ -0,0 +1,389 @@
+//! Size normalization across JP/US/EU/UK sizing systems.
+//!
+//! Free-form size strings such as `M`, `9号`, `EU 38`, `US 8`, `26.5cm` or
+//! `W32 L30` are parsed into a [`NormalizedSize`] for the garment's
+//! [`SizeGroup`]. Apparel is normalized to a letter size, shoes to the foot
+//! length in centimeters and bottoms to the waist (and inseam) in
+//! centimeters plus the matching letter size.
+
+use serde::{Deserialize, Serialize};
+
+const CM_PER_INCH: f64 = 2.54;
+
+/// Sizing system a size was written in, or should be displayed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeSystem {
+    /// International letter sizes (XS, S, M, L, ...)
+    Letter,
+    Jp,
+    Us,
+    Eu,
+    Uk,
+    /// Centimeters (shoe length, waist)
+    Cm,
+    /// Waist/inseam in inches (W32 L30)
+    Inch,
+}
+
+impl SizeSystem {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Letter => "letter",
+            Self::Jp => "jp",
+            Self::Us => "us",
+            Self::Eu => "eu",
+            Self::Uk => "uk",
+            Self::Cm => "cm",
+            Self::Inch => "inch",
+        }
+    }
+}
+
+/// How a garment is sized, derived from its category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeGroup {
+    Apparel,
+    Bottoms,
+    Shoes,
+}
+
+/// English words naming a size group. Categories are matched word by word,
+/// so "bootcut jeans" is not a boot and "short sleeve shirt" not shorts.
+const SHOE_WORDS: &[&str] = &[
+    "shoe", "shoes", "sneaker", "sneakers", "boot", "boots", "sandal", "sandals", "loafer",
+    "loafers", "pump", "pumps", "heel", "heels", "footwear",
+];
+const BOTTOM_WORDS: &[&str] = &[
+    "bottom", "bottoms", "pant", "pants", "trouser", "trousers", "jean", "jeans", "shorts",
+    "skirt", "skirts", "slacks", "chino", "chinos", "sweatpants", "joggers", "leggings",
+];
+
+/// Japanese garment nouns. Japanese compounds are not split into words and
+/// end in the garment they name, so these match the end of a word:
+/// "ブーツカットジーンズ" is jeans and "靴下" is not a shoe.
+const SHOE_SUFFIXES: &[&str] = &[
+    "靴", "シューズ", "スニーカー", "ブーツ", "サンダル", "パンプス", "ローファー",
+];
+const BOTTOM_SUFFIXES: &[&str] = &[
+    "ボトム", "ボトムス", "パンツ", "ズボン", "ジーンズ", "デニム", "スカート", "スラックス",
+];
+
+impl SizeGroup {
+    /// Map a free-form clothes category onto a size group. The last word
+    /// naming a group wins, as in "leather ankle boots".
+    #[must_use]
+    pub fn from_category(category: &str) -> Self {
+        category
+            .trim()
+            .to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .rev()
+            .find_map(Self::from_word)
+            .unwrap_or(Self::Apparel)
+    }
+
+    fn from_word(word: &str) -> Option<Self> {
+        let ends_with_any = |suffixes: &[&str]| suffixes.iter().any(|s| word.ends_with(s));
+        if SHOE_WORDS.contains(&word) || ends_with_any(SHOE_SUFFIXES) {
+            Some(Self::Shoes)
+        } else if BOTTOM_WORDS.contains(&word) || ends_with_any(BOTTOM_SUFFIXES) {
+            Some(Self::Bottoms)
+        } else {
+            None
+        }
+    }
+}
+
+/// International letter size, ordered from smallest to largest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LetterSize {
+    #[serde(rename = "XXS")]
+    Xxs,
+    #[serde(rename = "XS")]
+    Xs,
+    S,
+    M,
+    L,
+    #[serde(rename = "XL")]
+    Xl,
+    #[serde(rename = "XXL")]
+    Xxl,
+    #[serde(rename = "XXXL")]
+    Xxxl,
+}
+
+impl LetterSize {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Xxs => "XXS",
+            Self::Xs => "XS",
+            Self::S => "S",
+            Self::M => "M",
+            Self::L => "L",
+            Self::Xl => "XL",
+            Self::Xxl => "XXL",
+            Self::Xxxl => "XXXL",
+        }
+    }
+
+    /// Parse a letter size, including Japanese variants such as `LL` or `3L`
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_uppercase().as_str() {
+            "XXS" | "2XS" | "SS" => Some(Self::Xxs),
+            "XS" => Some(Self::Xs),
+            "S" => Some(Self::S),
+            "M" => Some(Self::M),
+            "L" => Some(Self::L),
+            "XL" | "LL" => Some(Self::Xl),
+            "XXL" | "2XL" | "3L" => Some(Self::Xxl),
+            "XXXL" | "3XL" | "4L" => Some(Self::Xxxl),
+            _ => None,
+        }
+    }
+
+    fn from_waist_cm(waist: f64) -> Self {
+        APPAREL
+            .iter()
+            .find(|row| waist < row.waist_cm_below)
+            .map_or(Self::Xxxl, |row| row.letter)
+    }
+}
+
+/// One row of the apparel conversion table. Numeric sizes are inclusive
+/// ranges; the first value of a range is used for display.
+struct ApparelRow {
+    letter: LetterSize,
+    jp: u8,
+    us: (u8, u8),
+    uk: (u8, u8),
+    eu: (u8, u8),
+    waist_cm_below: f64,
+}
+
+const APPAREL: &[ApparelRow] = &[
+    ApparelRow { letter: LetterSize::Xxs, jp: 3, us: (0, 0), uk: (2, 2), eu: (30, 30), waist_cm_below: 61.0 },
+    ApparelRow { letter: LetterSize::Xs, jp: 5, us: (1, 2), uk: (4, 6), eu: (32, 34), waist_cm_below: 66.0 },
+    ApparelRow { letter: LetterSize::S, jp: 7, us: (4, 6), uk: (8, 10), eu: (36, 38), waist_cm_below: 72.0 },
+    ApparelRow { letter: LetterSize::M, jp: 9, us: (8, 10), uk: (12, 14), eu: (40, 42), waist_cm_below: 78.0 },
+    ApparelRow { letter: LetterSize::L, jp: 11, us: (12, 14), uk: (16, 18), eu: (44, 46), waist_cm_below: 84.0 },
+    ApparelRow { letter: LetterSize::Xl, jp: 13, us: (16, 18), uk: (20, 22), eu: (48, 50), waist_cm_below: 90.0 },
+    ApparelRow { letter: LetterSize::Xxl, jp: 15, us: (20, 22), uk: (24, 26), eu: (52, 54), waist_cm_below: 98.0 },
+    ApparelRow { letter: LetterSize::Xxxl, jp: 17, us: (24, 26), uk: (28, 30), eu: (56, 58), waist_cm_below: f64::INFINITY },
+];
+
+/// A size parsed into a comparable structure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NormalizedSize {
+    pub group: SizeGroup,
+    /// System the size was originally written in
+    pub system: SizeSystem,
+    pub letter: Option<LetterSize>,
+    /// Foot length for shoes, waist for bottoms
+    pub cm: Option<f64>,
+    pub inseam_cm: Option<f64>,
+}
+
+impl NormalizedSize {
+    fn letter(group: SizeGroup, system: SizeSystem, letter: LetterSize) -> Self {
+        Self { group, system, letter: Some(letter), cm: None, inseam_cm: None }
+    }
+
+    /// Render the size in the requested system, `None` when the system does
+    /// not apply to this kind of garment
+    #[must_use]
+    pub fn display(&self, system: SizeSystem) -> Option<String> {
+        match self.group {
+            SizeGroup::Shoes => {
+                let cm = self.cm?;
+                match system {
+                    SizeSystem::Cm | SizeSystem::Jp => Some(format!("{cm:.1}cm")),
+                    SizeSystem::Us => Some(format!("US {}", half_step(cm - 18.0))),
+                    SizeSystem::Uk => Some(format!("UK {}", half_step(cm - 19.0))),
+                    SizeSystem::Eu => Some(format!("EU {}", half_step((cm + 1.5) * 1.5))),
+                    SizeSystem::Letter | SizeSystem::Inch => None,
+                }
+            }
+            SizeGroup::Bottoms if matches!(system, SizeSystem::Inch | SizeSystem::Cm) => {
+                let waist = self.cm?;
+                if system == SizeSystem::Cm {
+                    return Some(format!("W{waist:.0}cm"));
+                }
+                let waist = (waist / CM_PER_INCH).round();
+                Some(match self.inseam_cm {
+                    Some(inseam) => format!("W{waist} L{}", (inseam / CM_PER_INCH).round()),
+                    None => format!("W{waist}"),
+                })
+            }
+            _ => {
+                let row = APPAREL.iter().find(|row| Some(row.letter) == self.letter)?;
+                match system {
+                    SizeSystem::Letter => Some(row.letter.as_str().to_string()),
+                    SizeSystem::Jp => Some(format!("{}号", row.jp)),
+                    SizeSystem::Us => Some(format!("US {}", row.us.0)),
+                    SizeSystem::Uk => Some(format!("UK {}", row.uk.0)),
+                    SizeSystem::Eu => Some(format!("EU {}", row.eu.0)),
+                    SizeSystem::Cm | SizeSystem::Inch => None,
+                }
+            }
+        }
+    }
+
+    /// Whether a garment of this size fits someone wearing `wanted`
+    #[must_use]
+    pub fn fits(&self, wanted: &Self) -> bool {
+        match (self.group, self.cm, wanted.cm) {
+            (SizeGroup::Shoes, Some(cm), Some(wanted)) => (cm - wanted).abs() <= 0.25,
+            (SizeGroup::Bottoms, Some(cm), Some(wanted)) => (cm - wanted).abs() <= 2.0,
+            _ => self.letter.is_some() && self.letter == wanted.letter,
+        }
+    }
+}
+
+fn half_step(value: f64) -> String {
+    let rounded = (value * 2.0).round() / 2.0;
+    if rounded.fract() == 0.0 {
+        format!("{rounded:.0}")
+    } else {
+        format!("{rounded:.1}")
+    }
+}
+
+/// Parse a free-form size string for a garment of the given group
+#[must_use]
+pub fn parse(value: &str, group: SizeGroup) -> Option<NormalizedSize> {
+    let value = value.trim().to_uppercase().replace('　', " ");
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Some(letter) = LetterSize::parse(value) {
+        return Some(NormalizedSize::letter(group, SizeSystem::Letter, letter));
+    }
+
+    match group {
+        SizeGroup::Shoes => parse_shoe(value),
+        SizeGroup::Bottoms => parse_bottoms(value),
+        SizeGroup::Apparel => parse_apparel(value, group),
+    }
+}
+
+/// Split an optional system prefix (`EU 38`) or suffix (`9号`, `26.5cm`)
+/// from the numeric part.
+fn split_system(value: &str) -> (Option<SizeSystem>, &str) {
+    for (prefix, system) in [
+        ("JP", SizeSystem::Jp),
+        ("US", SizeSystem::Us),
+        ("EU", SizeSystem::Eu),
+        ("UK", SizeSystem::Uk),
+    ] {
+        if let Some(rest) = value.strip_prefix(prefix) {
+            return (Some(system), rest.trim());
+        }
+    }
+    if let Some(rest) = value.strip_suffix("号") {
+        return (Some(SizeSystem::Jp), rest.trim());
+    }
+    if let Some(rest) = value.strip_suffix("CM") {
+        return (Some(SizeSystem::Cm), rest.trim());
+    }
+    (None, value)
+}
+
+fn parse_apparel(value: &str, group: SizeGroup) -> Option<NormalizedSize> {
+    let (system, number) = split_system(value);
+    if let Some(letter) = LetterSize::parse(number) {
+        return Some(NormalizedSize::letter(group, system.unwrap_or(SizeSystem::Letter), letter));
+    }
+    let number: u8 = number.parse().ok()?;
+    let system = system.or(match number {
+        3..=19 if number % 2 == 1 => Some(SizeSystem::Jp),
+        30..=58 => Some(SizeSystem::Eu),
+        _ => None,
+    })?;
+    let in_range = |(low, high): (u8, u8)| (low..=high).contains(&number);
+    let row = APPAREL.iter().find(|row| match system {
+        SizeSystem::Jp => row.jp == number,
+        SizeSystem::Us => in_range(row.us),
+        SizeSystem::Uk => in_range(row.uk),
+        SizeSystem::Eu => in_range(row.eu),
+        _ => false,
+    })?;
+    Some(NormalizedSize::letter(group, system, row.letter))
+}
+
+fn parse_shoe(value: &str) -> Option<NormalizedSize> {
+    let (system, number) = split_system(value);
+    let number: f64 = number.parse().ok()?;
+    let system = system.or(if (20.0..=32.0).contains(&number) {
+        Some(SizeSystem::Cm)
+    } else if (34.0..=50.0).contains(&number) {
+        Some(SizeSystem::Eu)
+    } else if (3.0..=16.0).contains(&number) {
+        Some(SizeSystem::Us)
+    } else {
+        None
+    })?;
+    let cm = match system {
+        SizeSystem::Cm | SizeSystem::Jp => number,
+        SizeSystem::Us => number + 18.0,
+        SizeSystem::Uk => number + 19.0,
+        SizeSystem::Eu => number / 1.5 - 1.5,
+        SizeSystem::Letter | SizeSystem::Inch => return None,
+    };
+    Some(NormalizedSize {
+        group: SizeGroup::Shoes,
+        system,
+        letter: None,
+        cm: Some((cm * 2.0).round() / 2.0),
+        inseam_cm: None,
+    })
+}
+
+fn parse_bottoms(value: &str) -> Option<NormalizedSize> {
+    // W32 L30 / W32/L30 / 32x30 / 32/30 / W76cm
+    let parts: Vec<&str> = value
+        .split(|c: char| c == '/' || c == 'X' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect();
+    let waist_part = parts.first()?.trim_start_matches('W');
+    let inseam_part = parts.get(1).map(|part| part.trim_start_matches('L'));
+
+    // Sizes with an explicit system (EU 38, US 8, 9号) use the apparel table
+    if let (Some(_), Some(size)) = (split_system(value).0, parse_apparel(value, SizeGroup::Bottoms)) {
+        return Some(size);
+    }
+
+    let (system, waist) = match waist_part.strip_suffix("CM") {
+        Some(waist) => (SizeSystem::Cm, waist.parse::<f64>().ok()?),
+        None => {
+            let waist = waist_part.parse::<f64>().ok()?;
+            // Japanese tags give the waist in cm, western tags in inches
+            if waist >= 50.0 {
+                (SizeSystem::Cm, waist)
+            } else {
+                (SizeSystem::Inch, waist * CM_PER_INCH)
+            }
+        }
+    };
+    let inseam = match inseam_part {
+        Some(part) => {
+            let inseam = part.trim_end_matches("CM").parse::<f64>().ok()?;
+            Some(if system == SizeSystem::Inch { inseam * CM_PER_INCH } else { inseam })
+        }
+        None => None,
+    };
+
+    Some(NormalizedSize {
+        group: SizeGroup::Bottoms,
+        system,
+        letter: Some(LetterSize::from_waist_cm(waist)),
+        cm: Some(waist),
+        inseam_cm: inseam,
+    })
+}
@@ -0,0 +1,106 @@
+//! Outfit slots.
+//!
+//! Every garment fills one slot of an outfit, derived from its free-form
+//! category (English or Japanese). Slots also order the items of an outfit
+//! from head to toe, followed by accessories and bags.
+
+use serde::{Deserialize, Serialize};
+
+use super::sizes::SizeGroup;
+
+/// Part of an outfit a garment fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Slot {
+    Head,
+    Top,
+    #[serde(alias = "outerwear")]
+    Outer,
+    Bottom,
+    Shoes,
+    Accessory,
+    Bag,
+}
+
+impl Slot {
+    pub const ALL: [Self; 7] = [
+        Self::Head,
+        Self::Top,
+        Self::Outer,
+        Self::Bottom,
+        Self::Shoes,
+        Self::Accessory,
+        Self::Bag,
+    ];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::Top => "top",
+            Self::Outer => "outer",
+            Self::Bottom => "bottom",
+            Self::Shoes => "shoes",
+            Self::Accessory => "accessory",
+            Self::Bag => "bag",
+        }
+    }
+
+    /// Parse a slot name. `outerwear` is accepted for the outer slot.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_lowercase();
+        if value == "outerwear" {
+            return Some(Self::Outer);
+        }
+        Self::ALL.into_iter().find(|slot| slot.as_str() == value)
+    }
+
+    /// Slot filled by a garment of `category`, `None` for unknown categories
+    #[must_use]
+    pub fn from_category(category: &str) -> Option<Self> {
+        match SizeGroup::from_category(category) {
+            SizeGroup::Shoes => return Some(Self::Shoes),
+            SizeGroup::Bottoms => return Some(Self::Bottom),
+            SizeGroup::Apparel => {}
+        }
+
+        let category = category.trim().to_lowercase();
+        let contains_any = |words: &[&str]| words.iter().any(|w| category.contains(w));
+        if contains_any(&[
+            "bag", "backpack", "tote", "clutch", "pouch", "バッグ", "リュック", "ポーチ",
+        ]) {
+            Some(Self::Bag)
+        } else if contains_any(&[
+            "hat", "cap", "beanie", "beret", "帽子", "キャップ", "ハット", "ニット帽", "ベレー",
+        ]) {
+            Some(Self::Head)
+        } else if contains_any(&[
+            "outer", "coat", "jacket", "parka", "blazer", "cardigan", "アウター", "コート",
+            "ジャケット", "ブルゾン", "カーディガン",
+        ]) {
+            Some(Self::Outer)
+        } else if contains_any(&[
+            "top", "shirt", "tee", "blouse", "sweater", "knit", "hoodie", "polo", "トップス",
+            "シャツ", "ブラウス", "ニット", "セーター", "カットソー", "パーカー",
+        ]) {
+            Some(Self::Top)
+        } else if contains_any(&[
+            "accessor", "necklace", "earring", "bracelet", "ring", "watch", "belt", "scarf",
+            "stole", "tie", "glasses", "glove", "アクセサリー", "ネックレス", "ピアス",
+            "イヤリング", "ブレスレット", "指輪", "時計", "ベルト", "マフラー", "ストール",
+            "ネクタイ", "メガネ", "サングラス", "手袋",
+        ]) {
+            Some(Self::Accessory)
+        } else {
+            None
+        }
+    }
+
+    /// Whether a garment of `category` may be placed in this slot. Garments
+    /// of unknown categories fit any slot.
+    #[must_use]
+    pub fn accepts(&self, category: &str) -> bool {
+        Self::from_category(category).is_none_or(|slot| slot == *self)
+    }
+}
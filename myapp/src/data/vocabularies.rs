@@ -0,0 +1,285 @@
+//! Fixed vocabularies of coordinate metadata.
+//!
+//! Seasons, occasions and styles are stored by stable keys. Parsing accepts
+//! the keys in any case as well as common English and Japanese names;
+//! display labels come from the `assets/i18n` Fluent files, under message
+//! IDs such as `occasion-smart-casual`.
+
+use serde::{Deserialize, Serialize};
+
+use super::seasons::Season;
+
+/// A closed set of values identified by stable keys
+pub trait Vocabulary: Sized + Copy + 'static {
+    /// Name of the vocabulary, also the prefix of its Fluent message IDs
+    const NAME: &'static str;
+
+    fn all() -> &'static [Self];
+
+    /// Stable key stored in the database
+    fn key(&self) -> &'static str;
+
+    /// Parse a key or a known name of a value
+    fn parse(value: &str) -> Option<Self>;
+
+    /// Fluent message ID of the label
+    fn label_id(&self) -> String {
+        format!("{}-{}", Self::NAME, self.key().replace('_', "-"))
+    }
+}
+
+/// Lowercase a value and turn spaces and dashes into underscores, so that
+/// `Smart Casual` matches the key `smart_casual`
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Find the value whose key or one of whose names matches
+fn lookup<T: Vocabulary>(value: &str, names: fn(T) -> &'static [&'static str]) -> Option<T> {
+    let value = normalize(value);
+    T::all().iter().copied().find(|item| {
+        item.key() == value || names(*item).iter().any(|name| normalize(name) == value)
+    })
+}
+
+/// What a coordinate is worn for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "String")]
+pub enum Occasion {
+    Casual,
+    Work,
+    Formal,
+    Party,
+    Date,
+    Sports,
+    Travel,
+    Outdoor,
+    Home,
+    Other,
+}
+
+impl Occasion {
+    pub const ALL: [Self; 10] = [
+        Self::Casual,
+        Self::Work,
+        Self::Formal,
+        Self::Party,
+        Self::Date,
+        Self::Sports,
+        Self::Travel,
+        Self::Outdoor,
+        Self::Home,
+        Self::Other,
+    ];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Casual => "casual",
+            Self::Work => "work",
+            Self::Formal => "formal",
+            Self::Party => "party",
+            Self::Date => "date",
+            Self::Sports => "sports",
+            Self::Travel => "travel",
+            Self::Outdoor => "outdoor",
+            Self::Home => "home",
+            Self::Other => "other",
+        }
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Casual => &[
+                "everyday",
+                "daily",
+                "weekend",
+                "カジュアル",
+                "普段",
+                "普段着",
+                "休日",
+            ],
+            Self::Work => &["office", "business", "仕事", "オフィス", "ビジネス", "通勤"],
+            Self::Formal => &["ceremony", "wedding", "フォーマル", "冠婚葬祭", "結婚式"],
+            Self::Party => &["パーティー", "パーティ"],
+            Self::Date => &["デート"],
+            Self::Sports => &["sport", "gym", "workout", "スポーツ", "ジム"],
+            Self::Travel => &["trip", "vacation", "旅行"],
+            Self::Outdoor => &["outdoors", "hiking", "camping", "アウトドア"],
+            Self::Home => &["lounge", "loungewear", "部屋着", "家"],
+            Self::Other => &["その他"],
+        }
+    }
+
+    /// Parse an occasion key or a known English or Japanese name
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        lookup(value, Self::names)
+    }
+
+    /// Occasion of free text: `None` when blank, `Other` when unknown
+    #[must_use]
+    pub fn from_text(value: &str) -> Option<Self> {
+        (!value.trim().is_empty()).then(|| Self::parse(value).unwrap_or(Self::Other))
+    }
+}
+
+impl TryFrom<String> for Occasion {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("unknown occasion: {value}"))
+    }
+}
+
+impl Vocabulary for Occasion {
+    const NAME: &'static str = "occasion";
+
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn key(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::parse(value)
+    }
+}
+
+/// Overall look of a coordinate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", try_from = "String")]
+pub enum Style {
+    Casual,
+    SmartCasual,
+    Business,
+    Formal,
+    Street,
+    Minimal,
+    Classic,
+    Sporty,
+    Feminine,
+    Other,
+}
+
+impl Style {
+    pub const ALL: [Self; 10] = [
+        Self::Casual,
+        Self::SmartCasual,
+        Self::Business,
+        Self::Formal,
+        Self::Street,
+        Self::Minimal,
+        Self::Classic,
+        Self::Sporty,
+        Self::Feminine,
+        Self::Other,
+    ];
+
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Casual => "casual",
+            Self::SmartCasual => "smart_casual",
+            Self::Business => "business",
+            Self::Formal => "formal",
+            Self::Street => "street",
+            Self::Minimal => "minimal",
+            Self::Classic => "classic",
+            Self::Sporty => "sporty",
+            Self::Feminine => "feminine",
+            Self::Other => "other",
+        }
+    }
+
+    fn names(self) -> &'static [&'static str] {
+        match self {
+            Self::Casual => &["カジュアル"],
+            Self::SmartCasual => &["smart", "きれいめ", "キレイめ"],
+            Self::Business => &["ビジネス"],
+            Self::Formal => &["フォーマル"],
+            Self::Street => &["streetwear", "ストリート"],
+            Self::Minimal => &["minimalist", "simple", "シンプル", "ミニマル"],
+            Self::Classic => &["traditional", "trad", "preppy", "クラシック", "トラッド"],
+            Self::Sporty => &["athleisure", "スポーティ", "スポーティー"],
+            Self::Feminine => &["girly", "フェミニン", "ガーリー"],
+            Self::Other => &["その他"],
+        }
+    }
+
+    /// Parse a style key or a known English or Japanese name
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        lookup(value, Self::names)
+    }
+
+    /// Style of free text: `None` when blank, `Other` when unknown
+    #[must_use]
+    pub fn from_text(value: &str) -> Option<Self> {
+        (!value.trim().is_empty()).then(|| Self::parse(value).unwrap_or(Self::Other))
+    }
+}
+
+impl TryFrom<String> for Style {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value).ok_or_else(|| format!("unknown style: {value}"))
+    }
+}
+
+impl Vocabulary for Style {
+    const NAME: &'static str = "style";
+
+    fn all() -> &'static [Self] {
+        &Self::ALL
+    }
+
+    fn key(&self) -> &'static str {
+        self.as_str()
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::parse(value)
+    }
+}
+
+/// A value of a vocabulary with its translated label
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VocabularyEntry {
+    pub key: String,
+    pub label: String,
+}
+
+/// Every vocabulary of coordinate metadata, labelled in one language
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Vocabularies {
+    pub language: String,
+    pub seasons: Vec<VocabularyEntry>,
+    pub occasions: Vec<VocabularyEntry>,
+    pub styles: Vec<VocabularyEntry>,
+}
+
+impl Vocabularies {
+    /// Label every value with `label`, which receives the Fluent message ID
+    #[must_use]
+    pub fn new(language: &str, label: impl Fn(&str) -> String) -> Self {
+        fn entries<T: Vocabulary>(label: &impl Fn(&str) -> String) -> Vec<VocabularyEntry> {
+            T::all()
+                .iter()
+                .map(|item| VocabularyEntry {
+                    key: item.key().to_string(),
+                    label: label(&item.label_id()),
+                })
+                .collect()
+        }
+        Self {
+            language: language.to_string(),
+            seasons: entries::<Season>(&label),
+            occasions: entries::<Occasion>(&label),
+            styles: entries::<Style>(&label),
+        }
+    }
+}
@@ -8,8 +8,8 @@ use loco_rs::{
 };
 use tracing::info;
 
-const I18N_DIR: &str = "assets/i18n";
-const I18N_SHARED: &str = "assets/i18n/shared.ftl";
+use crate::common::i18n::{I18N_DIR, I18N_SHARED};
+
 #[allow(clippy::module_name_repetitions)]
 pub struct ViewEngineInitializer;
 
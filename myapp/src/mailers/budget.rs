@@ -0,0 +1,49 @@
+// budget mailer
+#![allow(non_upper_case_globals)]
+
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::models::{budgets::BudgetStatus, users};
+
+static exceeded: Dir<'_> = include_dir!("src/mailers/budget/exceeded");
+
+#[allow(clippy::module_name_repetitions)]
+pub struct BudgetMailer {}
+impl Mailer for BudgetMailer {}
+impl BudgetMailer {
+    /// Sending an alert that spending went over a monthly budget
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn send_exceeded(
+        ctx: &AppContext,
+        user: &users::Model,
+        status: &BudgetStatus,
+    ) -> Result<()> {
+        Self::mail_template(
+            ctx,
+            &exceeded,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "category": status.category.as_deref().unwrap_or("clothes"),
+                  "month": status.month.format("%Y-%m").to_string(),
+                  "spent": status.spent.to_string(),
+                  "limit": status.limit.to_string(),
+                  "reportUrl": format!(
+                      "{}/api/spending/user/{}",
+                      ctx.config.server.full_url(),
+                      user.id
+                  ),
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
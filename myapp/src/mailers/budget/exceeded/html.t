@@ -0,0 +1,10 @@
+;<html>
+
+<body>
+  Hi {{name}},
+  You spent {{spent}} on {{category}} in {{month}}, over your budget of {{limit}}.
+  <a href="{{reportUrl}}">See your spending</a>
+  <p>Best regards,<br>The Loco Team</p>
+</body>
+
+</html>
@@ -0,0 +1 @@
+Your {{month}} budget for {{category}} is exceeded
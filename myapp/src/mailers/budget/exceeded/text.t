@@ -0,0 +1,5 @@
+Hi {{name}},
+You spent {{spent}} on {{category}} in {{month}}, over your budget of {{limit}}.
+
+See your spending:
+{{reportUrl}}
@@ -0,0 +1,48 @@
+// export mailer
+#![allow(non_upper_case_globals)]
+
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::models::{users, wardrobe_exports};
+
+static ready: Dir<'_> = include_dir!("src/mailers/export/ready");
+
+#[allow(clippy::module_name_repetitions)]
+pub struct ExportMailer {}
+impl Mailer for ExportMailer {}
+impl ExportMailer {
+    /// Sending the time-limited download link of a finished export
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn send_ready(
+        ctx: &AppContext,
+        user: &users::Model,
+        export: &wardrobe_exports::Model,
+    ) -> Result<()> {
+        let token = export.download_token.clone().unwrap_or_default();
+        Self::mail_template(
+            ctx,
+            &ready,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals: json!({
+                  "name": user.name,
+                  "format": export.format,
+                  "downloadUrl": format!(
+                      "{}/api/exports/{}/download?token={token}",
+                      ctx.config.server.full_url(),
+                      export.pid
+                  ),
+                  "expiresAt": export.expires_at.map(|at| at.to_rfc3339()),
+                }),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+}
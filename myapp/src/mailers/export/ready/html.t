@@ -0,0 +1,11 @@
+;<html>
+
+<body>
+  Hi {{name}},
+  Your wardrobe export ({{format}}) is ready. Download it with the link below:
+  <a href="{{downloadUrl}}">Download Your Wardrobe</a>
+  <p>The link expires at {{expiresAt}}.</p>
+  <p>Best regards,<br>The Loco Team</p>
+</body>
+
+</html>
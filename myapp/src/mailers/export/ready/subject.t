@@ -0,0 +1 @@
+Your wardrobe export is ready
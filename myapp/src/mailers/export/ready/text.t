@@ -0,0 +1,6 @@
+Hi {{name}},
+Your wardrobe export ({{format}}) is ready. Download it with the link below:
+
+{{downloadUrl}}
+
+The link expires at {{expiresAt}}.
@@ -1 +1,3 @@
 pub mod auth;
+pub mod export;
+pub mod budget;
@@ -21,14 +21,43 @@ pub struct Model {
     pub in_stock: bool,
     pub stock_quantity: i32,
     pub image_url: Option<String>,
+    pub primary_color_hex: Option<String>,
+    pub primary_color_family: Option<String>,
+    pub secondary_color_hex: Option<String>,
+    pub secondary_color_family: Option<String>,
+    pub size_system: Option<String>,
+    pub size_letter: Option<String>,
+    #[sea_orm(column_type = "Decimal(Some((5, 1)))", nullable)]
+    pub size_cm: Option<Decimal>,
+    #[sea_orm(column_type = "Decimal(Some((5, 1)))", nullable)]
+    pub inseam_cm: Option<Decimal>,
+    pub lent_to: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
+    pub lifecycle_state: String,
+    pub condition: Option<String>,
+    pub state_changed_at: Option<DateTimeWithTimeZone>,
+    pub currency: String,
+    pub purchase_date: Option<Date>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
+    pub receipt_path: Option<String>,
+    pub receipt_content_type: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::clothes_coordinates::Entity")]
     ClothesCoordinates,
+    #[sea_orm(has_many = "super::clothes_state_changes::Entity")]
+    ClothesStateChanges,
+    #[sea_orm(has_many = "super::inventory_movements::Entity")]
+    InventoryMovements,
+    #[sea_orm(has_many = "super::trip_packing_items::Entity")]
+    TripPackingItems,
+    #[sea_orm(has_many = "super::wear_events::Entity")]
+    WearEvents,
 }
 
 impl Related<super::clothes_coordinates::Entity> for Entity {
@@ -36,3 +65,27 @@ impl Related<super::clothes_coordinates::Entity> for Entity {
         Relation::ClothesCoordinates.def()
     }
 }
+
+impl Related<super::clothes_state_changes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ClothesStateChanges.def()
+    }
+}
+
+impl Related<super::inventory_movements::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::InventoryMovements.def()
+    }
+}
+
+impl Related<super::trip_packing_items::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TripPackingItems.def()
+    }
+}
+
+impl Related<super::wear_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WearEvents.def()
+    }
+}
@@ -3,17 +3,26 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
 #[sea_orm(table_name = "clothes_coordinates")]
 pub struct Model {
     #[sea_orm(primary_key)]
     pub id: i32,
     pub clothes_id: i32,
     pub coordinate_id: i32,
-    pub position: Option<String>,
     pub notes: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub slot: Option<String>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub x: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub y: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub scale: Option<f64>,
+    #[sea_orm(column_type = "Double", nullable)]
+    pub rotation: Option<f64>,
+    pub z_index: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -13,19 +13,28 @@ pub struct Model {
     pub name: String,
     pub description: Option<String>,
     pub occasion: Option<String>,
-    pub season: Option<String>,
     pub style: Option<String>,
     pub user_id: i32,
     pub is_favorite: bool,
     pub image_url: Option<String>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub seasons: Json,
+    pub deleted_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(has_many = "super::clothes_coordinates::Entity")]
     ClothesCoordinates,
+    #[sea_orm(has_many = "super::coordinate_tombstones::Entity")]
+    CoordinateTombstones,
+    #[sea_orm(has_many = "super::coordinate_versions::Entity")]
+    CoordinateVersions,
+    #[sea_orm(has_many = "super::outfit_feedback::Entity")]
+    OutfitFeedback,
+    #[sea_orm(has_many = "super::outfit_plans::Entity")]
+    OutfitPlans,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::UserId",
@@ -34,6 +43,8 @@ pub enum Relation {
         on_delete = "Cascade"
     )]
     Users,
+    #[sea_orm(has_many = "super::wear_events::Entity")]
+    WearEvents,
 }
 
 impl Related<super::clothes_coordinates::Entity> for Entity {
@@ -42,8 +53,38 @@ impl Related<super::clothes_coordinates::Entity> for Entity {
     }
 }
 
+impl Related<super::coordinate_tombstones::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CoordinateTombstones.def()
+    }
+}
+
+impl Related<super::coordinate_versions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CoordinateVersions.def()
+    }
+}
+
+impl Related<super::outfit_feedback::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitFeedback.def()
+    }
+}
+
+impl Related<super::outfit_plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitPlans.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
     }
 }
+
+impl Related<super::wear_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WearEvents.def()
+    }
+}
@@ -2,9 +2,25 @@
 
 pub mod prelude;
 
+pub mod budgets;
+pub mod calendar_feeds;
 pub mod clothes;
 pub mod clothes_coordinates;
+pub mod clothes_imports;
+pub mod clothes_state_changes;
+pub mod coordinate_rules;
+pub mod coordinate_tombstones;
+pub mod coordinate_versions;
 pub mod coordinates;
+pub mod exchange_rates;
+pub mod inventory_movements;
+pub mod notifications;
+pub mod outfit_feedback;
+pub mod outfit_plans;
 pub mod passkeys;
+pub mod trip_packing_items;
+pub mod trips;
 pub mod users;
+pub mod wardrobe_exports;
+pub mod wear_events;
 pub mod webauthn_sessions;
@@ -1,8 +1,24 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.12
 
+pub use super::budgets::Entity as Budgets;
+pub use super::calendar_feeds::Entity as CalendarFeeds;
 pub use super::clothes::Entity as Clothes;
 pub use super::clothes_coordinates::Entity as ClothesCoordinates;
+pub use super::clothes_imports::Entity as ClothesImports;
+pub use super::clothes_state_changes::Entity as ClothesStateChanges;
+pub use super::coordinate_rules::Entity as CoordinateRules;
+pub use super::coordinate_tombstones::Entity as CoordinateTombstones;
+pub use super::coordinate_versions::Entity as CoordinateVersions;
 pub use super::coordinates::Entity as Coordinates;
+pub use super::exchange_rates::Entity as ExchangeRates;
+pub use super::inventory_movements::Entity as InventoryMovements;
+pub use super::notifications::Entity as Notifications;
+pub use super::outfit_feedback::Entity as OutfitFeedback;
+pub use super::outfit_plans::Entity as OutfitPlans;
 pub use super::passkeys::Entity as Passkeys;
+pub use super::trip_packing_items::Entity as TripPackingItems;
+pub use super::trips::Entity as Trips;
 pub use super::users::Entity as Users;
+pub use super::wardrobe_exports::Entity as WardrobeExports;
+pub use super::wear_events::Entity as WearEvents;
 pub use super::webauthn_sessions::Entity as WebauthnSessions;
@@ -20,14 +20,51 @@ pub struct Model {
     pub name: String,
     pub reset_token: Option<String>,
     pub reset_sent_at: Option<DateTimeWithTimeZone>,
+    pub home_currency: String,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::budgets::Entity")]
+    Budgets,
+    #[sea_orm(has_many = "super::calendar_feeds::Entity")]
+    CalendarFeeds,
+    #[sea_orm(has_one = "super::coordinate_rules::Entity")]
+    CoordinateRules,
     #[sea_orm(has_many = "super::coordinates::Entity")]
     Coordinates,
+    #[sea_orm(has_many = "super::notifications::Entity")]
+    Notifications,
+    #[sea_orm(has_many = "super::outfit_feedback::Entity")]
+    OutfitFeedback,
+    #[sea_orm(has_many = "super::outfit_plans::Entity")]
+    OutfitPlans,
     #[sea_orm(has_many = "super::passkeys::Entity")]
     Passkeys,
+    #[sea_orm(has_many = "super::trips::Entity")]
+    Trips,
+    #[sea_orm(has_many = "super::wardrobe_exports::Entity")]
+    WardrobeExports,
+    #[sea_orm(has_many = "super::wear_events::Entity")]
+    WearEvents,
+}
+
+impl Related<super::budgets::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budgets.def()
+    }
+}
+
+impl Related<super::calendar_feeds::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CalendarFeeds.def()
+    }
+}
+
+impl Related<super::coordinate_rules::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::CoordinateRules.def()
+    }
 }
 
 impl Related<super::coordinates::Entity> for Entity {
@@ -36,8 +73,44 @@ impl Related<super::coordinates::Entity> for Entity {
     }
 }
 
+impl Related<super::notifications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Notifications.def()
+    }
+}
+
+impl Related<super::outfit_feedback::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitFeedback.def()
+    }
+}
+
+impl Related<super::outfit_plans::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::OutfitPlans.def()
+    }
+}
+
 impl Related<super::passkeys::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Passkeys.def()
     }
 }
+
+impl Related<super::trips::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Trips.def()
+    }
+}
+
+impl Related<super::wardrobe_exports::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WardrobeExports.def()
+    }
+}
+
+impl Related<super::wear_events::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::WearEvents.def()
+    }
+}
@@ -0,0 +1,220 @@
+//! Monthly spending budgets.
+//!
+//! A user has at most one budget for all purchases and one per category.
+//! Each budget is checked against the purchases of every month, and alerts
+//! once per month it is exceeded in.
+
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::budgets::{self, ActiveModel, Entity, Model};
+use super::{_entities::users, coordinate_rules::FieldError};
+use crate::data::money::{Currency, Money};
+
+/// A budget to set, replacing the user's budget for the same category
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BudgetParams {
+    /// Budget for one category, for all purchases when omitted
+    pub category: Option<String>,
+    /// Limit per month as a decimal string such as `"150.00"`
+    pub amount: String,
+    /// The user's home currency when omitted
+    pub currency: Option<Currency>,
+}
+
+impl BudgetParams {
+    /// The category, `None` when blank
+    #[must_use]
+    pub fn category(&self) -> Option<String> {
+        self.category
+            .as_deref()
+            .map(str::trim)
+            .filter(|category| !category.is_empty())
+            .map(ToString::to_string)
+    }
+
+    /// The limit per month, in `currency` or the user's home currency
+    ///
+    /// # Errors
+    ///
+    /// With a message for the `amount` field when the amount is invalid
+    pub fn limit(&self, home_currency: Currency) -> Result<Money, String> {
+        Money::parse(&self.amount, self.currency.unwrap_or(home_currency))
+    }
+
+    /// Field-level problems with this budget
+    #[must_use]
+    pub fn problems(&self, home_currency: Currency) -> Vec<FieldError> {
+        match self.limit(home_currency) {
+            Ok(limit) if limit.amount.is_zero() => {
+                vec![FieldError::new("amount", "must be more than zero")]
+            }
+            Ok(_) => Vec::new(),
+            Err(message) => vec![FieldError::new("amount", message)],
+        }
+    }
+}
+
+/// How a budget stands in one month
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    pub budget_pid: Uuid,
+    pub category: Option<String>,
+    /// First day of the month
+    pub month: Date,
+    pub limit: Money,
+    /// Purchases the budget covers in the month, in the budget's currency
+    pub spent: Money,
+    pub exceeded: bool,
+}
+
+impl BudgetStatus {
+    /// One-line description for alerts, such as
+    /// `"Spent 180.00 EUR on tops in 2025-06, over the 150.00 EUR budget"`
+    #[must_use]
+    pub fn message(&self) -> String {
+        format!(
+            "Spent {} on {} in {}, {} the {} budget",
+            self.spent,
+            self.category.as_deref().unwrap_or("clothes"),
+            self.month.format("%Y-%m"),
+            if self.exceeded { "over" } else { "within" },
+            self.limit
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut this = self;
+        if insert {
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+        } else if this.updated_at.is_unchanged() {
+            this.updated_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(this)
+    }
+}
+
+impl Model {
+    /// Limit per month
+    #[must_use]
+    pub fn limit(&self) -> Money {
+        Money::new(
+            self.amount,
+            Currency::parse(&self.currency).unwrap_or_default(),
+        )
+    }
+
+    /// Whether purchases of `category` count against this budget
+    #[must_use]
+    pub fn covers(&self, category: &str) -> bool {
+        self.category
+            .as_deref()
+            .is_none_or(|own| own.eq_ignore_ascii_case(category.trim()))
+    }
+
+    /// Whether the user was alerted of this budget being exceeded in `month`
+    #[must_use]
+    pub fn alerted(&self, month: Date) -> bool {
+        self.alerted_for == Some(month)
+    }
+
+    /// How this budget stands in `month` with `spent` on what it covers
+    #[must_use]
+    pub fn status(&self, month: Date, spent: Money) -> BudgetStatus {
+        let limit = self.limit();
+        BudgetStatus {
+            budget_pid: self.pid,
+            category: self.category.clone(),
+            month,
+            limit,
+            spent,
+            exceeded: spent.amount > limit.amount,
+        }
+    }
+
+    /// Set the user's budget for the category of `params`. Changing a
+    /// budget re-arms its alert for the current month.
+    ///
+    /// # Errors
+    ///
+    /// When the amount is invalid
+    pub async fn set(
+        db: &DatabaseConnection,
+        user: &users::Model,
+        params: &BudgetParams,
+    ) -> ModelResult<Self> {
+        let limit = params
+            .limit(user.home_currency())
+            .map_err(|message| ModelError::msg(&format!("amount {message}")))?;
+        let category = params.category();
+        let existing = budgets::Entity::find()
+            .filter(budgets::Column::UserId.eq(user.id))
+            .filter(match &category {
+                Some(category) => budgets::Column::Category.eq(category.clone()),
+                None => budgets::Column::Category.is_null(),
+            })
+            .one(db)
+            .await?;
+
+        let budget = match existing {
+            Some(budget) => {
+                let mut budget = budget.into_active_model();
+                budget.amount = ActiveValue::set(limit.amount);
+                budget.currency = ActiveValue::set(limit.currency.code().to_string());
+                budget.alerted_for = ActiveValue::set(None);
+                budget.update(db).await?
+            }
+            None => {
+                ActiveModel {
+                    user_id: ActiveValue::set(user.id),
+                    category: ActiveValue::set(category),
+                    amount: ActiveValue::set(limit.amount),
+                    currency: ActiveValue::set(limit.currency.code().to_string()),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?
+            }
+        };
+        Ok(budget)
+    }
+
+    /// Budgets of a user by category, the overall budget last
+    pub async fn find_by_user(db: &DatabaseConnection, user_id: i32) -> ModelResult<Vec<Self>> {
+        Ok(budgets::Entity::find()
+            .filter(budgets::Column::UserId.eq(user_id))
+            .order_by_asc(budgets::Column::Category)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let budget = budgets::Entity::find()
+            .filter(budgets::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        budget.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+        let budget = Self::find_by_pid(db, pid).await?;
+        budget.delete(db).await?;
+        Ok(())
+    }
+
+    /// Remember the user was alerted of this budget being exceeded in `month`
+    pub async fn mark_alerted(self, db: &DatabaseConnection, month: Date) -> ModelResult<Self> {
+        let mut budget = self.into_active_model();
+        budget.alerted_for = ActiveValue::set(Some(month));
+        Ok(budget.update(db).await?)
+    }
+}
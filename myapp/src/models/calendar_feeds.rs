@@ -0,0 +1,237 @@
+use std::fmt::Write as _;
+
+use chrono::{Duration, Utc};
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use uuid::Uuid;
+
+pub use super::_entities::calendar_feeds::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::outfit_plans,
+    outfit_plans::{Model as OutfitPlan, PlannedOutfit},
+};
+
+/// RFC 5545 limits content lines to 75 octets, excluding the line break
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Past plans kept in the feed so recent days stay visible in calendar apps
+pub const FEED_PAST_DAYS: i64 = 90;
+
+/// iCalendar (RFC 5545) document of planned outfits, one all-day event per
+/// plan
+#[derive(Debug)]
+pub struct OutfitCalendar<'a> {
+    /// Base URL of the frontend, used for event links
+    pub app_url: &'a str,
+    pub plans: &'a [PlannedOutfit],
+}
+
+impl OutfitCalendar<'_> {
+    #[must_use]
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        push_line(&mut out, "BEGIN:VCALENDAR");
+        push_line(&mut out, "VERSION:2.0");
+        push_line(&mut out, "PRODID:-//myapp//Outfit calendar//EN");
+        push_line(&mut out, "CALSCALE:GREGORIAN");
+        push_line(&mut out, "METHOD:PUBLISH");
+        push_line(&mut out, "X-WR-CALNAME:Outfits");
+        push_line(&mut out, "REFRESH-INTERVAL;VALUE=DURATION:PT1H");
+        push_line(&mut out, "X-PUBLISHED-TTL:PT1H");
+        for planned in self.plans {
+            self.render_event(&mut out, planned);
+        }
+        push_line(&mut out, "END:VCALENDAR");
+        out
+    }
+
+    fn render_event(&self, out: &mut String, planned: &PlannedOutfit) {
+        let plan = &planned.plan;
+        let coordinate = &planned.coordinate.coordinate;
+        let url = format!(
+            "{}/coordinates/{}",
+            self.app_url.trim_end_matches('/'),
+            coordinate.pid
+        );
+
+        let summary = match &plan.occasion {
+            Some(occasion) => format!("{} ({occasion})", coordinate.name),
+            None => coordinate.name.clone(),
+        };
+        let mut description = String::from("Clothes:");
+        for item in &planned.coordinate.clothes {
+            let _ = write!(description, "\n- {}", item.name);
+        }
+        if let Some(note) = &plan.note {
+            let _ = write!(description, "\n\n{note}");
+        }
+
+        push_line(out, "BEGIN:VEVENT");
+        push_line(out, &format!("UID:{}", plan.pid));
+        push_line(out, &format!("DTSTAMP:{}", utc_stamp(&plan.updated_at)));
+        push_line(out, &format!("CREATED:{}", utc_stamp(&plan.created_at)));
+        push_line(
+            out,
+            &format!("LAST-MODIFIED:{}", utc_stamp(&plan.updated_at)),
+        );
+        push_line(
+            out,
+            &format!("DTSTART;VALUE=DATE:{}", plan.planned_on.format("%Y%m%d")),
+        );
+        push_line(
+            out,
+            &format!(
+                "DTEND;VALUE=DATE:{}",
+                (plan.planned_on + Duration::days(1)).format("%Y%m%d")
+            ),
+        );
+        push_line(out, &format!("SUMMARY:{}", escape_text(&summary)));
+        push_line(out, &format!("DESCRIPTION:{}", escape_text(&description)));
+        push_line(out, &format!("URL:{url}"));
+        push_line(out, "TRANSP:TRANSPARENT");
+        push_line(out, "END:VEVENT");
+    }
+}
+
+fn utc_stamp(at: &DateTimeWithTimeZone) -> String {
+    at.with_timezone(&Utc).format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT property value
+#[must_use]
+pub fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append a content line terminated by CRLF, folding it into chunks of at
+/// most 75 octets without splitting UTF-8 characters
+fn push_line(out: &mut String, line: &str) {
+    let mut limit = MAX_LINE_OCTETS;
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > limit {
+            out.push_str("\r\n ");
+            // The leading space of a continuation line counts as an octet
+            limit = MAX_LINE_OCTETS - 1;
+            octets = 0;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            this.token = ActiveValue::Set(Uuid::new_v4().simple().to_string());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Create a new subscription feed for a user
+    pub async fn create(db: &DatabaseConnection, user_id: i32) -> ModelResult<Self> {
+        Ok(calendar_feeds::ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    /// Find feed by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let feed = calendar_feeds::Entity::find()
+            .filter(calendar_feeds::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        feed.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Find a feed that has not been revoked by its token
+    pub async fn find_active_by_token(db: &DatabaseConnection, token: &str) -> ModelResult<Self> {
+        let feed = calendar_feeds::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(calendar_feeds::Column::Token, token)
+                    .build(),
+            )
+            .filter(calendar_feeds::Column::RevokedAt.is_null())
+            .one(db)
+            .await?;
+        feed.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Feeds of a user that have not been revoked
+    pub async fn find_active_by_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(calendar_feeds::Entity::find()
+            .filter(calendar_feeds::Column::UserId.eq(user_id))
+            .filter(calendar_feeds::Column::RevokedAt.is_null())
+            .order_by_asc(calendar_feeds::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Revoke the feed. Calendar apps subscribed to it stop receiving
+    /// updates.
+    pub async fn revoke(self, db: &DatabaseConnection) -> ModelResult<Self> {
+        if self.revoked_at.is_some() {
+            return Ok(self);
+        }
+        let mut active_model = self.into_active_model();
+        active_model.revoked_at = ActiveValue::set(Some(Utc::now().into()));
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Subscription URL under `app_url`
+    #[must_use]
+    pub fn url(&self, app_url: &str) -> String {
+        format!(
+            "{}/api/calendar/feeds/{}/outfits.ics",
+            app_url.trim_end_matches('/'),
+            self.token
+        )
+    }
+
+    /// Plans of the feed's user from [`FEED_PAST_DAYS`] ago onwards
+    pub async fn plans(&self, db: &DatabaseConnection) -> ModelResult<Vec<PlannedOutfit>> {
+        let from = Utc::now().date_naive() - Duration::days(FEED_PAST_DAYS);
+        let plans = outfit_plans::Entity::find()
+            .filter(outfit_plans::Column::UserId.eq(self.user_id))
+            .filter(outfit_plans::Column::PlannedOn.gte(from))
+            .order_by_asc(outfit_plans::Column::PlannedOn)
+            .order_by_asc(outfit_plans::Column::Id)
+            .all(db)
+            .await?;
+        Ok(OutfitPlan::with_coordinates(db, plans)
+            .await?
+            .into_iter()
+            .map(PlannedOutfit::without_unavailable)
+            .collect())
+    }
+}
@@ -0,0 +1,391 @@
+//! Capsule wardrobe analysis.
+//!
+//! A capsule is a set of garments meant to be mixed and matched. An outfit
+//! of the capsule takes one garment for every required slot and at most one
+//! for each other slot, keeps to the owner's slot limits and forbidden
+//! combinations, and does not mix three or more accent colors. Garments fill
+//! the slot they were most often given in the owner's coordinates, or the
+//! one derived from their category.
+
+use std::collections::{HashMap, HashSet};
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    _entities::{clothes, clothes_coordinates, coordinates},
+    coordinate_rules::{OutfitGarment, SlotRules},
+    suggestions::{color_family, color_harmony},
+};
+use crate::data::{colors::ColorFamily, slots::Slot};
+
+/// Outfits counted per analysis. Counts stop here and are flagged as capped.
+pub const MAX_COUNTED_OUTFITS: u64 = 100_000;
+
+/// Outfits whose color harmony falls below this mix too many accent colors
+const MIN_HARMONY: f64 = 0.5;
+
+/// Garments to analyze: the given garments, every garment of a category,
+/// or the whole wardrobe when neither is given
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CapsuleParams {
+    pub user_id: i32,
+    pub clothes_pids: Option<Vec<String>>,
+    pub category: Option<String>,
+}
+
+/// What the owner's coordinates tell about garments
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CapsuleHistory {
+    /// Slot each garment was most often given
+    pub slots: HashMap<i32, Slot>,
+    /// Garments each garment was combined with
+    pub worn_with: HashMap<i32, HashSet<i32>>,
+}
+
+impl CapsuleHistory {
+    #[must_use]
+    pub fn from_links(links: &[clothes_coordinates::Model]) -> Self {
+        let mut slot_counts: HashMap<i32, HashMap<Slot, usize>> = HashMap::new();
+        let mut by_coordinate: HashMap<i32, Vec<i32>> = HashMap::new();
+        for link in links {
+            if let Some(slot) = link.slot.as_deref().and_then(Slot::parse) {
+                *slot_counts
+                    .entry(link.clothes_id)
+                    .or_default()
+                    .entry(slot)
+                    .or_default() += 1;
+            }
+            by_coordinate
+                .entry(link.coordinate_id)
+                .or_default()
+                .push(link.clothes_id);
+        }
+
+        let slots = slot_counts
+            .into_iter()
+            .filter_map(|(clothes_id, counts)| {
+                // Most used slot, the first in outfit order on a tie
+                let slot = counts
+                    .into_iter()
+                    .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))?
+                    .0;
+                Some((clothes_id, slot))
+            })
+            .collect();
+        let mut worn_with: HashMap<i32, HashSet<i32>> = HashMap::new();
+        for clothes_ids in by_coordinate.values() {
+            for id in clothes_ids {
+                worn_with
+                    .entry(*id)
+                    .or_default()
+                    .extend(clothes_ids.iter().filter(|other| *other != id).copied());
+            }
+        }
+        Self { slots, worn_with }
+    }
+
+    /// Slot a garment fills in the capsule
+    #[must_use]
+    pub fn slot(&self, item: &clothes::Model) -> Option<Slot> {
+        self.slots
+            .get(&item.id)
+            .copied()
+            .or_else(|| Slot::from_category(&item.category))
+    }
+}
+
+/// Outfits a garment of the capsule is part of
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CapsuleItem {
+    pub clothes_id: i32,
+    pub slot: Option<Slot>,
+    pub outfits: u64,
+}
+
+/// A garment that is part of no outfit
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Orphan {
+    #[serde(flatten, with = "crate::models::clothes::priced")]
+    pub clothes: clothes::Model,
+    pub reason: String,
+}
+
+/// The garment from outside the capsule that adds the most outfits
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CapsuleAddition {
+    #[serde(flatten, with = "crate::models::clothes::priced")]
+    pub clothes: clothes::Model,
+    pub slot: Slot,
+    pub new_outfits: u64,
+    /// Garments of the capsule it was already combined with
+    pub worn_with: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CapsuleAnalysis {
+    pub garments: usize,
+    /// Valid outfits of the capsule
+    pub outfits: u64,
+    /// Whether counting stopped at [`MAX_COUNTED_OUTFITS`]
+    pub capped: bool,
+    /// Required slots no garment of the capsule fills
+    pub missing_slots: Vec<Slot>,
+    pub items: Vec<CapsuleItem>,
+    pub orphans: Vec<Orphan>,
+    pub best_addition: Option<CapsuleAddition>,
+}
+
+/// A garment with what the counting needs to know about it
+#[derive(Debug, Clone, Copy)]
+struct Piece<'a> {
+    item: &'a clothes::Model,
+    slot: Slot,
+    family: Option<ColorFamily>,
+}
+
+impl<'a> Piece<'a> {
+    fn new(item: &'a clothes::Model, history: &CapsuleHistory) -> Option<Self> {
+        Some(Self {
+            item,
+            slot: history.slot(item)?,
+            family: color_family(item),
+        })
+    }
+}
+
+/// Whether the rules let an outfit have a garment in `slot`
+fn allowed(rules: &SlotRules, slot: Slot) -> bool {
+    rules.max_per_slot.get(&slot) != Some(&0)
+}
+
+/// Garments to choose from for one slot
+struct SlotChoice<'a> {
+    required: bool,
+    pieces: Vec<Piece<'a>>,
+}
+
+struct OutfitCounter<'a> {
+    rules: &'a SlotRules,
+    total: u64,
+    capped: bool,
+    per_item: HashMap<i32, u64>,
+}
+
+impl<'a> OutfitCounter<'a> {
+    fn new(rules: &'a SlotRules) -> Self {
+        Self {
+            rules,
+            total: 0,
+            capped: false,
+            per_item: HashMap::new(),
+        }
+    }
+
+    /// Whether an outfit, or any outfit built on it, breaks the rules or
+    /// clashes
+    fn rejects(&self, outfit: &[Piece<'_>]) -> bool {
+        let families: Vec<ColorFamily> = outfit.iter().filter_map(|piece| piece.family).collect();
+        if color_harmony(&families).value < MIN_HARMONY {
+            return true;
+        }
+        let garments: Vec<OutfitGarment<'_>> = outfit
+            .iter()
+            .map(|piece| OutfitGarment {
+                slot: Some(piece.slot),
+                clothes: piece.item,
+            })
+            .collect();
+        self.rules
+            .forbidden_combinations
+            .iter()
+            .any(|combination| combination.is_violated_by(&garments))
+    }
+
+    fn count<'p>(&mut self, choices: &[SlotChoice<'p>], outfit: &mut Vec<Piece<'p>>) {
+        if self.total >= MAX_COUNTED_OUTFITS {
+            self.capped = true;
+            return;
+        }
+        let Some((choice, rest)) = choices.split_first() else {
+            // A single garment is not something to mix and match
+            if outfit.len() >= 2 {
+                self.total += 1;
+                for piece in outfit.iter() {
+                    *self.per_item.entry(piece.item.id).or_default() += 1;
+                }
+            }
+            return;
+        };
+        if !choice.required {
+            self.count(rest, outfit);
+        }
+        for piece in &choice.pieces {
+            outfit.push(*piece);
+            if !self.rejects(outfit) {
+                self.count(rest, outfit);
+            }
+            outfit.pop();
+        }
+    }
+}
+
+/// What each slot can take. `extra`, a garment from outside the capsule,
+/// is the only choice for its slot so that only outfits with it are counted.
+fn slot_choices<'a>(
+    pieces: &[Piece<'a>],
+    extra: Option<Piece<'a>>,
+    rules: &SlotRules,
+) -> Vec<SlotChoice<'a>> {
+    Slot::ALL
+        .into_iter()
+        .filter(|slot| allowed(rules, *slot))
+        .map(|slot| match extra.filter(|extra| extra.slot == slot) {
+            Some(extra) => SlotChoice {
+                required: true,
+                pieces: vec![extra],
+            },
+            None => SlotChoice {
+                required: rules.required_slots.contains(&slot),
+                pieces: pieces.iter().filter(|p| p.slot == slot).copied().collect(),
+            },
+        })
+        .filter(|choice| choice.required || !choice.pieces.is_empty())
+        .collect()
+}
+
+/// Analyze the capsule `garments` against the owner's rules. `others` are
+/// the remaining garments of the wardrobe, considered as additions.
+#[must_use]
+pub fn analyze(
+    garments: &[clothes::Model],
+    others: &[clothes::Model],
+    history: &CapsuleHistory,
+    rules: &SlotRules,
+) -> CapsuleAnalysis {
+    let pieces: Vec<Piece<'_>> = garments
+        .iter()
+        .filter_map(|item| Piece::new(item, history))
+        .filter(|piece| allowed(rules, piece.slot))
+        .collect();
+
+    let mut counter = OutfitCounter::new(rules);
+    counter.count(&slot_choices(&pieces, None, rules), &mut Vec::new());
+
+    let items: Vec<CapsuleItem> = garments
+        .iter()
+        .map(|item| CapsuleItem {
+            clothes_id: item.id,
+            slot: history.slot(item),
+            outfits: counter.per_item.get(&item.id).copied().unwrap_or(0),
+        })
+        .collect();
+    let orphans = garments
+        .iter()
+        .zip(&items)
+        .filter(|(_, usage)| usage.outfits == 0)
+        .map(|(item, usage)| Orphan {
+            clothes: item.clone(),
+            reason: match usage.slot {
+                None => "its category does not fill an outfit slot".to_string(),
+                Some(slot) if !allowed(rules, slot) => {
+                    format!("{} is limited to 0 garments", slot.as_str())
+                }
+                Some(_) => "it combines with no other garment of the capsule".to_string(),
+            },
+        })
+        .collect();
+
+    let capsule: HashSet<i32> = garments.iter().map(|item| item.id).collect();
+    let mut best_addition: Option<CapsuleAddition> = None;
+    for candidate in others.iter().filter(|item| !capsule.contains(&item.id)) {
+        let Some(extra) = Piece::new(candidate, history).filter(|piece| allowed(rules, piece.slot))
+        else {
+            continue;
+        };
+        let mut with_extra = OutfitCounter::new(rules);
+        with_extra.count(&slot_choices(&pieces, Some(extra), rules), &mut Vec::new());
+        let worn_with = history
+            .worn_with
+            .get(&candidate.id)
+            .map_or(0, |worn| worn.intersection(&capsule).count());
+        let better = best_addition
+            .as_ref()
+            .is_none_or(|best| (with_extra.total, worn_with) > (best.new_outfits, best.worn_with));
+        if with_extra.total > 0 && better {
+            best_addition = Some(CapsuleAddition {
+                clothes: candidate.clone(),
+                slot: extra.slot,
+                new_outfits: with_extra.total,
+                worn_with,
+            });
+        }
+    }
+
+    let missing_slots = rules
+        .required_slots
+        .iter()
+        .filter(|slot| !pieces.iter().any(|piece| piece.slot == **slot))
+        .copied()
+        .collect();
+    CapsuleAnalysis {
+        garments: garments.len(),
+        outfits: counter.total,
+        capped: counter.capped,
+        missing_slots,
+        items,
+        orphans,
+        best_addition,
+    }
+}
+
+/// Analyze the garments selected by `params` for their owner
+pub async fn analyze_for_user(
+    db: &DatabaseConnection,
+    params: &CapsuleParams,
+) -> ModelResult<CapsuleAnalysis> {
+    let wardrobe = clothes::Entity::find_live().all(db).await?;
+    let (garments, others): (Vec<clothes::Model>, Vec<clothes::Model>) =
+        match (&params.clothes_pids, &params.category) {
+            (Some(pids), _) => {
+                let pids = pids
+                    .iter()
+                    .map(|pid| Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into())))
+                    .collect::<ModelResult<HashSet<Uuid>>>()?;
+                let found = wardrobe
+                    .iter()
+                    .filter(|item| pids.contains(&item.pid))
+                    .count();
+                if found != pids.len() {
+                    return Err(ModelError::EntityNotFound);
+                }
+                wardrobe
+                    .into_iter()
+                    .partition(|item| pids.contains(&item.pid))
+            }
+            (None, Some(category)) => {
+                let category = category.trim().to_lowercase();
+                wardrobe
+                    .into_iter()
+                    .partition(|item| item.category.trim().to_lowercase() == category)
+            }
+            (None, None) => (wardrobe, Vec::new()),
+        };
+
+    let coordinate_ids: Vec<i32> = coordinates::Entity::find_live()
+        .filter(coordinates::Column::UserId.eq(params.user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|coordinate| coordinate.id)
+        .collect();
+    let links = clothes_coordinates::Entity::find()
+        .filter(clothes_coordinates::Column::CoordinateId.is_in(coordinate_ids))
+        .all(db)
+        .await?;
+    let history = CapsuleHistory::from_links(&links);
+    let rules = SlotRules::for_user(db, params.user_id).await?;
+
+    Ok(analyze(&garments, &others, &history, &rules))
+}
@@ -1,11 +1,30 @@
+use std::collections::HashMap;
+
 use loco_rs::prelude::*;
-use sea_orm::prelude::Decimal;
+use sea_orm::{
+    prelude::Decimal,
+    sea_query::{Expr, OnConflict, Query},
+    Condition, Select,
+};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::data::{
+    colors::{self, Color, ColorFamily},
+    lifecycle::{self, LifecycleState},
+    money::{Currency, Money},
+    sizes::{self, NormalizedSize, SizeGroup, SizeSystem},
+};
+
 pub use super::_entities::clothes::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes_coordinates, coordinate_tombstones, coordinates},
+    coordinate_rules::FieldError,
+    coordinate_versions::{self, VersionChange},
+    inventory_movements::{self, MovementReason},
+};
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CreateClothesParams {
     pub name: String,
     pub description: Option<String>,
@@ -13,11 +32,22 @@ pub struct CreateClothesParams {
     pub category: String,
     pub size: String,
     pub color: String,
+    /// Hex value (`#RRGGBB`) or palette name, derived from `color` when omitted
+    pub primary_color: Option<String>,
+    /// Hex value (`#RRGGBB`) or palette name
+    pub secondary_color: Option<String>,
     pub material: Option<String>,
-    pub price: f64,
-    pub in_stock: bool,
+    /// Decimal string such as `"12.50"`
+    pub price: String,
+    /// ISO-4217 code of the price, `USD` when omitted
+    #[serde(default)]
+    pub currency: Currency,
+    /// Units owned, recorded as the opening purchase in the inventory ledger
     pub stock_quantity: i32,
     pub image_url: Option<String>,
+    pub purchase_date: Option<Date>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -28,11 +58,284 @@ pub struct UpdateClothesParams {
     pub category: Option<String>,
     pub size: Option<String>,
     pub color: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
     pub material: Option<String>,
-    pub price: Option<f64>,
-    pub in_stock: Option<bool>,
-    pub stock_quantity: Option<i32>,
+    /// Decimal string such as `"12.50"`
+    pub price: Option<String>,
+    pub currency: Option<Currency>,
     pub image_url: Option<String>,
+    pub condition: Option<lifecycle::Condition>,
+    pub purchase_date: Option<Date>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
+}
+
+impl CreateClothesParams {
+    /// The price in its currency
+    ///
+    /// # Errors
+    ///
+    /// When the price is not a valid amount for the currency
+    pub fn money(&self) -> Result<Money, String> {
+        Money::parse(&self.price, self.currency)
+    }
+
+    /// Problems with the colors and opening stock of the new item
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        let mut problems = color_problems(
+            self.primary_color.as_deref(),
+            self.secondary_color.as_deref(),
+        );
+        if self.stock_quantity < 0 {
+            problems.push(FieldError::new("stock_quantity", "must not be negative"));
+        }
+        problems
+    }
+}
+
+impl UpdateClothesParams {
+    /// The new price of `item`, `None` when neither the amount nor the
+    /// currency changes. A new currency keeps the amount, which must then
+    /// fit the decimals of that currency.
+    ///
+    /// # Errors
+    ///
+    /// When the price is not a valid amount for the currency
+    pub fn money(&self, item: &Model) -> Result<Option<Money>, String> {
+        if self.price.is_none() && self.currency.is_none() {
+            return Ok(None);
+        }
+        let current = item.money();
+        let amount = self
+            .price
+            .clone()
+            .unwrap_or_else(|| current.amount.to_string());
+        Money::parse(&amount, self.currency.unwrap_or(current.currency)).map(Some)
+    }
+
+    /// Problems with the new colors
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        color_problems(
+            self.primary_color.as_deref(),
+            self.secondary_color.as_deref(),
+        )
+    }
+}
+
+/// `primary_color` and `secondary_color` that are neither a hex value nor a
+/// palette name
+fn color_problems(primary: Option<&str>, secondary: Option<&str>) -> Vec<FieldError> {
+    [("primary_color", primary), ("secondary_color", secondary)]
+        .into_iter()
+        .filter_map(|(field, value)| {
+            colors::resolve(value?).is_none().then(|| {
+                FieldError::new(
+                    field,
+                    "must be a hex value (#RRGGBB) or a palette color name",
+                )
+            })
+        })
+        .collect()
+}
+
+/// Color filter accepted by the `find_by_*` finders.
+///
+/// `family` matches either the primary or the secondary color family, while
+/// `near` keeps items whose primary or secondary color lies within
+/// `threshold` (CIEDE2000) of the given color.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ColorQuery {
+    pub family: Option<ColorFamily>,
+    pub near: Option<String>,
+    pub threshold: Option<f64>,
+}
+
+impl ColorQuery {
+    /// Default CIEDE2000 threshold for "similar colors"
+    pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+    fn apply(&self, select: Select<Entity>) -> Select<Entity> {
+        match self.family {
+            Some(family) => select.filter(
+                Condition::any()
+                    .add(clothes::Column::PrimaryColorFamily.eq(family.as_str()))
+                    .add(clothes::Column::SecondaryColorFamily.eq(family.as_str())),
+            ),
+            None => select,
+        }
+    }
+
+    /// The color given as `near`
+    ///
+    /// # Errors
+    ///
+    /// When `near` is neither a hex value nor a palette color
+    pub fn near_color(&self) -> ModelResult<Option<Color>> {
+        self.near.as_deref().map(resolve_color).transpose()
+    }
+
+    fn matches(&self, target: Option<&Color>, item: &Model) -> bool {
+        let Some(target) = target else {
+            return true;
+        };
+        let threshold = self.threshold.unwrap_or(Self::DEFAULT_THRESHOLD);
+        [&item.primary_color_hex, &item.secondary_color_hex]
+            .into_iter()
+            .flatten()
+            .filter_map(|hex| colors::distance(&target.hex, hex))
+            .any(|distance| distance <= threshold)
+    }
+}
+
+/// Size filter accepted by the `find_by_*` finders.
+///
+/// `size` is parsed for the garment's size group, so `M`, `EU 40` and `9号`
+/// all select the same tops. `size_system` selects the system used for
+/// `display_size` in responses.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SizeQuery {
+    pub size: Option<String>,
+    pub size_system: Option<SizeSystem>,
+}
+
+impl SizeQuery {
+    const GROUPS: [SizeGroup; 3] = [SizeGroup::Apparel, SizeGroup::Bottoms, SizeGroup::Shoes];
+
+    fn wanted(&self, group: SizeGroup) -> Option<NormalizedSize> {
+        self.size.as_deref().and_then(|size| sizes::parse(size, group))
+    }
+
+    fn tolerance(group: SizeGroup) -> f64 {
+        match group {
+            SizeGroup::Shoes => 0.25,
+            _ => 2.0,
+        }
+    }
+
+    fn condition(wanted: &NormalizedSize) -> Option<Condition> {
+        let tolerance = to_decimal(Self::tolerance(wanted.group));
+        let condition = match (wanted.cm.and_then(to_decimal), tolerance) {
+            (Some(cm), Some(tolerance)) => {
+                clothes::Column::SizeCm.between(cm - tolerance, cm + tolerance)
+            }
+            _ => clothes::Column::SizeLetter.eq(wanted.letter?.as_str()),
+        };
+        Some(Condition::all().add(condition))
+    }
+
+    fn apply(&self, select: Select<Entity>, group: SizeGroup) -> Select<Entity> {
+        match self.wanted(group).as_ref().and_then(Self::condition) {
+            Some(condition) => select.filter(condition),
+            None => select,
+        }
+    }
+
+    /// Narrow a query over every category to the rows the size could match
+    /// in any size group. `matches` then checks each row against its own
+    /// group, so `27cm` finds shoes and `EU 42` is not read as a letter size
+    /// for them.
+    fn apply_any(&self, select: Select<Entity>) -> Select<Entity> {
+        let conditions: Vec<Condition> = Self::GROUPS
+            .iter()
+            .filter_map(|group| self.wanted(*group))
+            .filter_map(|wanted| Self::condition(&wanted))
+            .collect();
+        if conditions.is_empty() {
+            return select;
+        }
+        select.filter(
+            conditions
+                .into_iter()
+                .fold(Condition::any(), |any, condition| any.add(condition)),
+        )
+    }
+
+    /// Whether `item` has the wanted size, read for the item's size group.
+    /// A size no group understands does not filter.
+    #[must_use]
+    pub fn matches(&self, item: &Model) -> bool {
+        if Self::GROUPS
+            .iter()
+            .all(|group| self.wanted(*group).is_none())
+        {
+            return true;
+        }
+        let Some(wanted) = self.wanted(SizeGroup::from_category(&item.category)) else {
+            return false;
+        };
+        match (wanted.cm.and_then(to_decimal), item.size_cm) {
+            (Some(cm), Some(item_cm)) => to_decimal(Self::tolerance(wanted.group))
+                .is_some_and(|tolerance| (cm - item_cm).abs() <= tolerance),
+            (Some(_), None) => false,
+            (None, _) => wanted
+                .letter
+                .is_none_or(|letter| item.size_letter.as_deref() == Some(letter.as_str())),
+        }
+    }
+}
+
+/// What happens to the coordinates that include a garment being deleted
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletePolicy {
+    /// Refuse to delete a garment that is part of any coordinate
+    #[default]
+    Block,
+    /// Leave an "item removed" placeholder in each coordinate
+    Detach,
+    /// Drop the garment from each coordinate without a trace
+    Cascade,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct DeleteQuery {
+    /// Overrides the configured `settings.clothes.delete_policy`
+    pub policy: Option<DeletePolicy>,
+}
+
+/// A coordinate that loses a garment when it is deleted
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AffectedCoordinate {
+    pub pid: Uuid,
+    pub name: String,
+    pub user_id: i32,
+    /// Garments left in the coordinate afterwards
+    pub remaining_clothes: usize,
+}
+
+/// The impact of deleting a garment under a delete policy
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeleteImpact {
+    pub clothes_pid: Uuid,
+    pub name: String,
+    pub policy: DeletePolicy,
+    /// The policy refuses the delete because coordinates include the garment
+    pub blocked: bool,
+    pub coordinates: Vec<AffectedCoordinate>,
+}
+
+impl DeleteImpact {
+    #[must_use]
+    pub fn new(item: &Model, policy: DeletePolicy, coordinates: Vec<AffectedCoordinate>) -> Self {
+        Self {
+            clothes_pid: item.pid,
+            name: item.name.clone(),
+            policy,
+            blocked: policy == DeletePolicy::Block && !coordinates.is_empty(),
+            coordinates,
+        }
+    }
+}
+
+fn to_decimal(value: f64) -> Option<Decimal> {
+    Decimal::from_f64_retain(value).map(|d| d.round_dp(1))
+}
+
+fn resolve_color(value: &str) -> ModelResult<Color> {
+    colors::resolve(value).ok_or_else(|| ModelError::msg(&format!("Unknown color: {value}")))
 }
 
 #[derive(Debug, Validate, Deserialize)]
@@ -62,30 +365,239 @@ impl ActiveModelBehavior for super::_entities::clothes::ActiveModel {
         C: ConnectionTrait,
     {
         self.validate()?;
+        let mut this = self;
+        if insert || this.size.is_set() || this.category.is_set() {
+            this.set_normalized_size();
+        }
         if insert {
-            let mut this = self;
             this.pid = ActiveValue::Set(Uuid::new_v4());
-            Ok(this)
-        } else {
-            Ok(self)
         }
+        Ok(this)
+    }
+}
+
+impl ActiveModel {
+    /// Derive the normalized size columns from `size` and `category`
+    pub fn set_normalized_size(&mut self) {
+        let normalized = match (self.size.try_as_ref(), self.category.try_as_ref()) {
+            (Some(size), Some(category)) => sizes::parse(size, SizeGroup::from_category(category)),
+            _ => None,
+        };
+        self.size_system = ActiveValue::set(normalized.as_ref().map(|n| n.system.as_str().to_string()));
+        self.size_letter = ActiveValue::set(
+            normalized.as_ref().and_then(|n| n.letter).map(|l| l.as_str().to_string()),
+        );
+        self.size_cm = ActiveValue::set(normalized.as_ref().and_then(|n| n.cm).and_then(to_decimal));
+        self.inseam_cm = ActiveValue::set(normalized.and_then(|n| n.inseam_cm).and_then(to_decimal));
+    }
+}
+
+/// Serde `with` module writing a garment with its price as [`Money`],
+/// `{"amount", "currency"}`, in place of the bare `price` and `currency`
+/// columns. Every response carrying garments goes through it, so prices read
+/// the same everywhere.
+pub mod priced {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+    use serde_json::Value;
+
+    use super::Model;
+    use crate::data::money::Money;
+
+    /// The JSON object of a garment with its price as [`Money`]
+    ///
+    /// # Errors
+    ///
+    /// When the garment cannot be serialized
+    pub fn to_value(item: &Model) -> Result<Value, serde_json::Error> {
+        let mut value = serde_json::to_value(item)?;
+        if let Some(fields) = value.as_object_mut() {
+            fields.remove("currency");
+            fields.insert("price".to_string(), serde_json::to_value(item.money())?);
+        }
+        Ok(value)
+    }
+
+    /// A garment from the object written by [`to_value`]
+    ///
+    /// # Errors
+    ///
+    /// When the object is not a garment or its price is not [`Money`]
+    pub fn from_value(mut value: Value) -> Result<Model, serde_json::Error> {
+        if let Some(fields) = value.as_object_mut() {
+            if let Some(price) = fields.remove("price") {
+                let money: Money = serde_json::from_value(price)?;
+                fields.insert("price".to_string(), serde_json::to_value(money.amount)?);
+                fields.insert("currency".to_string(), money.currency.code().into());
+            }
+        }
+        serde_json::from_value(value)
+    }
+
+    /// # Errors
+    ///
+    /// When the garment cannot be serialized
+    pub fn serialize<S: Serializer>(item: &Model, serializer: S) -> Result<S::Ok, S::Error> {
+        to_value(item)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    /// # Errors
+    ///
+    /// When the input is not a garment with a [`Money`] price
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Model, D::Error> {
+        from_value(Value::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// [`priced`] for a list of garments
+pub mod priced_list {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de, ser};
+    use serde_json::Value;
+
+    use super::{Model, priced};
+
+    /// # Errors
+    ///
+    /// When a garment cannot be serialized
+    pub fn serialize<S: Serializer>(items: &[Model], serializer: S) -> Result<S::Ok, S::Error> {
+        items
+            .iter()
+            .map(priced::to_value)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    /// # Errors
+    ///
+    /// When an item is not a garment with a [`Money`](crate::data::money::Money) price
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Model>, D::Error> {
+        Vec::<Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(priced::from_value)
+            .collect::<Result<_, _>>()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// A garment written through [`priced`], for containers whose fields
+/// cannot take a `with` attribute
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Priced(#[serde(with = "priced")] pub Model);
+
+impl Entity {
+    /// Clothes that are not in the trash
+    #[must_use]
+    pub fn find_live() -> Select<Self> {
+        Self::find().filter(clothes::Column::DeletedAt.is_null())
+    }
+
+    /// Clothes in the trash
+    #[must_use]
+    pub fn find_trashed() -> Select<Self> {
+        Self::find().filter(clothes::Column::DeletedAt.is_not_null())
+    }
+
+    /// Clothes that can be worn right now
+    #[must_use]
+    pub fn find_available() -> Select<Self> {
+        Self::find_live()
+            .filter(clothes::Column::LifecycleState.eq(LifecycleState::Active.as_str()))
+    }
+
+    /// Live clothes in one of the live coordinates of a user. Clothes have
+    /// no owner, so these are the clothes the user has.
+    #[must_use]
+    pub fn find_owned(user_id: i32) -> Select<Self> {
+        let links = Query::select()
+            .column((
+                clothes_coordinates::Entity,
+                clothes_coordinates::Column::ClothesId,
+            ))
+            .from(clothes_coordinates::Entity)
+            .inner_join(
+                coordinates::Entity,
+                Expr::col((coordinates::Entity, coordinates::Column::Id)).equals((
+                    clothes_coordinates::Entity,
+                    clothes_coordinates::Column::CoordinateId,
+                )),
+            )
+            .and_where(Expr::col((coordinates::Entity, coordinates::Column::UserId)).eq(user_id))
+            .and_where(Expr::col((coordinates::Entity, coordinates::Column::DeletedAt)).is_null())
+            .to_owned();
+        Self::find_live().filter(clothes::Column::Id.in_subquery(links))
     }
 }
 
 impl Model {
+    /// Lifecycle state of this item, `Active` for an unknown stored value
+    #[must_use]
+    pub fn lifecycle(&self) -> LifecycleState {
+        LifecycleState::parse(&self.lifecycle_state).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn condition(&self) -> Option<lifecycle::Condition> {
+        self.condition.as_deref().and_then(lifecycle::Condition::parse)
+    }
+
+    /// Whether this item can be worn right now
+    #[must_use]
+    pub fn is_available(&self) -> bool {
+        self.lifecycle().is_available()
+    }
+
+    /// Price of this item in its currency
+    #[must_use]
+    pub fn money(&self) -> Money {
+        Money::new(self.price, Currency::parse(&self.currency).unwrap_or_default())
+    }
+
+    /// Why this item cannot be worn right now, `None` when it can
+    #[must_use]
+    pub fn unavailable_reason(&self) -> Option<String> {
+        match self.lifecycle() {
+            LifecycleState::Active => None,
+            LifecycleState::Lent => Some(format!(
+                "lent to {}",
+                self.lent_to.as_deref().unwrap_or("someone")
+            )),
+            state => Some(state.describe().to_string()),
+        }
+    }
+
+    /// Parse this item's size for its category
+    #[must_use]
+    pub fn normalized_size(&self) -> Option<NormalizedSize> {
+        sizes::parse(&self.size, SizeGroup::from_category(&self.category))
+    }
+
     /// Get all coordinates that include this clothes item
     pub async fn get_coordinates(&self, db: &DatabaseConnection) -> ModelResult<Vec<super::coordinates::Model>> {
         let coordinates = self
             .find_related(super::_entities::coordinates::Entity)
+            .filter(coordinates::Column::DeletedAt.is_null())
             .all(db)
             .await?;
         Ok(coordinates)
     }
     /// Create a new clothes item
-    pub async fn create(
-        db: &DatabaseConnection,
+    pub async fn create<C: ConnectionTrait>(
+        db: &C,
         params: &CreateClothesParams,
     ) -> ModelResult<Self> {
+        let primary = match &params.primary_color {
+            Some(value) => Some(resolve_color(value)?),
+            None => colors::lookup_name(&params.color),
+        };
+        let secondary = params.secondary_color.as_deref().map(resolve_color).transpose()?;
+        let price = params.money().map_err(|message| ModelError::msg(&message))?;
+        if params.stock_quantity < 0 {
+            return Err(ModelError::msg("Stock quantity cannot be negative"));
+        }
+
         let clothes = clothes::ActiveModel {
             name: ActiveValue::set(params.name.clone()),
             description: ActiveValue::set(params.description.clone()),
@@ -94,23 +606,55 @@ impl Model {
             size: ActiveValue::set(params.size.clone()),
             color: ActiveValue::set(params.color.clone()),
             material: ActiveValue::set(params.material.clone()),
-            price: ActiveValue::set(Decimal::from_f64_retain(params.price)
-                .ok_or_else(|| ModelError::msg("Invalid price format"))?),
-            in_stock: ActiveValue::set(params.in_stock),
-            stock_quantity: ActiveValue::set(params.stock_quantity),
+            price: ActiveValue::set(price.amount),
+            currency: ActiveValue::set(price.currency.code().to_string()),
+            in_stock: ActiveValue::set(false),
+            stock_quantity: ActiveValue::set(0),
             image_url: ActiveValue::set(params.image_url.clone()),
+            purchase_date: ActiveValue::set(params.purchase_date),
+            store: ActiveValue::set(params.store.clone()),
+            order_number: ActiveValue::set(params.order_number.clone()),
+            primary_color_hex: ActiveValue::set(primary.as_ref().map(|c| c.hex.clone())),
+            primary_color_family: ActiveValue::set(
+                primary.map(|c| c.family.as_str().to_string()),
+            ),
+            secondary_color_hex: ActiveValue::set(secondary.as_ref().map(|c| c.hex.clone())),
+            secondary_color_family: ActiveValue::set(
+                secondary.map(|c| c.family.as_str().to_string()),
+            ),
             ..Default::default()
         }
         .insert(db)
         .await?;
+        if params.stock_quantity == 0 {
+            return Ok(clothes);
+        }
 
+        let (clothes, _) = inventory_movements::Model::append(
+            db,
+            clothes,
+            MovementReason::Purchase,
+            params.stock_quantity,
+            Some("initial stock".to_string()),
+        )
+        .await?;
         Ok(clothes)
     }
 
     /// Find clothes by PID
     pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
         let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
-        let clothes = clothes::Entity::find()
+        let clothes = clothes::Entity::find_live()
+            .filter(clothes::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        clothes.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Find clothes in the trash by PID
+    pub async fn find_trashed_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let clothes = clothes::Entity::find_trashed()
             .filter(clothes::Column::Pid.eq(parse_uuid))
             .one(db)
             .await?;
@@ -119,19 +663,50 @@ impl Model {
 
     /// Find all clothes items
     pub async fn find_all(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
-        let clothes = clothes::Entity::find().all(db).await?;
+        let clothes = clothes::Entity::find_live().all(db).await?;
         Ok(clothes)
     }
 
-    /// Find clothes by category
-    pub async fn find_by_category(db: &DatabaseConnection, category: &str) -> ModelResult<Vec<Self>> {
-        let clothes = clothes::Entity::find()
-            .filter(clothes::Column::Category.eq(category))
-            .all(db)
-            .await?;
+    /// Find clothes by category, optionally narrowed by color and size
+    pub async fn find_by_category(
+        db: &DatabaseConnection,
+        category: &str,
+        color: &ColorQuery,
+        size: &SizeQuery,
+    ) -> ModelResult<Vec<Self>> {
+        let select = clothes::Entity::find_live().filter(clothes::Column::Category.eq(category));
+        let select = size.apply(select, SizeGroup::from_category(category));
+        Self::find_with_color(db, select, color).await
+    }
+
+    /// Find clothes by color family and/or similarity to a given color, and
+    /// by size, read for each garment's own size group
+    pub async fn find_by_color(
+        db: &DatabaseConnection,
+        color: &ColorQuery,
+        size: &SizeQuery,
+    ) -> ModelResult<Vec<Self>> {
+        let select = size.apply_any(clothes::Entity::find_live());
+        let mut clothes = Self::find_with_color(db, select, color).await?;
+        clothes.retain(|item| size.matches(item));
         Ok(clothes)
     }
 
+    async fn find_with_color(
+        db: &DatabaseConnection,
+        select: Select<Entity>,
+        color: &ColorQuery,
+    ) -> ModelResult<Vec<Self>> {
+        let target = color.near_color()?;
+        Ok(color
+            .apply(select)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter(|item| color.matches(target.as_ref(), item))
+            .collect())
+    }
+
     /// Update clothes item
     pub async fn update_by_pid(
         db: &DatabaseConnection,
@@ -139,6 +714,7 @@ impl Model {
         params: &UpdateClothesParams,
     ) -> ModelResult<Self> {
         let clothes = Self::find_by_pid(db, pid).await?;
+        let price = params.money(&clothes).map_err(|message| ModelError::msg(&message))?;
         let mut active_model = clothes.into_active_model();
 
         if let Some(name) = &params.name {
@@ -158,31 +734,192 @@ impl Model {
         }
         if let Some(color) = &params.color {
             active_model.color = ActiveValue::set(color.clone());
+            // An unknown color name clears the derived primary color so that
+            // color searches do not keep matching the previous one
+            if params.primary_color.is_none() {
+                let primary = colors::lookup_name(color);
+                active_model.primary_color_hex =
+                    ActiveValue::set(primary.as_ref().map(|c| c.hex.clone()));
+                active_model.primary_color_family =
+                    ActiveValue::set(primary.map(|c| c.family.as_str().to_string()));
+            }
         }
-        if let Some(material) = &params.material {
-            active_model.material = ActiveValue::set(Some(material.clone()));
+        if let Some(primary_color) = &params.primary_color {
+            let primary = resolve_color(primary_color)?;
+            active_model.primary_color_hex = ActiveValue::set(Some(primary.hex));
+            active_model.primary_color_family =
+                ActiveValue::set(Some(primary.family.as_str().to_string()));
         }
-        if let Some(price) = params.price {
-            active_model.price = ActiveValue::set(Decimal::from_f64_retain(price)
-                .ok_or_else(|| ModelError::msg("Invalid price format"))?);
+        if let Some(secondary_color) = &params.secondary_color {
+            let secondary = resolve_color(secondary_color)?;
+            active_model.secondary_color_hex = ActiveValue::set(Some(secondary.hex));
+            active_model.secondary_color_family =
+                ActiveValue::set(Some(secondary.family.as_str().to_string()));
         }
-        if let Some(in_stock) = params.in_stock {
-            active_model.in_stock = ActiveValue::set(in_stock);
+        if let Some(material) = &params.material {
+            active_model.material = ActiveValue::set(Some(material.clone()));
         }
-        if let Some(stock_quantity) = params.stock_quantity {
-            active_model.stock_quantity = ActiveValue::set(stock_quantity);
+        if let Some(price) = price {
+            active_model.price = ActiveValue::set(price.amount);
+            active_model.currency = ActiveValue::set(price.currency.code().to_string());
         }
         if let Some(image_url) = &params.image_url {
             active_model.image_url = ActiveValue::set(Some(image_url.clone()));
         }
+        if let Some(purchase_date) = params.purchase_date {
+            active_model.purchase_date = ActiveValue::set(Some(purchase_date));
+        }
+        if let Some(store) = &params.store {
+            active_model.store = ActiveValue::set(Some(store.clone()));
+        }
+        if let Some(order_number) = &params.order_number {
+            active_model.order_number = ActiveValue::set(Some(order_number.clone()));
+        }
+        if let Some(condition) = params.condition {
+            active_model.condition = ActiveValue::set(Some(condition.as_str().to_string()));
+        }
 
         Ok(active_model.update(db).await?)
     }
 
-    /// Delete clothes item by PID
-    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+    /// Links of this item with the coordinates they belong to
+    async fn coordinate_links<C: ConnectionTrait>(
+        &self,
+        db: &C,
+    ) -> ModelResult<Vec<(clothes_coordinates::Model, coordinates::Model)>> {
+        Ok(clothes_coordinates::Entity::find()
+            .filter(clothes_coordinates::Column::ClothesId.eq(self.id))
+            .find_also_related(coordinates::Entity)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(link, coordinate)| Some((link, coordinate?)))
+            .collect())
+    }
+
+    /// What deleting this item under `policy` does to its coordinates
+    pub async fn delete_impact<C: ConnectionTrait>(
+        &self,
+        db: &C,
+        policy: DeletePolicy,
+    ) -> ModelResult<DeleteImpact> {
+        let links: Vec<_> = self
+            .coordinate_links(db)
+            .await?
+            .into_iter()
+            .filter(|(_, coordinate)| coordinate.deleted_at.is_none())
+            .collect();
+        let mut garments: HashMap<i32, usize> = HashMap::new();
+        for link in clothes_coordinates::Entity::find()
+            .filter(
+                clothes_coordinates::Column::CoordinateId
+                    .is_in(links.iter().map(|(link, _)| link.coordinate_id)),
+            )
+            .all(db)
+            .await?
+        {
+            *garments.entry(link.coordinate_id).or_default() += 1;
+        }
+        let coordinates = links
+            .into_iter()
+            .map(|(_, coordinate)| AffectedCoordinate {
+                remaining_clothes: garments
+                    .get(&coordinate.id)
+                    .map_or(0, |count| count.saturating_sub(1)),
+                pid: coordinate.pid,
+                name: coordinate.name,
+                user_id: coordinate.user_id,
+            })
+            .collect();
+        Ok(DeleteImpact::new(self, policy, coordinates))
+    }
+
+    /// Move clothes item to the trash by PID under a delete policy. Nothing is
+    /// deleted when the policy blocks it, which the returned impact tells.
+    pub async fn delete_by_pid(
+        db: &DatabaseConnection,
+        pid: &str,
+        policy: DeletePolicy,
+    ) -> ModelResult<DeleteImpact> {
         let clothes = Self::find_by_pid(db, pid).await?;
-        clothes.delete(db).await?;
-        Ok(())
+        let txn = db.begin().await?;
+
+        let impact = clothes.delete_impact(&txn, policy).await?;
+        if impact.blocked {
+            return Ok(impact);
+        }
+        // Links are kept as tombstones under every policy, so a restore puts
+        // the garment back into all of its coordinates
+        let links = clothes.coordinate_links(&txn).await?;
+        for (link, _) in &links {
+            let placeholder = policy == DeletePolicy::Detach;
+            coordinate_tombstones::Model::bury(&txn, link, &clothes, placeholder).await?;
+        }
+        clothes_coordinates::Entity::delete_many()
+            .filter(clothes_coordinates::Column::ClothesId.eq(clothes.id))
+            .exec(&txn)
+            .await?;
+        let mut trashed = clothes.into_active_model();
+        trashed.deleted_at = ActiveValue::set(Some(chrono::Utc::now().into()));
+        trashed.update(&txn).await?;
+        for (_, coordinate) in &links {
+            coordinate_versions::Model::record(&txn, coordinate, VersionChange::ClothesRemoved)
+                .await?;
+        }
+
+        txn.commit().await?;
+        Ok(impact)
+    }
+
+    /// Point this item at its receipt, stored at `path`
+    pub async fn attach_receipt(
+        self,
+        db: &DatabaseConnection,
+        path: &str,
+        content_type: &str,
+    ) -> ModelResult<Self> {
+        let mut item = self.into_active_model();
+        item.receipt_path = ActiveValue::set(Some(path.to_string()));
+        item.receipt_content_type = ActiveValue::set(Some(content_type.to_string()));
+        Ok(item.update(db).await?)
+    }
+
+    /// Take clothes item out of the trash by PID. It goes back into every
+    /// coordinate it was part of when it was deleted.
+    pub async fn restore_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let clothes = Self::find_trashed_by_pid(db, pid).await?;
+        let txn = db.begin().await?;
+
+        let mut restored = clothes.into_active_model();
+        restored.deleted_at = ActiveValue::set(None);
+        let clothes = restored.update(&txn).await?;
+
+        let tombstones = coordinate_tombstones::Entity::find()
+            .filter(coordinate_tombstones::Column::ClothesPid.eq(clothes.pid))
+            .find_also_related(coordinates::Entity)
+            .all(&txn)
+            .await?;
+        for (tombstone, coordinate) in tombstones {
+            clothes_coordinates::Entity::insert(tombstone.link(clothes.id))
+                .on_conflict(
+                    OnConflict::columns([
+                        clothes_coordinates::Column::ClothesId,
+                        clothes_coordinates::Column::CoordinateId,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .do_nothing()
+                .exec_without_returning(&txn)
+                .await?;
+            tombstone.delete(&txn).await?;
+            if let Some(coordinate) = coordinate {
+                coordinate_versions::Model::record(&txn, &coordinate, VersionChange::ClothesAdded)
+                    .await?;
+            }
+        }
+
+        txn.commit().await?;
+        Ok(clothes)
     }
 }
\ No newline at end of file
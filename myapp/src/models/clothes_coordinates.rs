@@ -1,7 +1,71 @@
 use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
 pub use super::_entities::clothes_coordinates::{ActiveModel, Model, Entity};
+use crate::data::slots::Slot;
 pub type ClothesCoordinates = Entity;
 
+/// Slot of a garment in a coordinate and its placement on the outfit board
+/// canvas
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ClothesLayout {
+    pub slot: Option<Slot>,
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub scale: Option<f64>,
+    /// Degrees, clockwise
+    pub rotation: Option<f64>,
+    pub z_index: Option<i32>,
+}
+
+impl ClothesLayout {
+    /// Problems with the canvas values
+    #[must_use]
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (name, value) in [("x", self.x), ("y", self.y), ("rotation", self.rotation)] {
+            if value.is_some_and(|value| !value.is_finite()) {
+                problems.push(format!("{name} must be a finite number"));
+            }
+        }
+        if self.scale.is_some_and(|scale| !scale.is_finite() || scale <= 0.0) {
+            problems.push("scale must be greater than 0".to_string());
+        }
+        problems
+    }
+
+    /// Problems with placing a garment of `category` in this layout's slot
+    #[must_use]
+    pub fn category_problems(&self, category: &str) -> Vec<String> {
+        match self.slot {
+            Some(slot) if !slot.accepts(category) => vec![format!(
+                "a {category} garment cannot be placed in the {} slot",
+                slot.as_str()
+            )],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Where a garment sits in a coordinate, with the notes on it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ClothesPlacement {
+    pub clothes_id: i32,
+    #[serde(flatten)]
+    pub layout: ClothesLayout,
+    pub notes: Option<String>,
+}
+
+impl From<&Model> for ClothesPlacement {
+    fn from(link: &Model) -> Self {
+        Self {
+            clothes_id: link.clothes_id,
+            layout: link.layout(),
+            notes: link.notes.clone(),
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl ActiveModelBehavior for ActiveModel {
     async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
@@ -19,10 +83,33 @@ impl ActiveModelBehavior for ActiveModel {
 }
 
 // implement your read-oriented logic here
-impl Model {}
+impl Model {
+    /// Slot and canvas placement of the garment
+    #[must_use]
+    pub fn layout(&self) -> ClothesLayout {
+        ClothesLayout {
+            slot: self.slot.as_deref().and_then(Slot::parse),
+            x: self.x,
+            y: self.y,
+            scale: self.scale,
+            rotation: self.rotation,
+            z_index: self.z_index,
+        }
+    }
+}
 
 // implement your write-oriented logic here
-impl ActiveModel {}
+impl ActiveModel {
+    /// Replace the slot and canvas placement
+    pub fn set_layout(&mut self, layout: &ClothesLayout) {
+        self.slot = sea_orm::ActiveValue::Set(layout.slot.map(|slot| slot.as_str().to_string()));
+        self.x = sea_orm::ActiveValue::Set(layout.x);
+        self.y = sea_orm::ActiveValue::Set(layout.y);
+        self.scale = sea_orm::ActiveValue::Set(layout.scale);
+        self.rotation = sea_orm::ActiveValue::Set(layout.rotation);
+        self.z_index = sea_orm::ActiveValue::Set(layout.z_index);
+    }
+}
 
 // implement your custom finders, selectors oriented logic here
 impl Entity {}
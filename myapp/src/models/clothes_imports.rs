@@ -0,0 +1,672 @@
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use sea_orm::sea_query::Expr;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+pub use super::_entities::clothes_imports::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes_coordinates, coordinates},
+    clothes::{self, CreateClothesParams},
+    coordinates::season_list,
+    wardrobe_exports::ExportCoordinate,
+};
+use crate::data::{
+    colors,
+    money::{Currency, Money},
+    vocabularies::{Occasion, Style},
+};
+
+/// Number of rows inserted per batch (and progress update) of a background
+/// import
+const PROGRESS_INTERVAL: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ImportStatus {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportClothesParams {
+    pub format: ImportFormat,
+    /// Raw CSV text, a JSON array of objects, or a JSON wardrobe export
+    pub data: String,
+    /// Source column name -> `CreateClothesParams` field name. Columns that
+    /// are not mapped are matched by name (case-insensitive, spaces become
+    /// underscores).
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Owner of the coordinates restored from a wardrobe export
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RowError {
+    /// 1-based index of the data row (the CSV header is not counted), 0 for
+    /// errors in the coordinates of a wardrobe export
+    pub row: usize,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub valid_rows: usize,
+    pub imported_rows: usize,
+    /// Coordinates of a wardrobe export, restored along with the clothes
+    pub coordinates: usize,
+    pub errors: Vec<RowError>,
+}
+
+/// A coordinate of a wardrobe export with its clothes resolved to rows
+#[derive(Debug)]
+pub struct ImportCoordinate {
+    pub coordinate: ExportCoordinate,
+    /// Index into `ParsedImport::rows` of each item, matching
+    /// `coordinate.clothes`
+    pub rows: Vec<usize>,
+}
+
+/// Rows of an upload, each either ready to insert or with its errors
+pub struct ParsedImport {
+    pub rows: Vec<Result<CreateClothesParams, Vec<RowError>>>,
+    pub coordinates: Vec<ImportCoordinate>,
+    /// Errors in `coordinates`, such as references to unknown clothes
+    pub coordinate_errors: Vec<RowError>,
+}
+
+impl ParsedImport {
+    #[must_use]
+    pub fn errors(&self) -> Vec<RowError> {
+        self.rows
+            .iter()
+            .filter_map(|row| row.as_ref().err())
+            .flatten()
+            .chain(&self.coordinate_errors)
+            .cloned()
+            .collect()
+    }
+
+    #[must_use]
+    pub fn valid_rows(&self) -> usize {
+        self.rows.iter().filter(|row| row.is_ok()).count()
+    }
+
+    #[must_use]
+    pub fn report(&self, dry_run: bool, imported_rows: usize) -> ImportReport {
+        ImportReport {
+            dry_run,
+            total_rows: self.rows.len(),
+            valid_rows: self.valid_rows(),
+            imported_rows,
+            coordinates: self.coordinates.len(),
+            errors: self.errors(),
+        }
+    }
+}
+
+const REQUIRED_FIELDS: [&str; 6] = ["name", "brand", "category", "size", "color", "price"];
+
+/// Parse and validate every row of an upload
+///
+/// # Errors
+///
+/// When the data is not valid CSV, or neither a JSON array of objects nor a
+/// wardrobe export
+pub fn parse(
+    format: ImportFormat,
+    data: &str,
+    mapping: &HashMap<String, String>,
+) -> ModelResult<ParsedImport> {
+    let (records, coordinates) = match format {
+        ImportFormat::Csv => (read_csv(data)?, Vec::new()),
+        ImportFormat::Json => read_json(data)?,
+    };
+    let (coordinates, coordinate_errors) = resolve_coordinates(&records, coordinates);
+    let mapping: HashMap<String, String> = mapping
+        .iter()
+        .map(|(from, to)| (normalize_key(from), to.clone()))
+        .collect();
+
+    let rows = records
+        .into_iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let record = record
+                .into_iter()
+                .map(|(key, value)| {
+                    let key = normalize_key(&key);
+                    (mapping.get(&key).cloned().unwrap_or(key), value)
+                })
+                .collect();
+            validate_row(index + 1, record)
+        })
+        .collect();
+
+    Ok(ParsedImport {
+        rows,
+        coordinates,
+        coordinate_errors,
+    })
+}
+
+/// Match the clothes of each coordinate to the row carrying the same `pid`
+fn resolve_coordinates(
+    records: &[Map<String, Value>],
+    coordinates: Vec<ExportCoordinate>,
+) -> (Vec<ImportCoordinate>, Vec<RowError>) {
+    let rows: HashMap<Uuid, usize> = records
+        .iter()
+        .enumerate()
+        .filter_map(|(index, record)| {
+            let pid = record.get("pid")?.as_str()?;
+            Some((Uuid::parse_str(pid.trim()).ok()?, index))
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let coordinates = coordinates
+        .into_iter()
+        .enumerate()
+        .map(|(index, coordinate)| {
+            let mut resolved = Vec::with_capacity(coordinate.clothes.len());
+            for item in &coordinate.clothes {
+                match rows.get(&item.clothes_pid) {
+                    Some(row) => resolved.push(*row),
+                    None => errors.push(RowError {
+                        row: 0,
+                        field: Some(format!("coordinates[{index}].clothes")),
+                        message: format!("Unknown clothes pid: {}", item.clothes_pid),
+                    }),
+                }
+            }
+            if coordinate.name.trim().is_empty() {
+                errors.push(RowError {
+                    row: 0,
+                    field: Some(format!("coordinates[{index}].name")),
+                    message: "Name must not be empty".to_string(),
+                });
+            }
+            ImportCoordinate {
+                coordinate,
+                rows: resolved,
+            }
+        })
+        .collect();
+
+    (coordinates, errors)
+}
+
+fn normalize_key(key: &str) -> String {
+    key.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Raw rows of an upload, keyed by column name
+type Records = Vec<Map<String, Value>>;
+
+fn read_csv(data: &str) -> ModelResult<Records> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| ModelError::msg(&format!("Invalid CSV header: {e}")))?
+        .clone();
+
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(|e| ModelError::msg(&format!("Invalid CSV: {e}")))?;
+            Ok(headers
+                .iter()
+                .zip(record.iter())
+                .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                .collect())
+        })
+        .collect()
+}
+
+/// The part of a wardrobe export read by the import. Clothes are kept as raw
+/// rows so they go through the same validation as any other upload.
+#[derive(Deserialize)]
+struct WardrobeBackup {
+    clothes: Vec<Value>,
+    #[serde(default)]
+    coordinates: Vec<ExportCoordinate>,
+}
+
+fn read_json(data: &str) -> ModelResult<(Records, Vec<ExportCoordinate>)> {
+    let invalid = |e: serde_json::Error| ModelError::msg(&format!("Invalid JSON: {e}"));
+    let (rows, coordinates) = match serde_json::from_str(data).map_err(invalid)? {
+        Value::Array(rows) => (rows, Vec::new()),
+        document @ Value::Object(_) => {
+            let backup: WardrobeBackup = serde_json::from_value(document).map_err(invalid)?;
+            (backup.clothes, backup.coordinates)
+        }
+        _ => {
+            return Err(ModelError::msg(
+                "Invalid JSON: expected an array of rows or a wardrobe export",
+            ))
+        }
+    };
+    let rows = rows
+        .into_iter()
+        .map(|row| match row {
+            Value::Object(map) => Ok(map),
+            _ => Err(ModelError::msg("Invalid JSON: every row must be an object")),
+        })
+        .collect::<ModelResult<_>>()?;
+    Ok((rows, coordinates))
+}
+
+/// Coerce spreadsheet values (everything is a string in CSV) into the types
+/// expected by `CreateClothesParams`
+fn coerce(field: &str, value: Value) -> Result<Value, String> {
+    // Prices are decimal strings, JSON numbers are taken as written
+    if let ("price", Value::Number(number)) = (field, &value) {
+        return Ok(Value::String(number.to_string()));
+    }
+    let Value::String(text) = value else {
+        return Ok(value);
+    };
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(Value::Null);
+    }
+    match field {
+        "currency" => Currency::parse(text)
+            .map(|currency| Value::String(currency.code().to_string()))
+            .ok_or_else(|| format!("'{text}' is not an ISO-4217 currency code")),
+        "purchase_date" => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(|_| Value::String(text.to_string()))
+            .map_err(|_| format!("'{text}' is not a date (YYYY-MM-DD)")),
+        "stock_quantity" => text
+            .parse::<i32>()
+            .map(Value::from)
+            .map_err(|_| format!("'{text}' is not a valid quantity")),
+        _ => Ok(Value::String(text.to_string())),
+    }
+}
+
+fn validate_row(
+    row: usize,
+    record: Map<String, Value>,
+) -> Result<CreateClothesParams, Vec<RowError>> {
+    let error = |field: &str, message: String| RowError {
+        row,
+        field: Some(field.to_string()),
+        message,
+    };
+    let mut errors = Vec::new();
+    let mut fields = Map::new();
+
+    for (field, value) in record {
+        match coerce(&field, value) {
+            Ok(value) => {
+                fields.insert(field, value);
+            }
+            Err(message) => errors.push(error(&field, message)),
+        }
+    }
+    for field in REQUIRED_FIELDS {
+        let invalid = errors.iter().any(|e| e.field.as_deref() == Some(field));
+        if !invalid && fields.get(field).is_none_or(Value::is_null) {
+            errors.push(error(field, "is required".to_string()));
+        }
+    }
+    let currency = fields
+        .get("currency")
+        .and_then(Value::as_str)
+        .and_then(Currency::parse)
+        .unwrap_or_default();
+    if let Some(Err(message)) = fields
+        .get("price")
+        .and_then(Value::as_str)
+        .map(|price| Money::parse(price, currency))
+    {
+        errors.push(error("price", message));
+    }
+    // Caught here so a dry run reports it, the import would fail on it
+    if fields
+        .get("stock_quantity")
+        .and_then(Value::as_i64)
+        .is_some_and(|quantity| quantity < 0)
+    {
+        errors.push(error("stock_quantity", "must not be negative".to_string()));
+    }
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    // Spreadsheets rarely track stock, default to a single owned item
+    let quantity = fields
+        .get("stock_quantity")
+        .and_then(Value::as_i64)
+        .unwrap_or(1);
+    fields
+        .entry("stock_quantity")
+        .and_modify(|v| {
+            if v.is_null() {
+                *v = Value::from(quantity);
+            }
+        })
+        .or_insert_with(|| Value::from(quantity));
+
+    let params: CreateClothesParams = serde_json::from_value(Value::Object(fields))
+        .map_err(|e| vec![RowError { row, field: None, message: e.to_string() }])?;
+
+    let validator = clothes::Validator {
+        name: params.name.clone(),
+        brand: params.brand.clone(),
+        category: params.category.clone(),
+    };
+    if let Err(validation) = validator.validate() {
+        for (field, field_errors) in validation.field_errors() {
+            for field_error in field_errors {
+                let message = field_error
+                    .message
+                    .as_ref()
+                    .map_or_else(|| field_error.code.to_string(), ToString::to_string);
+                errors.push(error(&field, message));
+            }
+        }
+    }
+    for (field, value) in [
+        ("primary_color", &params.primary_color),
+        ("secondary_color", &params.secondary_color),
+    ] {
+        match value {
+            Some(value) if colors::resolve(value).is_none() => {
+                errors.push(error(field, format!("Unknown color: {value}")));
+            }
+            _ => {}
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(params)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Insert all rows of a fully valid import, and the coordinates of a
+/// wardrobe export for `user_id`, in a single transaction
+///
+/// # Errors
+///
+/// When the import contains invalid rows, has coordinates but no `user_id`,
+/// or on DB errors, in which case nothing is imported
+pub async fn import(
+    db: &DatabaseConnection,
+    parsed: &ParsedImport,
+    user_id: Option<i32>,
+) -> ModelResult<usize> {
+    ensure_valid(parsed, user_id)?;
+
+    let txn = db.begin().await?;
+    let mut clothes_ids = Vec::with_capacity(parsed.rows.len());
+    for params in parsed.rows.iter().flatten() {
+        clothes_ids.push(clothes::Model::create(&txn, params).await?.id);
+    }
+    insert_coordinates(&txn, parsed, &clothes_ids, user_id).await?;
+    txn.commit().await?;
+
+    Ok(parsed.rows.len())
+}
+
+fn ensure_valid(parsed: &ParsedImport, user_id: Option<i32>) -> ModelResult<()> {
+    if !parsed.errors().is_empty() {
+        return Err(ModelError::msg("Import contains invalid rows"));
+    }
+    if !parsed.coordinates.is_empty() && user_id.is_none() {
+        return Err(ModelError::msg("user_id is required to import coordinates"));
+    }
+    Ok(())
+}
+
+/// Recreate the coordinates of a wardrobe export, `clothes_ids` holding the
+/// id of the clothes inserted for each row
+async fn insert_coordinates<C: ConnectionTrait>(
+    db: &C,
+    parsed: &ParsedImport,
+    clothes_ids: &[i32],
+    user_id: Option<i32>,
+) -> ModelResult<()> {
+    let Some(user_id) = user_id else {
+        return Ok(());
+    };
+    for ImportCoordinate { coordinate, rows } in &parsed.coordinates {
+        let inserted = coordinates::ActiveModel {
+            name: ActiveValue::set(coordinate.name.clone()),
+            description: ActiveValue::set(coordinate.description.clone()),
+            occasion: ActiveValue::set(
+                coordinate
+                    .occasion
+                    .as_deref()
+                    .and_then(Occasion::from_text)
+                    .map(|occasion| occasion.as_str().to_string()),
+            ),
+            seasons: ActiveValue::set(season_list(&coordinate.seasons)),
+            style: ActiveValue::set(
+                coordinate
+                    .style
+                    .as_deref()
+                    .and_then(Style::from_text)
+                    .map(|style| style.as_str().to_string()),
+            ),
+            user_id: ActiveValue::set(user_id),
+            is_favorite: ActiveValue::set(coordinate.is_favorite),
+            image_url: ActiveValue::set(coordinate.image_url.clone()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        for (item, row) in coordinate.clothes.iter().zip(rows) {
+            let mut link = clothes_coordinates::ActiveModel {
+                clothes_id: ActiveValue::set(clothes_ids[*row]),
+                coordinate_id: ActiveValue::set(inserted.id),
+                notes: ActiveValue::set(item.notes.clone()),
+                ..Default::default()
+            };
+            link.set_layout(&item.layout);
+            link.insert(db).await?;
+        }
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Create a queued import for a background worker
+    pub async fn create_queued(
+        db: &DatabaseConnection,
+        params: &ImportClothesParams,
+        total_rows: usize,
+    ) -> ModelResult<Self> {
+        let import = clothes_imports::ActiveModel {
+            format: ActiveValue::set(params.format.as_str().to_string()),
+            status: ActiveValue::set(ImportStatus::Queued.as_str().to_string()),
+            total_rows: ActiveValue::set(i32::try_from(total_rows).unwrap_or(i32::MAX)),
+            processed_rows: ActiveValue::set(0),
+            imported_rows: ActiveValue::set(0),
+            source: ActiveValue::set(Some(params.data.clone())),
+            mapping: ActiveValue::set(Some(serde_json::to_value(&params.mapping).map_err(
+                |e| ModelError::Any(e.into()),
+            )?)),
+            errors: ActiveValue::set(None),
+            user_id: ActiveValue::set(params.user_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(import)
+    }
+
+    /// Find import by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let import = clothes_imports::Entity::find()
+            .filter(clothes_imports::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        import.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Parse the stored source with the stored format and mapping
+    fn parse_source(&self) -> ModelResult<ParsedImport> {
+        let format = ImportFormat::parse(&self.format)
+            .ok_or_else(|| ModelError::msg(&format!("Unknown import format: {}", self.format)))?;
+        let mapping: HashMap<String, String> = self
+            .mapping
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| ModelError::Any(e.into()))?
+            .unwrap_or_default();
+        parse(format, self.source.as_deref().unwrap_or_default(), &mapping)
+    }
+
+    /// Run a queued import, recording progress as rows are inserted. Any
+    /// failure, including a source that cannot be parsed, marks the import
+    /// `failed` with its errors; the source is cleared either way.
+    pub async fn run(self, db: &DatabaseConnection) -> ModelResult<Self> {
+        let parsed = self.parse_source();
+        let user_id = self.user_id;
+
+        let mut active_model = self.into_active_model();
+        active_model.status = ActiveValue::set(ImportStatus::Running.as_str().to_string());
+        let import = active_model.update(db).await?;
+
+        let (result, mut errors) = match parsed {
+            Ok(parsed) => (
+                import_in_batches(db, import.id, &parsed, user_id).await,
+                parsed.errors(),
+            ),
+            Err(err) => (Err(err), Vec::new()),
+        };
+
+        let mut active_model = import.into_active_model();
+        active_model.source = ActiveValue::set(None);
+        match result {
+            Ok(imported) => {
+                let imported = i32::try_from(imported).unwrap_or(i32::MAX);
+                active_model.status = ActiveValue::set(ImportStatus::Completed.as_str().to_string());
+                active_model.processed_rows = ActiveValue::set(imported);
+                active_model.imported_rows = ActiveValue::set(imported);
+            }
+            Err(err) => {
+                if errors.is_empty() {
+                    errors.push(RowError { row: 0, field: None, message: err.to_string() });
+                }
+                active_model.status = ActiveValue::set(ImportStatus::Failed.as_str().to_string());
+                active_model.errors = ActiveValue::set(Some(
+                    serde_json::to_value(errors).map_err(|e| ModelError::Any(e.into()))?,
+                ));
+            }
+        }
+        Ok(active_model.update(db).await?)
+    }
+}
+
+/// Insert the rows of a background import in a single transaction, so a
+/// failure leaves nothing behind. Progress is recorded in the same
+/// transaction every [`PROGRESS_INTERVAL`] rows, so a failed import does not
+/// keep the progress of rows that were rolled back.
+/// Coordinates of a wardrobe export are inserted once all clothes are in.
+async fn import_in_batches(
+    db: &DatabaseConnection,
+    import_id: i32,
+    parsed: &ParsedImport,
+    user_id: Option<i32>,
+) -> ModelResult<usize> {
+    ensure_valid(parsed, user_id)?;
+
+    let rows: Vec<&CreateClothesParams> = parsed.rows.iter().flatten().collect();
+    let mut clothes_ids = Vec::with_capacity(rows.len());
+    let mut processed = 0;
+    let txn = db.begin().await?;
+    for batch in rows.chunks(PROGRESS_INTERVAL) {
+        for params in batch {
+            clothes_ids.push(clothes::Model::create(&txn, params).await?.id);
+        }
+
+        processed += batch.len();
+        clothes_imports::Entity::update_many()
+            .col_expr(
+                clothes_imports::Column::ProcessedRows,
+                Expr::value(i32::try_from(processed).unwrap_or(i32::MAX)),
+            )
+            .filter(clothes_imports::Column::Id.eq(import_id))
+            .exec(&txn)
+            .await?;
+    }
+    insert_coordinates(&txn, parsed, &clothes_ids, user_id).await?;
+    txn.commit().await?;
+
+    Ok(processed)
+}
@@ -0,0 +1,184 @@
+//! Lifecycle history of the clothes: every state transition with the
+//! condition the garment was in and an optional note.
+
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+pub use super::_entities::clothes_state_changes::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::clothes,
+    coordinate_rules::FieldError,
+    inventory_movements::{self, MovementReason},
+};
+use crate::data::lifecycle::{Condition, LifecycleState};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransitionParams {
+    pub state: LifecycleState,
+    /// Condition after the transition, unchanged when omitted
+    pub condition: Option<Condition>,
+    pub note: Option<String>,
+    /// Who borrows the garment, required when lending it
+    pub lent_to: Option<String>,
+}
+
+impl TransitionParams {
+    /// Field-level problems with moving a garment from `from` to the
+    /// requested state
+    #[must_use]
+    pub fn problems(&self, from: LifecycleState) -> Vec<FieldError> {
+        let mut problems = Vec::new();
+        if !from.can_become(self.state) {
+            problems.push(FieldError::new(
+                "state",
+                format!(
+                    "cannot change from {} to {}",
+                    from.as_str(),
+                    self.state.as_str()
+                ),
+            ));
+        }
+        let lent_to = self.lent_to.as_deref().map(str::trim).unwrap_or_default();
+        match (self.state, lent_to.is_empty()) {
+            (LifecycleState::Lent, true) => problems.push(FieldError::new(
+                "lent_to",
+                "is required when lending a garment",
+            )),
+            (LifecycleState::Lent, false) | (_, true) => {}
+            (_, false) => problems.push(FieldError::new(
+                "lent_to",
+                "is only allowed when lending a garment",
+            )),
+        }
+        problems
+    }
+}
+
+/// A transition in the lifecycle history of a garment
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct StateChange {
+    pub from_state: Option<LifecycleState>,
+    pub to_state: Option<LifecycleState>,
+    pub condition: Option<Condition>,
+    pub note: Option<String>,
+    pub changed_at: DateTimeWithTimeZone,
+}
+
+impl From<&Model> for StateChange {
+    fn from(change: &Model) -> Self {
+        Self {
+            from_state: LifecycleState::parse(&change.from_state),
+            to_state: LifecycleState::parse(&change.to_state),
+            condition: change.condition.as_deref().and_then(Condition::parse),
+            note: change.note.clone(),
+            changed_at: change.created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Move a garment to another lifecycle state and record the transition.
+    /// Garments that leave the wardrobe for good are taken out of the
+    /// inventory.
+    ///
+    /// # Errors
+    ///
+    /// When the transition is not allowed from the garment's current state
+    pub async fn transition(
+        db: &DatabaseConnection,
+        item: &clothes::Model,
+        params: &TransitionParams,
+    ) -> ModelResult<(clothes::Model, Self)> {
+        let txn = db.begin().await?;
+        // Lock the garment, a concurrent transition may have moved it on
+        let item = clothes::Entity::find_by_id(item.id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let from = item.lifecycle();
+        if let Some(problem) = params.problems(from).first() {
+            return Err(ModelError::msg(&format!(
+                "{} {}",
+                problem.field, problem.message
+            )));
+        }
+
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let condition = params
+            .condition
+            .map(|condition| condition.as_str().to_string())
+            .or_else(|| item.condition.clone());
+        let mut active_model = item.into_active_model();
+        active_model.lifecycle_state = ActiveValue::set(params.state.as_str().to_string());
+        active_model.state_changed_at = ActiveValue::set(Some(now));
+        active_model.condition = ActiveValue::set(condition.clone());
+        active_model.lent_to = ActiveValue::set(
+            params
+                .lent_to
+                .as_deref()
+                .map(str::trim)
+                .filter(|lent_to| !lent_to.is_empty())
+                .map(ToString::to_string),
+        );
+        let mut item = active_model.update(&txn).await?;
+        // Garments that leave the wardrobe leave the inventory with them
+        if params.state.is_final() && item.stock_quantity > 0 {
+            let (reason, note) = match params.state {
+                LifecycleState::Donated => (MovementReason::Donated, None),
+                _ => (MovementReason::Adjustment, Some(params.state.as_str().to_string())),
+            };
+            let quantity = reason.change(item.stock_quantity);
+            (item, _) = inventory_movements::Model::append(&txn, item, reason, quantity, note)
+                .await?;
+        }
+
+        let change = ActiveModel {
+            clothes_id: ActiveValue::set(item.id),
+            from_state: ActiveValue::set(from.as_str().to_string()),
+            to_state: ActiveValue::set(params.state.as_str().to_string()),
+            condition: ActiveValue::set(condition),
+            note: ActiveValue::set(params.note.clone()),
+            created_at: ActiveValue::set(now),
+            updated_at: ActiveValue::set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        txn.commit().await?;
+        Ok((item, change))
+    }
+
+    /// Lifecycle history of a garment, oldest first
+    pub async fn history(
+        db: &DatabaseConnection,
+        item: &clothes::Model,
+    ) -> ModelResult<Vec<StateChange>> {
+        Ok(clothes_state_changes::Entity::find()
+            .filter(clothes_state_changes::Column::ClothesId.eq(item.id))
+            .order_by_asc(clothes_state_changes::Column::Id)
+            .all(db)
+            .await?
+            .iter()
+            .map(StateChange::from)
+            .collect())
+    }
+}
@@ -0,0 +1,270 @@
+//! Per-user rules for the garments of a coordinate.
+//!
+//! Limits per slot and forbidden combinations are enforced when clothes are
+//! added to a coordinate. Required slots are not enforced; they make up the
+//! completeness score that nudges users to finish their outfits.
+
+use std::collections::{BTreeMap, HashMap};
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::_entities::clothes;
+pub use super::_entities::coordinate_rules::{self, ActiveModel, Entity, Model};
+use crate::data::slots::Slot;
+
+/// A validation error for one field of a request
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FieldError {
+    /// Path of the field, such as `clothes_ids[2]`
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    #[must_use]
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Garments that may not be worn together. Each term is a slot name or a
+/// word of the garment category, such as `["sandals", "socks"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ForbiddenCombination {
+    pub items: Vec<String>,
+    /// Shown instead of the default message
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl ForbiddenCombination {
+    fn matches(term: &str, garment: &OutfitGarment<'_>) -> bool {
+        match Slot::parse(term) {
+            Some(slot) => garment.slot == Some(slot),
+            None => garment
+                .clothes
+                .category
+                .to_lowercase()
+                .contains(&term.trim().to_lowercase()),
+        }
+    }
+
+    /// Whether every term matches a garment of the outfit
+    #[must_use]
+    pub fn is_violated_by(&self, garments: &[OutfitGarment<'_>]) -> bool {
+        !self.items.is_empty()
+            && self
+                .items
+                .iter()
+                .all(|term| garments.iter().any(|garment| Self::matches(term, garment)))
+    }
+}
+
+/// A garment of an outfit and the slot it fills
+#[derive(Debug, Clone, Copy)]
+pub struct OutfitGarment<'a> {
+    pub slot: Option<Slot>,
+    pub clothes: &'a clothes::Model,
+}
+
+/// How complete an outfit is with respect to the required slots
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Completeness {
+    /// Share of required slots that are filled, from 0 to 1
+    pub score: f64,
+    pub missing_slots: Vec<Slot>,
+}
+
+/// Rules for the garments of a user's coordinates
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SlotRules {
+    pub required_slots: Vec<Slot>,
+    /// Slots without an entry take any number of garments
+    pub max_per_slot: BTreeMap<Slot, usize>,
+    pub forbidden_combinations: Vec<ForbiddenCombination>,
+}
+
+impl Default for SlotRules {
+    fn default() -> Self {
+        Self {
+            required_slots: vec![Slot::Top, Slot::Bottom, Slot::Shoes],
+            max_per_slot: [
+                Slot::Head,
+                Slot::Outer,
+                Slot::Bottom,
+                Slot::Shoes,
+                Slot::Bag,
+            ]
+            .into_iter()
+            .map(|slot| (slot, 1))
+            .collect(),
+            forbidden_combinations: Vec::new(),
+        }
+    }
+}
+
+impl SlotRules {
+    /// Problems with the rule set itself
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        let mut problems = Vec::new();
+        for slot in &self.required_slots {
+            if self.max_per_slot.get(slot) == Some(&0) {
+                problems.push(FieldError::new(
+                    format!("max_per_slot.{}", slot.as_str()),
+                    format!("{} is required and cannot be limited to 0", slot.as_str()),
+                ));
+            }
+        }
+        for (index, combination) in self.forbidden_combinations.iter().enumerate() {
+            if combination.items.len() < 2
+                || combination.items.iter().any(|term| term.trim().is_empty())
+            {
+                problems.push(FieldError::new(
+                    format!("forbidden_combinations[{index}].items"),
+                    "must list at least two slots or categories",
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Limits and forbidden combinations broken by an outfit, reported on
+    /// `field`
+    #[must_use]
+    pub fn violations(&self, garments: &[OutfitGarment<'_>], field: &str) -> Vec<FieldError> {
+        let mut counts: BTreeMap<Slot, usize> = BTreeMap::new();
+        for slot in garments.iter().filter_map(|garment| garment.slot) {
+            *counts.entry(slot).or_default() += 1;
+        }
+
+        let mut violations = Vec::new();
+        for (slot, count) in counts {
+            if let Some(max) = self.max_per_slot.get(&slot).filter(|max| count > **max) {
+                violations.push(FieldError::new(
+                    field,
+                    format!("at most {max} {} allowed, got {count}", slot.as_str()),
+                ));
+            }
+        }
+        for combination in &self.forbidden_combinations {
+            if combination.is_violated_by(garments) {
+                let message = combination.message.clone().unwrap_or_else(|| {
+                    format!(
+                        "{} cannot be worn together",
+                        combination.items.join(" and ")
+                    )
+                });
+                violations.push(FieldError::new(field, message));
+            }
+        }
+        violations
+    }
+
+    /// Completeness of an outfit whose garments fill `slots`
+    #[must_use]
+    pub fn completeness(&self, slots: impl IntoIterator<Item = Option<Slot>>) -> Completeness {
+        let filled: Vec<Slot> = slots.into_iter().flatten().collect();
+        let missing_slots: Vec<Slot> = self
+            .required_slots
+            .iter()
+            .filter(|slot| !filled.contains(slot))
+            .copied()
+            .collect();
+        let score = if self.required_slots.is_empty() {
+            1.0
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let share = 1.0 - missing_slots.len() as f64 / self.required_slots.len() as f64;
+            (share * 100.0).round() / 100.0
+        };
+        Completeness {
+            score,
+            missing_slots,
+        }
+    }
+
+    /// Rules of a user, the defaults when none were saved
+    pub async fn for_user<C: ConnectionTrait>(db: &C, user_id: i32) -> ModelResult<Self> {
+        Ok(Self::for_users(db, &[user_id])
+            .await?
+            .remove(&user_id)
+            .unwrap_or_default())
+    }
+
+    /// Saved rules of several users. Users without saved rules are left out.
+    pub async fn for_users<C: ConnectionTrait>(
+        db: &C,
+        user_ids: &[i32],
+    ) -> ModelResult<HashMap<i32, Self>> {
+        coordinate_rules::Entity::find()
+            .filter(coordinate_rules::Column::UserId.is_in(user_ids.iter().copied()))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|saved| Ok((saved.user_id, saved.slot_rules()?)))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// The saved rules
+    ///
+    /// # Errors
+    ///
+    /// When the stored JSON does not match [`SlotRules`]
+    pub fn slot_rules(&self) -> ModelResult<SlotRules> {
+        serde_json::from_value(self.rules.clone())
+            .map_err(|e| ModelError::msg(&format!("Invalid coordinate rules: {e}")))
+    }
+
+    /// Save the rules of a user, replacing any saved before
+    pub async fn upsert(
+        db: &DatabaseConnection,
+        user_id: i32,
+        rules: &SlotRules,
+    ) -> ModelResult<Self> {
+        let rules = serde_json::to_value(rules).map_err(|e| ModelError::Any(e.into()))?;
+        let existing = coordinate_rules::Entity::find()
+            .filter(coordinate_rules::Column::UserId.eq(user_id))
+            .one(db)
+            .await?;
+        Ok(match existing {
+            Some(existing) => {
+                let mut active_model = existing.into_active_model();
+                active_model.rules = ActiveValue::set(rules);
+                active_model.update(db).await?
+            }
+            None => {
+                coordinate_rules::ActiveModel {
+                    user_id: ActiveValue::set(user_id),
+                    rules: ActiveValue::set(rules),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?
+            }
+        })
+    }
+}
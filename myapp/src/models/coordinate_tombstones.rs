@@ -0,0 +1,127 @@
+//! Links of trashed garments with their coordinates, kept until the trash is
+//! purged so restoring a garment puts it back into its outfits. Garments
+//! deleted with the `detach` delete policy leave a placeholder, so outfits
+//! show an "item removed" slot instead of silently losing a piece.
+
+use std::collections::HashMap;
+
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+
+pub use super::_entities::coordinate_tombstones::{self, ActiveModel, Entity, Model};
+use super::_entities::{clothes, clothes_coordinates};
+use crate::data::slots::Slot;
+
+/// Label shown in place of a removed garment
+pub const REMOVED_LABEL: &str = "item removed";
+
+/// A garment that was removed from a coordinate by deleting it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RemovedClothes {
+    pub label: String,
+    pub clothes_pid: Uuid,
+    pub name: String,
+    pub category: String,
+    pub slot: Option<Slot>,
+    pub removed_at: DateTimeWithTimeZone,
+}
+
+impl From<&Model> for RemovedClothes {
+    fn from(tombstone: &Model) -> Self {
+        Self {
+            label: REMOVED_LABEL.to_string(),
+            clothes_pid: tombstone.clothes_pid,
+            name: tombstone.name.clone(),
+            category: tombstone.category.clone(),
+            slot: tombstone.slot.as_deref().and_then(Slot::parse),
+            removed_at: tombstone.created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Keep a garment's link in place of it, shown as an "item removed"
+    /// slot when `placeholder` is set
+    pub async fn bury<C: ConnectionTrait>(
+        db: &C,
+        link: &clothes_coordinates::Model,
+        item: &clothes::Model,
+        placeholder: bool,
+    ) -> ModelResult<Self> {
+        Ok(ActiveModel {
+            coordinate_id: ActiveValue::set(link.coordinate_id),
+            clothes_pid: ActiveValue::set(item.pid),
+            name: ActiveValue::set(item.name.clone()),
+            category: ActiveValue::set(item.category.clone()),
+            slot: ActiveValue::set(link.slot.clone()),
+            placeholder: ActiveValue::set(placeholder),
+            notes: ActiveValue::set(link.notes.clone()),
+            x: ActiveValue::set(link.x),
+            y: ActiveValue::set(link.y),
+            scale: ActiveValue::set(link.scale),
+            rotation: ActiveValue::set(link.rotation),
+            z_index: ActiveValue::set(link.z_index),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    /// The link this tombstone was kept for, pointing at `clothes_id`
+    #[must_use]
+    pub fn link(&self, clothes_id: i32) -> clothes_coordinates::ActiveModel {
+        clothes_coordinates::ActiveModel {
+            clothes_id: ActiveValue::set(clothes_id),
+            coordinate_id: ActiveValue::set(self.coordinate_id),
+            notes: ActiveValue::set(self.notes.clone()),
+            slot: ActiveValue::set(self.slot.clone()),
+            x: ActiveValue::set(self.x),
+            y: ActiveValue::set(self.y),
+            scale: ActiveValue::set(self.scale),
+            rotation: ActiveValue::set(self.rotation),
+            z_index: ActiveValue::set(self.z_index),
+            ..Default::default()
+        }
+    }
+
+    /// Removed garments of several coordinates at once, keyed by coordinate
+    /// ID. Coordinates without any are left out.
+    pub async fn for_coordinates<C: ConnectionTrait>(
+        db: &C,
+        coordinate_ids: &[i32],
+    ) -> ModelResult<HashMap<i32, Vec<RemovedClothes>>> {
+        let tombstones = coordinate_tombstones::Entity::find()
+            .filter(
+                coordinate_tombstones::Column::CoordinateId.is_in(coordinate_ids.iter().copied()),
+            )
+            .filter(coordinate_tombstones::Column::Placeholder.eq(true))
+            .order_by_asc(coordinate_tombstones::Column::Id)
+            .all(db)
+            .await?;
+        let mut by_coordinate: HashMap<i32, Vec<RemovedClothes>> = HashMap::new();
+        for tombstone in &tombstones {
+            by_coordinate
+                .entry(tombstone.coordinate_id)
+                .or_default()
+                .push(RemovedClothes::from(tombstone));
+        }
+        Ok(by_coordinate)
+    }
+}
@@ -0,0 +1,309 @@
+//! Version history of coordinates.
+//!
+//! Every change to a coordinate's metadata or garments records a snapshot.
+//! Snapshots can be compared with each other and restored. Images are not
+//! versioned; the composite image is re-rendered from the restored garments.
+
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub use super::_entities::coordinate_versions::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes_coordinates, coordinates},
+    clothes_coordinates::ClothesLayout,
+};
+use crate::data::seasons::Season;
+
+/// What produced a version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionChange {
+    Created,
+    Updated,
+    ClothesAdded,
+    ClothesRemoved,
+    LayoutUpdated,
+    Cloned,
+    Restored,
+}
+
+impl VersionChange {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Updated => "updated",
+            Self::ClothesAdded => "clothes_added",
+            Self::ClothesRemoved => "clothes_removed",
+            Self::LayoutUpdated => "layout_updated",
+            Self::Cloned => "cloned",
+            Self::Restored => "restored",
+        }
+    }
+}
+
+/// A garment of a snapshot
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SnapshotClothes {
+    pub clothes_id: i32,
+    #[serde(flatten)]
+    pub layout: ClothesLayout,
+    pub notes: Option<String>,
+}
+
+impl From<&clothes_coordinates::Model> for SnapshotClothes {
+    fn from(link: &clothes_coordinates::Model) -> Self {
+        Self {
+            clothes_id: link.clothes_id,
+            layout: link.layout(),
+            notes: link.notes.clone(),
+        }
+    }
+}
+
+/// Metadata and garments of a coordinate at one point in time
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct CoordinateSnapshot {
+    pub name: String,
+    pub description: Option<String>,
+    pub occasion: Option<String>,
+    pub seasons: Vec<Season>,
+    pub style: Option<String>,
+    pub is_favorite: bool,
+    /// Ordered by clothes ID
+    pub clothes: Vec<SnapshotClothes>,
+}
+
+/// A metadata field that differs between two versions
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MetadataChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// A garment whose slot or canvas placement changed
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Repositioned {
+    pub clothes_id: i32,
+    pub from: ClothesLayout,
+    pub to: ClothesLayout,
+}
+
+/// Differences between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct SnapshotChanges {
+    pub metadata: Vec<MetadataChange>,
+    pub added: Vec<SnapshotClothes>,
+    pub removed: Vec<SnapshotClothes>,
+    pub repositioned: Vec<Repositioned>,
+}
+
+/// Versions to compare, the latest when `to` is not given
+#[derive(Debug, Deserialize)]
+pub struct DiffQuery {
+    pub from: i32,
+    pub to: Option<i32>,
+}
+
+/// Differences between two versions of a coordinate
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct VersionDiff {
+    pub from: i32,
+    pub to: i32,
+    #[serde(flatten)]
+    pub changes: SnapshotChanges,
+}
+
+impl CoordinateSnapshot {
+    #[must_use]
+    pub fn new(coordinate: &coordinates::Model, links: &[clothes_coordinates::Model]) -> Self {
+        let mut clothes: Vec<SnapshotClothes> = links.iter().map(SnapshotClothes::from).collect();
+        clothes.sort_by_key(|item| item.clothes_id);
+        Self {
+            name: coordinate.name.clone(),
+            description: coordinate.description.clone(),
+            occasion: coordinate.occasion.clone(),
+            seasons: coordinate.seasons(),
+            style: coordinate.style.clone(),
+            is_favorite: coordinate.is_favorite,
+            clothes,
+        }
+    }
+
+    /// Changes that turn this snapshot into `to`
+    #[must_use]
+    pub fn changes(&self, to: &Self) -> SnapshotChanges {
+        let metadata = |snapshot: &Self| -> Map<String, Value> {
+            match serde_json::to_value(snapshot) {
+                Ok(Value::Object(mut fields)) => {
+                    fields.remove("clothes");
+                    fields
+                }
+                _ => Map::new(),
+            }
+        };
+        let (before, after) = (metadata(self), metadata(to));
+        let metadata = before
+            .iter()
+            .filter(|(field, value)| after.get(*field) != Some(value))
+            .map(|(field, value)| MetadataChange {
+                field: field.clone(),
+                from: value.clone(),
+                to: after.get(field).cloned().unwrap_or(Value::Null),
+            })
+            .collect();
+
+        let find = |snapshot: &Self, clothes_id: i32| {
+            snapshot
+                .clothes
+                .iter()
+                .find(|item| item.clothes_id == clothes_id)
+                .cloned()
+        };
+        SnapshotChanges {
+            metadata,
+            added: to
+                .clothes
+                .iter()
+                .filter(|item| find(self, item.clothes_id).is_none())
+                .cloned()
+                .collect(),
+            removed: self
+                .clothes
+                .iter()
+                .filter(|item| find(to, item.clothes_id).is_none())
+                .cloned()
+                .collect(),
+            repositioned: self
+                .clothes
+                .iter()
+                .filter_map(|item| {
+                    let moved = find(to, item.clothes_id)?;
+                    (moved.layout != item.layout).then(|| Repositioned {
+                        clothes_id: item.clothes_id,
+                        from: item.layout.clone(),
+                        to: moved.layout,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Record the current state of a coordinate as a new version. Nothing is
+    /// recorded when it matches the latest version.
+    pub async fn record<C: ConnectionTrait>(
+        db: &C,
+        coordinate: &coordinates::Model,
+        change: VersionChange,
+    ) -> ModelResult<Option<Self>> {
+        let links = clothes_coordinates::Entity::find()
+            .filter(clothes_coordinates::Column::CoordinateId.eq(coordinate.id))
+            .all(db)
+            .await?;
+        let snapshot = CoordinateSnapshot::new(coordinate, &links);
+
+        let latest = coordinate_versions::Entity::find()
+            .filter(coordinate_versions::Column::CoordinateId.eq(coordinate.id))
+            .order_by_desc(coordinate_versions::Column::Version)
+            .one(db)
+            .await?;
+        if let Some(latest) = &latest
+            && latest.snapshot()? == snapshot
+        {
+            return Ok(None);
+        }
+
+        let version = coordinate_versions::ActiveModel {
+            coordinate_id: ActiveValue::set(coordinate.id),
+            version: ActiveValue::set(latest.map_or(1, |latest| latest.version + 1)),
+            change: ActiveValue::set(change.as_str().to_string()),
+            snapshot: ActiveValue::set(
+                serde_json::to_value(&snapshot).map_err(|e| ModelError::Any(e.into()))?,
+            ),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(Some(version))
+    }
+
+    /// Versions of a coordinate, oldest first
+    pub async fn find_by_coordinate(
+        db: &DatabaseConnection,
+        coordinate_id: i32,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(coordinate_versions::Entity::find()
+            .filter(coordinate_versions::Column::CoordinateId.eq(coordinate_id))
+            .order_by_asc(coordinate_versions::Column::Version)
+            .all(db)
+            .await?)
+    }
+
+    /// Latest version of a coordinate
+    pub async fn find_latest(db: &DatabaseConnection, coordinate_id: i32) -> ModelResult<Self> {
+        let version = coordinate_versions::Entity::find()
+            .filter(coordinate_versions::Column::CoordinateId.eq(coordinate_id))
+            .order_by_desc(coordinate_versions::Column::Version)
+            .one(db)
+            .await?;
+        version.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Find a version of a coordinate by its number
+    pub async fn find_version(
+        db: &DatabaseConnection,
+        coordinate_id: i32,
+        version: i32,
+    ) -> ModelResult<Self> {
+        let version = coordinate_versions::Entity::find()
+            .filter(coordinate_versions::Column::CoordinateId.eq(coordinate_id))
+            .filter(coordinate_versions::Column::Version.eq(version))
+            .one(db)
+            .await?;
+        version.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// The stored snapshot
+    ///
+    /// # Errors
+    ///
+    /// When the stored JSON does not match [`CoordinateSnapshot`]
+    pub fn snapshot(&self) -> ModelResult<CoordinateSnapshot> {
+        serde_json::from_value(self.snapshot.clone())
+            .map_err(|e| ModelError::msg(&format!("Invalid coordinate snapshot: {e}")))
+    }
+
+    /// Differences from this version to `to`
+    ///
+    /// # Errors
+    ///
+    /// When a stored snapshot is invalid
+    pub fn diff(&self, to: &Self) -> ModelResult<VersionDiff> {
+        Ok(VersionDiff {
+            from: self.version,
+            to: to.version,
+            changes: self.snapshot()?.changes(&to.snapshot()?),
+        })
+    }
+}
@@ -1,17 +1,35 @@
+use std::collections::HashMap;
+
 use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, QuerySelect, Select, sea_query::OnConflict};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub use super::_entities::coordinates::{self, ActiveModel, Entity, Model};
 pub use super::_entities::clothes_coordinates;
+use super::{
+    _entities::clothes,
+    clothes_coordinates::{ClothesLayout, ClothesPlacement},
+    coordinate_tombstones::{self, RemovedClothes},
+    coordinate_rules::{Completeness, FieldError, OutfitGarment, SlotRules},
+    coordinate_versions::{self, CoordinateSnapshot, VersionChange},
+    outfit_feedback::RatingSummary,
+    outfit_images,
+};
+use crate::data::{
+    seasons::{self, Season},
+    slots::Slot,
+    vocabularies::{Occasion, Style},
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CreateCoordinateParams {
     pub name: String,
     pub description: Option<String>,
-    pub occasion: Option<String>,
-    pub season: Option<String>,
-    pub style: Option<String>,
+    pub occasion: Option<Occasion>,
+    #[serde(default)]
+    pub seasons: Vec<Season>,
+    pub style: Option<Style>,
     pub user_id: i32,
     pub is_favorite: Option<bool>,
     pub image_url: Option<String>,
@@ -22,30 +40,182 @@ pub struct CreateCoordinateParams {
 pub struct UpdateCoordinateParams {
     pub name: Option<String>,
     pub description: Option<String>,
-    pub occasion: Option<String>,
-    pub season: Option<String>,
-    pub style: Option<String>,
+    pub occasion: Option<Occasion>,
+    /// Replaces every season of the coordinate
+    pub seasons: Option<Vec<Season>>,
+    pub style: Option<Style>,
     pub is_favorite: Option<bool>,
     pub image_url: Option<String>,
 }
 
+impl UpdateCoordinateParams {
+    /// Field-level problems with the update
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        match &self.name {
+            Some(name) if name.trim().is_empty() => {
+                vec![FieldError::new("name", "must not be empty")]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Options for cloning a coordinate
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CloneCoordinateParams {
+    /// Name of the copy, `"<name> (copy)"` when not given
+    pub name: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct AddClothesToCoordinateParams {
     pub clothes_ids: Vec<i32>,
 }
 
+/// Slot, canvas placement and notes of one garment in a coordinate. The
+/// layout is replaced as a whole; notes are kept when not given.
 #[derive(Debug, Deserialize, Serialize)]
-pub struct ClothesPositionParams {
+pub struct ClothesLayoutParams {
     pub clothes_id: i32,
-    pub position: Option<String>,
+    #[serde(flatten)]
+    pub layout: ClothesLayout,
     pub notes: Option<String>,
 }
 
+/// Layout of several garments of a coordinate, applied together
 #[derive(Debug, Deserialize, Serialize)]
+pub struct ReorderClothesParams {
+    pub items: Vec<ClothesLayoutParams>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct CoordinateWithClothes {
     #[serde(flatten)]
     pub coordinate: Model,
+    #[serde(with = "crate::models::clothes::priced_list")]
     pub clothes: Vec<super::clothes::Model>,
+    /// Slot, canvas placement and notes of each garment, in the order of
+    /// `clothes`
+    pub placements: Vec<ClothesPlacement>,
+    /// How many of the owner's required slots are filled
+    pub completeness: Completeness,
+    /// Averages of the feedback given after wearing it
+    pub ratings: RatingSummary,
+    /// Placeholders for garments deleted while they were part of it
+    pub removed_clothes: Vec<RemovedClothes>,
+}
+
+impl CoordinateWithClothes {
+    /// Coordinate with its garments, in link order, scored against the
+    /// owner's rules
+    #[must_use]
+    pub fn new(
+        coordinate: Model,
+        garments: Vec<(clothes_coordinates::Model, clothes::Model)>,
+        rules: &SlotRules,
+    ) -> Self {
+        let completeness = rules.completeness(
+            garments
+                .iter()
+                .map(|(link, item)| garment_slot(link.slot.as_deref(), item)),
+        );
+        Self {
+            coordinate,
+            placements: garments
+                .iter()
+                .map(|(link, _)| ClothesPlacement::from(link))
+                .collect(),
+            clothes: garments.into_iter().map(|(_, item)| item).collect(),
+            completeness,
+            ratings: RatingSummary::default(),
+            removed_clothes: Vec::new(),
+        }
+    }
+    /// Leave out the garments that cannot be worn right now
+    pub fn retain_available(&mut self) {
+        self.clothes.retain(clothes::Model::is_available);
+        let kept: Vec<i32> = self.clothes.iter().map(|item| item.id).collect();
+        self.placements
+            .retain(|placement| kept.contains(&placement.clothes_id));
+    }
+}
+
+impl Entity {
+    /// Coordinates that are not in the trash
+    #[must_use]
+    pub fn find_live() -> Select<Self> {
+        Self::find().filter(coordinates::Column::DeletedAt.is_null())
+    }
+
+    /// Coordinates in the trash
+    #[must_use]
+    pub fn find_trashed() -> Select<Self> {
+        Self::find().filter(coordinates::Column::DeletedAt.is_not_null())
+    }
+}
+
+/// Which of a user's coordinates a list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateFilter {
+    All,
+    Season(Season),
+    Favorites,
+}
+
+impl CoordinateFilter {
+    #[must_use]
+    pub fn matches(&self, coordinate: &Model) -> bool {
+        match self {
+            Self::All => true,
+            Self::Season(season) => coordinate.seasons().contains(season),
+            Self::Favorites => coordinate.is_favorite,
+        }
+    }
+}
+
+/// Cursor pagination of coordinate lists
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct PageQuery {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<u64>,
+}
+
+impl PageQuery {
+    pub const DEFAULT_LIMIT: u64 = 50;
+    pub const MAX_LIMIT: u64 = 200;
+
+    #[must_use]
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT)
+    }
+
+    /// ID of the last coordinate of the previous page
+    ///
+    /// # Errors
+    ///
+    /// When the cursor was not handed out by a previous page
+    pub fn after(&self) -> ModelResult<Option<i32>> {
+        self.cursor
+            .as_deref()
+            .map(|cursor| {
+                cursor
+                    .parse()
+                    .map_err(|_| ModelError::msg(&format!("invalid cursor: {cursor}")))
+            })
+            .transpose()
+    }
+}
+
+/// A page of coordinates
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CoordinatePage {
+    pub coordinates: Vec<CoordinateWithClothes>,
+    /// Cursor of the next page, `None` on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Validate, Deserialize)]
@@ -80,6 +250,48 @@ impl ActiveModelBehavior for super::_entities::coordinates::ActiveModel {
 }
 
 impl Model {
+    /// Seasons the coordinate belongs to, in season order
+    #[must_use]
+    pub fn seasons(&self) -> Vec<Season> {
+        seasons::deserialize_list(&self.seasons).unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn occasion(&self) -> Option<Occasion> {
+        self.occasion.as_deref().and_then(Occasion::parse)
+    }
+
+    #[must_use]
+    pub fn style(&self) -> Option<Style> {
+        self.style.as_deref().and_then(Style::parse)
+    }
+
+    /// Field-level problems with a new coordinate: an empty name, clothes
+    /// that do not exist or are listed twice, and garments breaking the
+    /// owner's slot rules
+    pub async fn create_problems(
+        db: &DatabaseConnection,
+        params: &CreateCoordinateParams,
+    ) -> ModelResult<Vec<FieldError>> {
+        let mut problems = Vec::new();
+        if params.name.trim().is_empty() {
+            problems.push(FieldError::new("name", "must not be empty"));
+        }
+        let clothes = find_clothes(db, &params.clothes_ids).await?;
+        problems.extend(clothes_id_problems(&params.clothes_ids, &clothes));
+
+        let garments: Vec<OutfitGarment> = clothes
+            .values()
+            .map(|item| OutfitGarment {
+                slot: Slot::from_category(&item.category),
+                clothes: item,
+            })
+            .collect();
+        let rules = SlotRules::for_user(db, params.user_id).await?;
+        problems.extend(rules.violations(&garments, "clothes_ids"));
+        Ok(problems)
+    }
+
     /// Create a new coordinate with clothes
     pub async fn create_with_clothes(
         db: &DatabaseConnection,
@@ -91,9 +303,9 @@ impl Model {
         let coordinate = coordinates::ActiveModel {
             name: ActiveValue::set(params.name.clone()),
             description: ActiveValue::set(params.description.clone()),
-            occasion: ActiveValue::set(params.occasion.clone()),
-            season: ActiveValue::set(params.season.clone()),
-            style: ActiveValue::set(params.style.clone()),
+            occasion: ActiveValue::set(params.occasion.map(|o| o.as_str().to_string())),
+            seasons: ActiveValue::set(season_list(&params.seasons)),
+            style: ActiveValue::set(params.style.map(|style| style.as_str().to_string())),
             user_id: ActiveValue::set(params.user_id),
             is_favorite: ActiveValue::set(params.is_favorite.unwrap_or(false)),
             image_url: ActiveValue::set(params.image_url.clone()),
@@ -103,11 +315,12 @@ impl Model {
         .await?;
 
         // Add clothes to coordinate
+        let slots = default_slots(&txn, &params.clothes_ids).await?;
         for clothes_id in &params.clothes_ids {
             clothes_coordinates::ActiveModel {
                 clothes_id: ActiveValue::set(*clothes_id),
                 coordinate_id: ActiveValue::set(coordinate.id),
-                position: ActiveValue::set(None),
+                slot: ActiveValue::set(slots.get(clothes_id).cloned()),
                 notes: ActiveValue::set(None),
                 ..Default::default()
             }
@@ -115,6 +328,7 @@ impl Model {
             .await?;
         }
 
+        coordinate_versions::Model::record(&txn, &coordinate, VersionChange::Created).await?;
         txn.commit().await?;
 
         // Load the coordinate with clothes
@@ -124,7 +338,7 @@ impl Model {
     /// Find coordinate by PID
     pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
         let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
-        let coordinate = coordinates::Entity::find()
+        let coordinate = coordinates::Entity::find_live()
             .filter(
                 model::query::condition()
                     .eq(coordinates::Column::Pid, parse_uuid)
@@ -135,23 +349,78 @@ impl Model {
         coordinate.ok_or_else(|| ModelError::EntityNotFound)
     }
 
+    /// Find coordinate in the trash by PID
+    pub async fn find_trashed_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let coordinate = coordinates::Entity::find_trashed()
+            .filter(coordinates::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        coordinate.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
     /// Find coordinate by PID with clothes
     pub async fn find_by_pid_with_clothes(
         db: &DatabaseConnection,
         pid: &str,
     ) -> ModelResult<CoordinateWithClothes> {
         let coordinate = Self::find_by_pid(db, pid).await?;
-        let clothes = coordinate
-            .find_related(super::_entities::clothes::Entity)
+        Self::with_clothes(db, vec![coordinate])
+            .await?
+            .pop()
+            .ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Load the clothes of several coordinates at once
+    pub async fn with_clothes<C: ConnectionTrait>(
+        db: &C,
+        coordinates: Vec<Self>,
+    ) -> ModelResult<Vec<CoordinateWithClothes>> {
+        let links = clothes_coordinates::Entity::find()
+            .filter(
+                clothes_coordinates::Column::CoordinateId
+                    .is_in(coordinates.iter().map(|coordinate| coordinate.id)),
+            )
+            .order_by_asc(clothes_coordinates::Column::Id)
             .all(db)
             .await?;
-
-        Ok(CoordinateWithClothes { coordinate, clothes })
+        let clothes: HashMap<i32, clothes::Model> = clothes::Entity::find_live()
+            .filter(clothes::Column::Id.is_in(links.iter().map(|link| link.clothes_id)))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+        let user_ids: Vec<i32> = coordinates.iter().map(|c| c.user_id).collect();
+        let rules = SlotRules::for_users(db, &user_ids).await?;
+        let default_rules = SlotRules::default();
+        let coordinate_ids: Vec<i32> = coordinates.iter().map(|c| c.id).collect();
+        let mut ratings = RatingSummary::for_coordinates(db, &coordinate_ids).await?;
+        let mut removed = coordinate_tombstones::Model::for_coordinates(db, &coordinate_ids).await?;
+
+        Ok(coordinates
+            .into_iter()
+            .map(|coordinate| {
+                let garments = links
+                    .iter()
+                    .filter(|link| link.coordinate_id == coordinate.id)
+                    .filter_map(|link| Some((link.clone(), clothes.get(&link.clothes_id)?.clone())))
+                    .collect();
+                let rules = rules.get(&coordinate.user_id).unwrap_or(&default_rules);
+                let ratings = ratings.remove(&coordinate.id).unwrap_or_default();
+                let removed_clothes = removed.remove(&coordinate.id).unwrap_or_default();
+                CoordinateWithClothes {
+                    ratings,
+                    removed_clothes,
+                    ..CoordinateWithClothes::new(coordinate, garments, rules)
+                }
+            })
+            .collect())
     }
 
     /// Find all coordinates for a user
     pub async fn find_by_user(db: &DatabaseConnection, user_id: i32) -> ModelResult<Vec<Self>> {
-        let coordinates = coordinates::Entity::find()
+        let coordinates = coordinates::Entity::find_live()
             .filter(
                 model::query::condition()
                     .eq(coordinates::Column::UserId, user_id)
@@ -168,17 +437,51 @@ impl Model {
         user_id: i32,
     ) -> ModelResult<Vec<CoordinateWithClothes>> {
         let coordinates = Self::find_by_user(db, user_id).await?;
-        let mut result = Vec::new();
-
-        for coordinate in coordinates {
-            let clothes = coordinate
-                .find_related(super::_entities::clothes::Entity)
-                .all(db)
-                .await?;
-            result.push(CoordinateWithClothes { coordinate, clothes });
+        Self::with_clothes(db, coordinates).await
+    }
+
+    /// A page of a user's coordinates in creation order, with their clothes
+    pub async fn find_page_by_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        filter: CoordinateFilter,
+        page: &PageQuery,
+    ) -> ModelResult<CoordinatePage> {
+        let limit = page.limit();
+        let mut after = page.after()?;
+        let mut found: Vec<Self> = Vec::new();
+        // Seasons are filtered here, so a batch may come back short
+        loop {
+            let mut query = coordinates::Entity::find_live()
+                .filter(coordinates::Column::UserId.eq(user_id))
+                .order_by_asc(coordinates::Column::Id)
+                .limit(limit + 1);
+            if let Some(after) = after {
+                query = query.filter(coordinates::Column::Id.gt(after));
+            }
+            if filter == CoordinateFilter::Favorites {
+                query = query.filter(coordinates::Column::IsFavorite.eq(true));
+            }
+            let batch = query.all(db).await?;
+            let exhausted = u64::try_from(batch.len()).unwrap_or(u64::MAX) <= limit;
+            after = batch.last().map(|coordinate| coordinate.id);
+            found.extend(batch.into_iter().filter(|coordinate| filter.matches(coordinate)));
+            if exhausted || u64::try_from(found.len()).unwrap_or(u64::MAX) > limit {
+                break;
+            }
         }
 
-        Ok(result)
+        let mut next_cursor = None;
+        if let Ok(limit) = usize::try_from(limit)
+            && found.len() > limit
+        {
+            found.truncate(limit);
+            next_cursor = found.last().map(|coordinate| coordinate.id.to_string());
+        }
+        Ok(CoordinatePage {
+            coordinates: Self::with_clothes(db, found).await?,
+            next_cursor,
+        })
     }
 
     /// Update coordinate
@@ -196,14 +499,14 @@ impl Model {
         if let Some(description) = &params.description {
             active_model.description = ActiveValue::set(Some(description.clone()));
         }
-        if let Some(occasion) = &params.occasion {
-            active_model.occasion = ActiveValue::set(Some(occasion.clone()));
+        if let Some(occasion) = params.occasion {
+            active_model.occasion = ActiveValue::set(Some(occasion.as_str().to_string()));
         }
-        if let Some(season) = &params.season {
-            active_model.season = ActiveValue::set(Some(season.clone()));
+        if let Some(seasons) = &params.seasons {
+            active_model.seasons = ActiveValue::set(season_list(seasons));
         }
-        if let Some(style) = &params.style {
-            active_model.style = ActiveValue::set(Some(style.clone()));
+        if let Some(style) = params.style {
+            active_model.style = ActiveValue::set(Some(style.as_str().to_string()));
         }
         if let Some(is_favorite) = params.is_favorite {
             active_model.is_favorite = ActiveValue::set(is_favorite);
@@ -212,46 +515,99 @@ impl Model {
             active_model.image_url = ActiveValue::set(Some(image_url.clone()));
         }
 
-        Ok(active_model.update(db).await?)
+        let coordinate = active_model.update(db).await?;
+        coordinate_versions::Model::record(db, &coordinate, VersionChange::Updated).await?;
+        Ok(coordinate)
     }
 
-    /// Add clothes to coordinate
+    /// Field-level problems with adding clothes: clothes that do not exist or
+    /// are listed twice, and an outfit breaking the owner's slot rules
+    pub async fn add_clothes_problems(
+        db: &DatabaseConnection,
+        coordinate_pid: &str,
+        params: &AddClothesToCoordinateParams,
+    ) -> ModelResult<Vec<FieldError>> {
+        let coordinate = Self::find_by_pid(db, coordinate_pid).await?;
+        let clothes = find_clothes(db, &params.clothes_ids).await?;
+        let mut problems = clothes_id_problems(&params.clothes_ids, &clothes);
+
+        let current = coordinate_garments(db, coordinate.id).await?;
+        let mut garments: Vec<OutfitGarment> = current
+            .iter()
+            .map(|(link, item)| OutfitGarment {
+                slot: garment_slot(link.slot.as_deref(), item),
+                clothes: item,
+            })
+            .collect();
+        garments.extend(
+            clothes
+                .values()
+                .filter(|item| !current.iter().any(|(link, _)| link.clothes_id == item.id))
+                .map(|item| OutfitGarment {
+                    slot: Slot::from_category(&item.category),
+                    clothes: item,
+                }),
+        );
+        let rules = SlotRules::for_user(db, coordinate.user_id).await?;
+        problems.extend(rules.violations(&garments, "clothes_ids"));
+        Ok(problems)
+    }
+
+    /// Add clothes to coordinate, keeping the links that already exist
+    ///
+    /// The clothes are locked until the links are in, so none of them can be
+    /// deleted in between. Returns the problems with clothes that do not
+    /// exist, in which case nothing is added.
     pub async fn add_clothes(
         db: &DatabaseConnection,
         coordinate_pid: &str,
         params: &AddClothesToCoordinateParams,
-    ) -> ModelResult<()> {
+    ) -> ModelResult<Vec<FieldError>> {
         let coordinate = Self::find_by_pid(db, coordinate_pid).await?;
+        let txn = db.begin().await?;
 
-        for clothes_id in &params.clothes_ids {
-            // Check if the relation already exists
-            let existing = clothes_coordinates::Entity::find()
-                .filter(
-                    model::query::condition()
-                        .eq(clothes_coordinates::Column::ClothesId, *clothes_id)
-                        .eq(clothes_coordinates::Column::CoordinateId, coordinate.id)
-                        .build(),
-                )
-                .one(db)
-                .await?;
-
-            if existing.is_none() {
-                clothes_coordinates::ActiveModel {
-                    clothes_id: ActiveValue::set(*clothes_id),
-                    coordinate_id: ActiveValue::set(coordinate.id),
-                    position: ActiveValue::set(None),
-                    notes: ActiveValue::set(None),
-                    ..Default::default()
-                }
-                .insert(db)
-                .await?;
-            }
+        let clothes: HashMap<i32, clothes::Model> = clothes::Entity::find_live()
+            .filter(clothes::Column::Id.is_in(params.clothes_ids.iter().copied()))
+            .lock_shared()
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|item| (item.id, item))
+            .collect();
+        let problems = clothes_id_problems(&params.clothes_ids, &clothes);
+        if !problems.is_empty() {
+            return Ok(problems);
         }
 
-        Ok(())
+        let links = clothes.values().map(|item| clothes_coordinates::ActiveModel {
+            clothes_id: ActiveValue::set(item.id),
+            coordinate_id: ActiveValue::set(coordinate.id),
+            slot: ActiveValue::set(
+                Slot::from_category(&item.category).map(|slot| slot.as_str().to_string()),
+            ),
+            notes: ActiveValue::set(None),
+            ..Default::default()
+        });
+        clothes_coordinates::Entity::insert_many(links)
+            .on_conflict(
+                OnConflict::columns([
+                    clothes_coordinates::Column::ClothesId,
+                    clothes_coordinates::Column::CoordinateId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec_without_returning(&txn)
+            .await?;
+
+        coordinate_versions::Model::record(&txn, &coordinate, VersionChange::ClothesAdded).await?;
+        txn.commit().await?;
+        Ok(Vec::new())
     }
 
-    /// Remove clothes from coordinate
+    /// Remove clothes from coordinate, `EntityNotFound` when the clothes are
+    /// not in it
     pub async fn remove_clothes(
         db: &DatabaseConnection,
         coordinate_pid: &str,
@@ -259,7 +615,7 @@ impl Model {
     ) -> ModelResult<()> {
         let coordinate = Self::find_by_pid(db, coordinate_pid).await?;
 
-        clothes_coordinates::Entity::delete_many()
+        let removed = clothes_coordinates::Entity::delete_many()
             .filter(
                 model::query::condition()
                     .eq(clothes_coordinates::Column::ClothesId, clothes_id)
@@ -268,76 +624,257 @@ impl Model {
             )
             .exec(db)
             .await?;
+        if removed.rows_affected == 0 {
+            return Err(ModelError::EntityNotFound);
+        }
 
+        coordinate_versions::Model::record(db, &coordinate, VersionChange::ClothesRemoved).await?;
         Ok(())
     }
 
-    /// Update clothes position in coordinate
-    pub async fn update_clothes_position(
+    /// Field-level problems with a layout update: garments that are not in
+    /// the coordinate or listed twice, invalid canvas values, slots that do
+    /// not match the garment's category and slots breaking the owner's rules
+    pub async fn layout_problems(
         db: &DatabaseConnection,
         coordinate_pid: &str,
-        params: &ClothesPositionParams,
-    ) -> ModelResult<()> {
+        items: &[ClothesLayoutParams],
+    ) -> ModelResult<Vec<FieldError>> {
         let coordinate = Self::find_by_pid(db, coordinate_pid).await?;
+        let current = coordinate_garments(db, coordinate.id).await?;
+
+        let mut problems = Vec::new();
+        let mut seen = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            let field = format!("items[{index}]");
+            let Some((_, garment)) = current
+                .iter()
+                .find(|(link, _)| link.clothes_id == item.clothes_id)
+            else {
+                problems.push(FieldError::new(
+                    format!("{field}.clothes_id"),
+                    format!("clothes {} is not in this coordinate", item.clothes_id),
+                ));
+                continue;
+            };
+            if seen.contains(&item.clothes_id) {
+                problems.push(FieldError::new(
+                    format!("{field}.clothes_id"),
+                    format!("clothes {} is listed more than once", item.clothes_id),
+                ));
+            }
+            seen.push(item.clothes_id);
+            problems.extend(
+                item.layout
+                    .problems()
+                    .into_iter()
+                    .map(|problem| FieldError::new(field.clone(), problem)),
+            );
+            problems.extend(
+                item.layout
+                    .category_problems(&garment.category)
+                    .into_iter()
+                    .map(|problem| FieldError::new(format!("{field}.slot"), problem)),
+            );
+        }
 
-        let relation = clothes_coordinates::Entity::find()
-            .filter(
-                model::query::condition()
-                    .eq(clothes_coordinates::Column::ClothesId, params.clothes_id)
-                    .eq(clothes_coordinates::Column::CoordinateId, coordinate.id)
-                    .build(),
-            )
-            .one(db)
-            .await?
-            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let garments: Vec<OutfitGarment> = current
+            .iter()
+            .map(|(link, item)| {
+                let slot = match items.iter().find(|i| i.clothes_id == link.clothes_id) {
+                    Some(update) => update.layout.slot.map(|slot| slot.as_str()),
+                    None => link.slot.as_deref(),
+                };
+                OutfitGarment {
+                    slot: garment_slot(slot, item),
+                    clothes: item,
+                }
+            })
+            .collect();
+        let rules = SlotRules::for_user(db, coordinate.user_id).await?;
+        problems.extend(rules.violations(&garments, "items"));
+        Ok(problems)
+    }
 
-        let mut active_model = relation.into_active_model();
-        active_model.position = ActiveValue::set(params.position.clone());
-        active_model.notes = ActiveValue::set(params.notes.clone());
-        active_model.update(db).await?;
+    /// Update the slot, canvas placement and notes of garments in the
+    /// coordinate, all in one transaction
+    pub async fn update_layout(
+        db: &DatabaseConnection,
+        coordinate_pid: &str,
+        items: &[ClothesLayoutParams],
+    ) -> ModelResult<Vec<clothes_coordinates::Model>> {
+        let coordinate = Self::find_by_pid(db, coordinate_pid).await?;
 
-        Ok(())
+        let txn = db.begin().await?;
+        let mut updated = Vec::with_capacity(items.len());
+        for item in items {
+            let relation = clothes_coordinates::Entity::find()
+                .filter(
+                    model::query::condition()
+                        .eq(clothes_coordinates::Column::ClothesId, item.clothes_id)
+                        .eq(clothes_coordinates::Column::CoordinateId, coordinate.id)
+                        .build(),
+                )
+                .one(&txn)
+                .await?
+                .ok_or_else(|| ModelError::EntityNotFound)?;
+
+            let mut active_model = relation.into_active_model();
+            active_model.set_layout(&item.layout);
+            if let Some(notes) = &item.notes {
+                active_model.notes = ActiveValue::set(Some(notes.clone()));
+            }
+            updated.push(active_model.update(&txn).await?);
+        }
+        coordinate_versions::Model::record(&txn, &coordinate, VersionChange::LayoutUpdated)
+            .await?;
+        txn.commit().await?;
+
+        Ok(updated)
     }
 
-    /// Delete coordinate by PID
-    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+    /// Copy a coordinate along with its garments, their layout and notes. A
+    /// rendered outfit image is not copied; the copy gets its own.
+    pub async fn clone_by_pid(
+        db: &DatabaseConnection,
+        pid: &str,
+        params: &CloneCoordinateParams,
+    ) -> ModelResult<CoordinateWithClothes> {
+        let original = Self::find_by_pid(db, pid).await?;
+        let links = clothes_coordinates::Entity::find()
+            .filter(clothes_coordinates::Column::CoordinateId.eq(original.id))
+            .order_by_asc(clothes_coordinates::Column::Id)
+            .all(db)
+            .await?;
+
+        let txn = db.begin().await?;
+        let image_url = if outfit_images::is_rendered(&original) {
+            None
+        } else {
+            original.image_url.clone()
+        };
+        let copy = coordinates::ActiveModel {
+            name: ActiveValue::set(
+                params
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("{} (copy)", original.name)),
+            ),
+            description: ActiveValue::set(original.description.clone()),
+            occasion: ActiveValue::set(original.occasion.clone()),
+            seasons: ActiveValue::set(original.seasons.clone()),
+            style: ActiveValue::set(original.style.clone()),
+            user_id: ActiveValue::set(original.user_id),
+            is_favorite: ActiveValue::set(false),
+            image_url: ActiveValue::set(image_url),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        for link in &links {
+            let mut active_model = clothes_coordinates::ActiveModel {
+                clothes_id: ActiveValue::set(link.clothes_id),
+                coordinate_id: ActiveValue::set(copy.id),
+                notes: ActiveValue::set(link.notes.clone()),
+                ..Default::default()
+            };
+            active_model.set_layout(&link.layout());
+            active_model.insert(&txn).await?;
+        }
+        coordinate_versions::Model::record(&txn, &copy, VersionChange::Cloned).await?;
+        txn.commit().await?;
+
+        Self::find_by_pid_with_clothes(db, &copy.pid.to_string()).await
+    }
+
+    /// Restore the metadata and garments of an earlier version, recorded as a
+    /// new version. Garments that were deleted since are left out.
+    pub async fn restore_version(
+        db: &DatabaseConnection,
+        pid: &str,
+        version: i32,
+    ) -> ModelResult<CoordinateWithClothes> {
         let coordinate = Self::find_by_pid(db, pid).await?;
+        let snapshot: CoordinateSnapshot =
+            coordinate_versions::Model::find_version(db, coordinate.id, version)
+                .await?
+                .snapshot()?;
+        let clothes_ids: Vec<i32> = snapshot.clothes.iter().map(|item| item.clothes_id).collect();
+        let existing = find_clothes(db, &clothes_ids).await?;
 
         let txn = db.begin().await?;
+        let mut active_model = coordinate.into_active_model();
+        active_model.name = ActiveValue::set(snapshot.name.clone());
+        active_model.description = ActiveValue::set(snapshot.description.clone());
+        active_model.occasion = ActiveValue::set(snapshot.occasion.clone());
+        active_model.seasons = ActiveValue::set(season_list(&snapshot.seasons));
+        active_model.style = ActiveValue::set(snapshot.style.clone());
+        active_model.is_favorite = ActiveValue::set(snapshot.is_favorite);
+        let coordinate = active_model.update(&txn).await?;
 
-        // Delete all clothes_coordinates relations
         clothes_coordinates::Entity::delete_many()
-            .filter(
-                model::query::condition()
-                    .eq(clothes_coordinates::Column::CoordinateId, coordinate.id)
-                    .build(),
-            )
+            .filter(clothes_coordinates::Column::CoordinateId.eq(coordinate.id))
             .exec(&txn)
             .await?;
+        for item in snapshot
+            .clothes
+            .iter()
+            .filter(|item| existing.contains_key(&item.clothes_id))
+        {
+            let mut link = clothes_coordinates::ActiveModel {
+                clothes_id: ActiveValue::set(item.clothes_id),
+                coordinate_id: ActiveValue::set(coordinate.id),
+                notes: ActiveValue::set(item.notes.clone()),
+                ..Default::default()
+            };
+            link.set_layout(&item.layout);
+            link.insert(&txn).await?;
+        }
+        coordinate_versions::Model::record(&txn, &coordinate, VersionChange::Restored).await?;
+        txn.commit().await?;
+
+        Self::find_by_pid_with_clothes(db, pid).await
+    }
 
-        // Delete the coordinate
-        coordinate.delete(&txn).await?;
+    /// Move coordinate to the trash by PID. Its clothes stay linked, so a
+    /// restore brings it back whole.
+    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+        let coordinate = Self::find_by_pid(db, pid).await?;
+        let mut trashed = coordinate.into_active_model();
+        trashed.deleted_at = ActiveValue::set(Some(chrono::Utc::now().into()));
+        trashed.update(db).await?;
+        Ok(())
+    }
 
-        txn.commit().await?;
+    /// Take coordinate out of the trash by PID
+    pub async fn restore_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let coordinate = Self::find_trashed_by_pid(db, pid).await?;
+        let mut restored = coordinate.into_active_model();
+        restored.deleted_at = ActiveValue::set(None);
+        Ok(restored.update(db).await?)
+    }
+
+    /// Delete coordinate for good, with its clothes links
+    pub async fn purge<C: ConnectionTrait>(self, db: &C) -> ModelResult<()> {
+        clothes_coordinates::Entity::delete_many()
+            .filter(clothes_coordinates::Column::CoordinateId.eq(self.id))
+            .exec(db)
+            .await?;
+        self.delete(db).await?;
         Ok(())
     }
 
-    /// Find coordinates by season
+    /// Find coordinates belonging to a season
     pub async fn find_by_season(
         db: &DatabaseConnection,
         user_id: i32,
-        season: &str,
+        season: Season,
     ) -> ModelResult<Vec<Self>> {
-        let coordinates = coordinates::Entity::find()
-            .filter(
-                model::query::condition()
-                    .eq(coordinates::Column::UserId, user_id)
-                    .eq(coordinates::Column::Season, season)
-                    .build(),
-            )
-            .all(db)
-            .await?;
-        Ok(coordinates)
+        let coordinates = Self::find_by_user(db, user_id).await?;
+        Ok(coordinates
+            .into_iter()
+            .filter(|coordinate| coordinate.seasons().contains(&season))
+            .collect())
     }
 
     /// Find favorite coordinates
@@ -345,7 +882,7 @@ impl Model {
         db: &DatabaseConnection,
         user_id: i32,
     ) -> ModelResult<Vec<Self>> {
-        let coordinates = coordinates::Entity::find()
+        let coordinates = coordinates::Entity::find_live()
             .filter(
                 model::query::condition()
                     .eq(coordinates::Column::UserId, user_id)
@@ -356,4 +893,87 @@ impl Model {
             .await?;
         Ok(coordinates)
     }
-}
\ No newline at end of file
+}
+
+/// Stored form of a coordinate's seasons: their keys in season order
+#[must_use]
+pub fn season_list(seasons: &[Season]) -> serde_json::Value {
+    let mut seasons = seasons.to_vec();
+    seasons.sort();
+    seasons.dedup();
+    seasons.iter().map(Season::as_str).collect::<Vec<_>>().into()
+}
+
+/// Slot of each garment derived from its category
+async fn default_slots<C: ConnectionTrait>(
+    db: &C,
+    clothes_ids: &[i32],
+) -> ModelResult<HashMap<i32, String>> {
+    Ok(clothes::Entity::find()
+        .filter(clothes::Column::Id.is_in(clothes_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|item| {
+            let slot = Slot::from_category(&item.category)?;
+            Some((item.id, slot.as_str().to_string()))
+        })
+        .collect())
+}
+
+/// Slot a garment fills: the slot of its link, or the one derived from its
+/// category
+fn garment_slot(link_slot: Option<&str>, item: &clothes::Model) -> Option<Slot> {
+    link_slot
+        .and_then(Slot::parse)
+        .or_else(|| Slot::from_category(&item.category))
+}
+
+async fn find_clothes(
+    db: &DatabaseConnection,
+    clothes_ids: &[i32],
+) -> ModelResult<HashMap<i32, clothes::Model>> {
+    Ok(clothes::Entity::find_live()
+        .filter(clothes::Column::Id.is_in(clothes_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect())
+}
+
+/// Clothes IDs that do not exist or are listed more than once
+fn clothes_id_problems(
+    clothes_ids: &[i32],
+    clothes: &HashMap<i32, clothes::Model>,
+) -> Vec<FieldError> {
+    let mut problems = Vec::new();
+    for (index, id) in clothes_ids.iter().enumerate() {
+        let field = format!("clothes_ids[{index}]");
+        if !clothes.contains_key(id) {
+            problems.push(FieldError::new(field, format!("clothes {id} does not exist")));
+        } else if clothes_ids[..index].contains(id) {
+            problems.push(FieldError::new(
+                field,
+                format!("clothes {id} is listed more than once"),
+            ));
+        }
+    }
+    problems
+}
+
+/// Links of a coordinate with their clothes, in link order
+async fn coordinate_garments(
+    db: &DatabaseConnection,
+    coordinate_id: i32,
+) -> ModelResult<Vec<(clothes_coordinates::Model, clothes::Model)>> {
+    Ok(clothes_coordinates::Entity::find()
+        .filter(clothes_coordinates::Column::CoordinateId.eq(coordinate_id))
+        .order_by_asc(clothes_coordinates::Column::Id)
+        .find_also_related(clothes::Entity)
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(link, item)| Some((link, item?)))
+        .collect())
+}
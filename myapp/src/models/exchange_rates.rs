@@ -0,0 +1,337 @@
+//! Exchange rates between currencies.
+//!
+//! Admins maintain the table with the `exchange_rates` task, one pair at a
+//! time or from a CSV or JSON file. Amounts are converted with the rate of
+//! their pair, its inverse, or through a currency both sides have a rate
+//! with, so a file quoted against EUR covers every pair of its currencies.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, prelude::Decimal, sea_query::OnConflict};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub use super::_entities::exchange_rates::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes, users},
+    clothes_imports::ImportFormat,
+    coordinate_rules::FieldError,
+};
+use crate::data::money::{Currency, Money};
+
+/// A rate to store: one unit of `base` is worth `rate` units of `quote`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RateParams {
+    pub base: Currency,
+    pub quote: Currency,
+    /// Decimal string such as `"1.0842"`
+    pub rate: String,
+}
+
+impl RateParams {
+    /// The rate, `None` unless it is a positive decimal
+    #[must_use]
+    pub fn rate(&self) -> Option<Decimal> {
+        Decimal::from_str(self.rate.trim())
+            .ok()
+            .filter(Decimal::is_sign_positive)
+            .filter(|rate| !rate.is_zero())
+    }
+
+    /// Field-level problems with this rate
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        let mut problems = Vec::new();
+        if self.base == self.quote {
+            problems.push(FieldError::new("quote", "must differ from base"));
+        }
+        if self.rate().is_none() {
+            problems.push(FieldError::new(
+                "rate",
+                format!("'{}' is not a positive decimal", self.rate),
+            ));
+        }
+        problems
+    }
+}
+
+/// Parse a rates file: CSV with `base,quote,rate` columns, or a JSON array
+/// of `{"base", "quote", "rate"}` objects. JSON rates may be numbers, they
+/// are taken as written.
+///
+/// # Errors
+///
+/// When the file is malformed or a row is invalid, naming the row
+pub fn parse(format: ImportFormat, data: &str) -> ModelResult<Vec<RateParams>> {
+    let row_error = |row: usize, message: &dyn std::fmt::Display| {
+        ModelError::msg(&format!("row {row}: {message}"))
+    };
+    let rates = match format {
+        ImportFormat::Csv => csv::Reader::from_reader(data.as_bytes())
+            .deserialize::<RateParams>()
+            .enumerate()
+            .map(|(index, row)| row.map_err(|e| row_error(index + 1, &e)))
+            .collect::<ModelResult<Vec<_>>>()?,
+        ImportFormat::Json => {
+            let rows: Vec<Map<String, Value>> = serde_json::from_str(data)
+                .map_err(|e| ModelError::msg(&format!("Invalid JSON: {e}")))?;
+            rows.into_iter()
+                .enumerate()
+                .map(|(index, mut row)| {
+                    if let Some(Value::Number(rate)) = row.get("rate") {
+                        let rate = rate.to_string();
+                        row.insert("rate".to_string(), Value::String(rate));
+                    }
+                    serde_json::from_value(Value::Object(row)).map_err(|e| row_error(index + 1, &e))
+                })
+                .collect::<ModelResult<Vec<_>>>()?
+        }
+    };
+    for (index, rate) in rates.iter().enumerate() {
+        if let Some(problem) = rate.problems().first() {
+            let message = format!("{} {}", problem.field, problem.message);
+            return Err(row_error(index + 1, &message));
+        }
+    }
+    Ok(rates)
+}
+
+/// One rate per pair, the last given for a pair that is repeated. A single
+/// upsert cannot touch the same row twice.
+#[must_use]
+pub fn latest_per_pair(rates: &[RateParams]) -> Vec<&RateParams> {
+    let mut latest = BTreeMap::new();
+    for params in rates {
+        latest.insert((params.base, params.quote), params);
+    }
+    latest.into_values().collect()
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Store rates, replacing the rates of the same pairs. `source` records
+    /// where they came from (`manual` or the imported file). Returns the
+    /// number of pairs stored.
+    ///
+    /// # Errors
+    ///
+    /// When a rate is invalid, in which case nothing is stored
+    pub async fn store(
+        db: &DatabaseConnection,
+        rates: &[RateParams],
+        source: &str,
+    ) -> ModelResult<usize> {
+        let now: DateTimeWithTimeZone = chrono::Utc::now().into();
+        let problem = rates
+            .iter()
+            .find_map(|params| params.problems().into_iter().next());
+        if let Some(problem) = problem {
+            return Err(ModelError::msg(&format!(
+                "{} {}",
+                problem.field, problem.message
+            )));
+        }
+        let rates = latest_per_pair(rates);
+        let mut models = Vec::with_capacity(rates.len());
+        for params in &rates {
+            models.push(ActiveModel {
+                base: ActiveValue::set(params.base.code().to_string()),
+                quote: ActiveValue::set(params.quote.code().to_string()),
+                rate: ActiveValue::set(params.rate().unwrap_or_default()),
+                source: ActiveValue::set(source.to_string()),
+                created_at: ActiveValue::set(now),
+                updated_at: ActiveValue::set(now),
+                ..Default::default()
+            });
+        }
+        if models.is_empty() {
+            return Ok(0);
+        }
+
+        let txn = db.begin().await?;
+        exchange_rates::Entity::insert_many(models)
+            .on_conflict(
+                OnConflict::columns([exchange_rates::Column::Base, exchange_rates::Column::Quote])
+                    .update_columns([
+                        exchange_rates::Column::Rate,
+                        exchange_rates::Column::Source,
+                        exchange_rates::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(rates.len())
+    }
+
+    /// All stored rates, by pair
+    pub async fn list(db: &DatabaseConnection) -> ModelResult<Vec<Self>> {
+        Ok(exchange_rates::Entity::find()
+            .order_by_asc(exchange_rates::Column::Base)
+            .order_by_asc(exchange_rates::Column::Quote)
+            .all(db)
+            .await?)
+    }
+}
+
+/// Rates to convert amounts with
+#[derive(Debug, Default, Clone)]
+pub struct Rates {
+    pairs: BTreeMap<(Currency, Currency), Decimal>,
+}
+
+impl Rates {
+    /// Rates from `(base, quote, rate)` triples, non-positive rates are
+    /// ignored
+    #[must_use]
+    pub fn new(rates: impl IntoIterator<Item = (Currency, Currency, Decimal)>) -> Self {
+        Self {
+            pairs: rates
+                .into_iter()
+                .filter(|(_, _, rate)| rate.is_sign_positive() && !rate.is_zero())
+                .map(|(base, quote, rate)| ((base, quote), rate))
+                .collect(),
+        }
+    }
+
+    /// Load the stored rates, skipping rows with unknown currencies
+    pub async fn load(db: &DatabaseConnection) -> ModelResult<Self> {
+        Ok(Self::new(Model::list(db).await?.into_iter().filter_map(
+            |rate| {
+                Some((
+                    Currency::parse(&rate.base)?,
+                    Currency::parse(&rate.quote)?,
+                    rate.rate,
+                ))
+            },
+        )))
+    }
+
+    /// Units of `to` one unit of `from` is worth
+    #[must_use]
+    pub fn rate(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        self.direct(from, to).or_else(|| {
+            let currencies: BTreeSet<Currency> = self
+                .pairs
+                .keys()
+                .flat_map(|(base, quote)| [*base, *quote])
+                .collect();
+            currencies
+                .into_iter()
+                .find_map(|via| self.direct(from, via)?.checked_mul(self.direct(via, to)?))
+        })
+    }
+
+    fn direct(&self, from: Currency, to: Currency) -> Option<Decimal> {
+        self.pairs.get(&(from, to)).copied().or_else(|| {
+            self.pairs
+                .get(&(to, from))
+                .and_then(|rate| Decimal::ONE.checked_div(*rate))
+        })
+    }
+
+    /// `money` in `currency`, `None` without a rate between the two or when
+    /// the result is too large
+    #[must_use]
+    pub fn convert(&self, money: Money, currency: Currency) -> Option<Money> {
+        self.rate(money.currency, currency)
+            .and_then(|rate| money.convert(currency, rate))
+    }
+}
+
+/// Value of the clothes in stock, in one currency
+#[derive(Debug, Clone, Serialize)]
+pub struct WardrobeTotals {
+    pub currency: Currency,
+    /// Sum of every price that could be converted
+    pub total: Money,
+    /// Sums in the currencies the prices were entered in
+    pub by_currency: Vec<Money>,
+    /// Currencies without a rate to `currency`, or whose sum is too large,
+    /// left out of `total`
+    pub unconverted: Vec<Currency>,
+}
+
+impl WardrobeTotals {
+    /// Total the price of every unit in stock
+    #[must_use]
+    pub fn new(clothes: &[clothes::Model], currency: Currency, rates: &Rates) -> Self {
+        let mut sums: BTreeMap<Currency, Option<Decimal>> = BTreeMap::new();
+        for item in clothes {
+            let price = item.money();
+            let sum = sums.entry(price.currency).or_insert(Some(Decimal::ZERO));
+            *sum = sum.and_then(|sum| {
+                price
+                    .amount
+                    .checked_mul(Decimal::from(item.stock_quantity.max(0)))
+                    .and_then(|value| sum.checked_add(value))
+            });
+        }
+
+        let mut by_currency = Vec::new();
+        let mut total = Decimal::ZERO;
+        let mut unconverted = Vec::new();
+        for (sum_currency, amount) in sums {
+            let Some(amount) = amount else {
+                unconverted.push(sum_currency);
+                continue;
+            };
+            let sum = Money::new(amount, sum_currency);
+            by_currency.push(sum);
+            match rates
+                .convert(sum, currency)
+                .and_then(|converted| total.checked_add(converted.amount))
+            {
+                Some(sum_total) => total = sum_total,
+                None => unconverted.push(sum_currency),
+            }
+        }
+        Self {
+            currency,
+            total: Money::new(total, currency),
+            by_currency,
+            unconverted,
+        }
+    }
+
+    /// Totals of the clothes in the user's coordinates in `currency`, or in
+    /// the user's home currency
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        currency: Option<Currency>,
+    ) -> ModelResult<Self> {
+        let currency = match currency {
+            Some(currency) => currency,
+            None => users::Entity::find_by_id(user_id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ModelError::EntityNotFound)?
+                .home_currency(),
+        };
+        let clothes = clothes::Entity::find_owned(user_id).all(db).await?;
+        let rates = Rates::load(db).await?;
+        Ok(Self::new(&clothes, currency, &rates))
+    }
+}
@@ -0,0 +1,209 @@
+//! Inventory ledger of the clothes.
+//!
+//! Stock only changes by appending a movement. `clothes.stock_quantity` is
+//! the balance after the latest movement and `clothes.in_stock` follows from
+//! it, so the two can no longer disagree.
+
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+
+pub use super::_entities::inventory_movements::{self, ActiveModel, Entity, Model};
+use super::{_entities::clothes, coordinate_rules::FieldError};
+
+/// Why the stock of a garment changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementReason {
+    Purchase,
+    Lost,
+    Donated,
+    Adjustment,
+}
+
+impl MovementReason {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Purchase => "purchase",
+            Self::Lost => "lost",
+            Self::Donated => "donated",
+            Self::Adjustment => "adjustment",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        [Self::Purchase, Self::Lost, Self::Donated, Self::Adjustment]
+            .into_iter()
+            .find(|reason| reason.as_str() == value)
+    }
+
+    /// Signed stock change for `quantity` units. Purchases add to the stock,
+    /// losses and donations take from it and adjustments keep their sign.
+    #[must_use]
+    pub fn change(&self, quantity: i32) -> i32 {
+        match self {
+            Self::Purchase | Self::Adjustment => quantity,
+            Self::Lost | Self::Donated => -quantity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MovementParams {
+    pub reason: MovementReason,
+    /// Number of units, signed for adjustments only
+    pub quantity: i32,
+    pub note: Option<String>,
+}
+
+impl MovementParams {
+    /// Field-level problems with applying this movement to `stock` units
+    #[must_use]
+    pub fn problems(&self, stock: i32) -> Vec<FieldError> {
+        let mut problems = Vec::new();
+        if self.quantity == 0 {
+            problems.push(FieldError::new("quantity", "must not be zero"));
+        } else if self.quantity < 0 && self.reason != MovementReason::Adjustment {
+            problems.push(FieldError::new(
+                "quantity",
+                "must be positive, record an adjustment to correct the stock",
+            ));
+        } else if stock + self.reason.change(self.quantity) < 0 {
+            problems.push(FieldError::new(
+                "quantity",
+                format!("exceeds the {stock} in stock"),
+            ));
+        }
+        problems
+    }
+}
+
+/// A movement in the inventory history of a garment
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Movement {
+    pub reason: Option<MovementReason>,
+    pub quantity: i32,
+    pub balance: i32,
+    pub note: Option<String>,
+    pub recorded_at: DateTimeWithTimeZone,
+}
+
+impl From<&Model> for Movement {
+    fn from(movement: &Model) -> Self {
+        Self {
+            reason: MovementReason::parse(&movement.reason),
+            quantity: movement.quantity,
+            balance: movement.balance,
+            note: movement.note.clone(),
+            recorded_at: movement.created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if !insert && self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(chrono::Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Record a stock movement for a garment
+    ///
+    /// # Errors
+    ///
+    /// When the movement is invalid or would take the stock below zero
+    pub async fn record(
+        db: &DatabaseConnection,
+        item: &clothes::Model,
+        params: &MovementParams,
+    ) -> ModelResult<(clothes::Model, Self)> {
+        let txn = db.begin().await?;
+        // Lock the garment so concurrent movements see each other's balance
+        let item = clothes::Entity::find_by_id(item.id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let stock = Self::balance(&txn, item.id).await?;
+        if let Some(problem) = params.problems(stock).first() {
+            return Err(ModelError::msg(&format!(
+                "{} {}",
+                problem.field, problem.message
+            )));
+        }
+        let recorded = Self::append(
+            &txn,
+            item,
+            params.reason,
+            params.reason.change(params.quantity),
+            params.note.clone(),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(recorded)
+    }
+
+    /// Append a movement of `quantity` units (signed) and derive the stock of
+    /// the garment from the new balance. Callers lock the garment first.
+    pub(crate) async fn append<C: ConnectionTrait>(
+        db: &C,
+        item: clothes::Model,
+        reason: MovementReason,
+        quantity: i32,
+        note: Option<String>,
+    ) -> ModelResult<(clothes::Model, Self)> {
+        let balance = Self::balance(db, item.id).await? + quantity;
+        let movement = ActiveModel {
+            clothes_id: ActiveValue::set(item.id),
+            reason: ActiveValue::set(reason.as_str().to_string()),
+            quantity: ActiveValue::set(quantity),
+            balance: ActiveValue::set(balance),
+            note: ActiveValue::set(note),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        let mut active_model = item.into_active_model();
+        active_model.stock_quantity = ActiveValue::set(balance);
+        active_model.in_stock = ActiveValue::set(balance > 0);
+        Ok((active_model.update(db).await?, movement))
+    }
+
+    /// Stock of a garment according to the ledger
+    pub async fn balance<C: ConnectionTrait>(db: &C, clothes_id: i32) -> ModelResult<i32> {
+        Ok(inventory_movements::Entity::find()
+            .filter(inventory_movements::Column::ClothesId.eq(clothes_id))
+            .order_by_desc(inventory_movements::Column::Id)
+            .one(db)
+            .await?
+            .map_or(0, |movement| movement.balance))
+    }
+
+    /// Inventory history of a garment, oldest first
+    pub async fn history(
+        db: &DatabaseConnection,
+        item: &clothes::Model,
+    ) -> ModelResult<Vec<Movement>> {
+        Ok(inventory_movements::Entity::find()
+            .filter(inventory_movements::Column::ClothesId.eq(item.id))
+            .order_by_asc(inventory_movements::Column::Id)
+            .all(db)
+            .await?
+            .iter()
+            .map(Movement::from)
+            .collect())
+    }
+}
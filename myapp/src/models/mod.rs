@@ -4,4 +4,25 @@ pub mod clothes;
 pub mod coordinates;
 pub mod passkeys;
 pub mod clothes_coordinates;
+pub mod clothes_imports;
 pub mod webauthn_sessions;
+pub mod wardrobe_exports;
+pub mod wear_events;
+pub mod outfit_plans;
+pub mod calendar_feeds;
+pub mod suggestions;
+pub mod outfit_images;
+pub mod coordinate_rules;
+pub mod coordinate_versions;
+pub mod outfit_feedback;
+pub mod trips;
+pub mod trip_packing_items;
+pub mod capsules;
+pub mod coordinate_tombstones;
+pub mod trash;
+pub mod clothes_state_changes;
+pub mod inventory_movements;
+pub mod exchange_rates;
+pub mod budgets;
+pub mod notifications;
+pub mod purchases;
@@ -0,0 +1,109 @@
+//! In-app notifications, listed to the user until they are read.
+
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::notifications::{self, ActiveModel, Entity, Model};
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Spending went over a monthly budget
+    BudgetExceeded,
+}
+
+impl NotificationKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BudgetExceeded => "budget_exceeded",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct NotificationQuery {
+    /// Only list notifications that were not read yet
+    #[serde(default)]
+    pub unread: bool,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let mut this = self;
+        if insert {
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+        } else if this.updated_at.is_unchanged() {
+            this.updated_at = ActiveValue::Set(chrono::Utc::now().into());
+        }
+        Ok(this)
+    }
+}
+
+impl Model {
+    /// Notify a user. `data` carries the details clients render it with.
+    pub async fn notify<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        data: Option<serde_json::Value>,
+    ) -> ModelResult<Self> {
+        Ok(ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            kind: ActiveValue::set(kind.as_str().to_string()),
+            title: ActiveValue::set(title.to_string()),
+            body: ActiveValue::set(body.to_string()),
+            data: ActiveValue::set(data),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    /// Notifications of a user, latest first
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        query: &NotificationQuery,
+    ) -> ModelResult<Vec<Self>> {
+        let mut select =
+            notifications::Entity::find().filter(notifications::Column::UserId.eq(user_id));
+        if query.unread {
+            select = select.filter(notifications::Column::ReadAt.is_null());
+        }
+        Ok(select
+            .order_by_desc(notifications::Column::CreatedAt)
+            .order_by_desc(notifications::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let notification = notifications::Entity::find()
+            .filter(notifications::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        notification.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Mark a notification read, keeping the time it was first read
+    pub async fn mark_read_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let notification = Self::find_by_pid(db, pid).await?;
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+        let mut notification = notification.into_active_model();
+        notification.read_at = ActiveValue::set(Some(chrono::Utc::now().into()));
+        Ok(notification.update(db).await?)
+    }
+}
@@ -0,0 +1,285 @@
+//! How an outfit went after wearing it.
+//!
+//! Feedback rates a coordinate from 1 to 5 for comfort, fit for the weather
+//! and compliments received, and may point at the wear event it is about.
+//! The averages are shown with the coordinate and favor its garments in
+//! suggestions.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::outfit_feedback::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{coordinates, wear_events},
+    coordinate_rules::FieldError,
+};
+
+/// Lowest and highest rating
+pub const RATING_RANGE: std::ops::RangeInclusive<i16> = 1..=5;
+
+/// Feedback on a worn coordinate
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateFeedbackParams {
+    pub user_id: i32,
+    pub coordinate_pid: String,
+    /// Wear event of the coordinate the feedback is about
+    pub wear_event_pid: Option<String>,
+    /// Defaults to the day of the wear event, or today
+    pub worn_on: Option<Date>,
+    #[serde(flatten)]
+    pub ratings: FeedbackRatings,
+}
+
+/// Ratings and note of a feedback. On update, fields that are not given are
+/// kept.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct FeedbackRatings {
+    pub comfort: Option<i16>,
+    pub weather_fit: Option<i16>,
+    pub compliments: Option<i16>,
+    pub note: Option<String>,
+}
+
+impl FeedbackRatings {
+    /// Ratings outside 1 to 5
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        [
+            ("comfort", self.comfort),
+            ("weather_fit", self.weather_fit),
+            ("compliments", self.compliments),
+        ]
+        .into_iter()
+        .filter_map(|(field, rating)| {
+            rating
+                .filter(|rating| !RATING_RANGE.contains(rating))
+                .map(|_| {
+                    FieldError::new(
+                        field,
+                        format!(
+                            "must be between {} and {}",
+                            RATING_RANGE.start(),
+                            RATING_RANGE.end()
+                        ),
+                    )
+                })
+        })
+        .collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.comfort.is_none()
+            && self.weather_fit.is_none()
+            && self.compliments.is_none()
+            && self
+                .note
+                .as_deref()
+                .is_none_or(|note| note.trim().is_empty())
+    }
+}
+
+impl CreateFeedbackParams {
+    /// Ratings outside 1 to 5, and feedback without any rating or note
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        let mut problems = self.ratings.problems();
+        if self.ratings.is_empty() {
+            problems.push(FieldError::new(
+                "ratings",
+                "give at least one rating or a note",
+            ));
+        }
+        problems
+    }
+}
+
+/// Average ratings of a coordinate's feedback
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RatingSummary {
+    pub count: usize,
+    pub comfort: Option<f64>,
+    pub weather_fit: Option<f64>,
+    pub compliments: Option<f64>,
+    /// Average of every rating given
+    pub overall: Option<f64>,
+}
+
+impl RatingSummary {
+    #[must_use]
+    pub fn from_feedback<'a>(feedback: impl IntoIterator<Item = &'a Model>) -> Self {
+        let feedback: Vec<&Model> = feedback.into_iter().collect();
+        let comfort: Vec<i16> = feedback.iter().filter_map(|f| f.comfort).collect();
+        let weather_fit: Vec<i16> = feedback.iter().filter_map(|f| f.weather_fit).collect();
+        let compliments: Vec<i16> = feedback.iter().filter_map(|f| f.compliments).collect();
+        let all: Vec<i16> = [&comfort, &weather_fit, &compliments]
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect();
+        Self {
+            count: feedback.len(),
+            comfort: average(&comfort),
+            weather_fit: average(&weather_fit),
+            compliments: average(&compliments),
+            overall: average(&all),
+        }
+    }
+
+    /// Summaries of several coordinates at once, keyed by coordinate ID.
+    /// Coordinates without feedback are left out.
+    pub async fn for_coordinates<C: ConnectionTrait>(
+        db: &C,
+        coordinate_ids: &[i32],
+    ) -> ModelResult<HashMap<i32, Self>> {
+        let feedback = outfit_feedback::Entity::find()
+            .filter(outfit_feedback::Column::CoordinateId.is_in(coordinate_ids.iter().copied()))
+            .all(db)
+            .await?;
+        let mut by_coordinate: HashMap<i32, Vec<&Model>> = HashMap::new();
+        for item in &feedback {
+            by_coordinate
+                .entry(item.coordinate_id)
+                .or_default()
+                .push(item);
+        }
+        Ok(by_coordinate
+            .into_iter()
+            .map(|(coordinate_id, feedback)| (coordinate_id, Self::from_feedback(feedback)))
+            .collect())
+    }
+}
+
+/// Average rounded to two decimals, `None` without ratings
+fn average(ratings: &[i16]) -> Option<f64> {
+    if ratings.is_empty() {
+        return None;
+    }
+    let sum: f64 = ratings.iter().map(|rating| f64::from(*rating)).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let average = sum / ratings.len() as f64;
+    Some((average * 100.0).round() / 100.0)
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Find feedback by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let feedback = outfit_feedback::Entity::find()
+            .filter(outfit_feedback::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        feedback.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Record feedback on a coordinate of the user. A given wear event must
+    /// belong to the coordinate.
+    pub async fn create(
+        db: &DatabaseConnection,
+        params: &CreateFeedbackParams,
+    ) -> ModelResult<Self> {
+        let coordinate = super::coordinates::Model::find_by_pid(db, &params.coordinate_pid).await?;
+        if coordinate.user_id != params.user_id {
+            return Err(ModelError::EntityNotFound);
+        }
+
+        let wear_event = match &params.wear_event_pid {
+            Some(pid) => {
+                let pid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+                let event = wear_events::Entity::find()
+                    .filter(wear_events::Column::Pid.eq(pid))
+                    .filter(wear_events::Column::CoordinateId.eq(coordinate.id))
+                    .one(db)
+                    .await?;
+                Some(event.ok_or_else(|| ModelError::EntityNotFound)?)
+            }
+            None => None,
+        };
+        let worn_on = params
+            .worn_on
+            .or_else(|| wear_event.as_ref().map(|event| event.worn_on))
+            .unwrap_or_else(|| Utc::now().date_naive());
+
+        let feedback = outfit_feedback::ActiveModel {
+            user_id: ActiveValue::set(params.user_id),
+            coordinate_id: ActiveValue::set(coordinate.id),
+            wear_event_id: ActiveValue::set(wear_event.map(|event| event.id)),
+            worn_on: ActiveValue::set(worn_on),
+            comfort: ActiveValue::set(params.ratings.comfort),
+            weather_fit: ActiveValue::set(params.ratings.weather_fit),
+            compliments: ActiveValue::set(params.ratings.compliments),
+            note: ActiveValue::set(params.ratings.note.clone()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(feedback)
+    }
+
+    /// Feedback on a coordinate, most recent wear first
+    pub async fn find_by_coordinate(
+        db: &DatabaseConnection,
+        coordinate: &coordinates::Model,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(outfit_feedback::Entity::find()
+            .filter(outfit_feedback::Column::CoordinateId.eq(coordinate.id))
+            .order_by_desc(outfit_feedback::Column::WornOn)
+            .order_by_desc(outfit_feedback::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Change the ratings or note of a feedback
+    pub async fn update_by_pid(
+        db: &DatabaseConnection,
+        pid: &str,
+        ratings: &FeedbackRatings,
+    ) -> ModelResult<Self> {
+        let feedback = Self::find_by_pid(db, pid).await?;
+        let mut active_model = feedback.into_active_model();
+        if let Some(comfort) = ratings.comfort {
+            active_model.comfort = ActiveValue::set(Some(comfort));
+        }
+        if let Some(weather_fit) = ratings.weather_fit {
+            active_model.weather_fit = ActiveValue::set(Some(weather_fit));
+        }
+        if let Some(compliments) = ratings.compliments {
+            active_model.compliments = ActiveValue::set(Some(compliments));
+        }
+        if let Some(note) = &ratings.note {
+            active_model.note = ActiveValue::set(Some(note.clone()));
+        }
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Delete a feedback
+    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+        let feedback = Self::find_by_pid(db, pid).await?;
+        feedback.delete(db).await?;
+        Ok(())
+    }
+}
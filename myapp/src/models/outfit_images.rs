@@ -0,0 +1,373 @@
+//! Composite outfit images.
+//!
+//! The garment images of a coordinate are composed into a single flat-lay
+//! image. Garments with a canvas position are drawn where the user placed
+//! them; the others are arranged in a grid ordered from head to toe. The
+//! result is kept in the app storage and becomes the coordinate's image
+//! unless the user supplied an image of their own.
+
+use std::{io::Cursor, path::Path};
+
+use image::{
+    DynamicImage, ImageFormat, Rgba, RgbaImage,
+    imageops::{self, FilterType},
+};
+use loco_rs::{prelude::*, storage::Storage};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    _entities::{clothes, clothes_coordinates, coordinates},
+    clothes_coordinates::ClothesLayout,
+};
+use crate::{
+    common::{images::ImageFetcher, settings::OutfitImageSettings},
+    data::colors,
+};
+
+/// A garment at scale 1 fits into a box of this fraction of the canvas
+const BASE_SIZE: f64 = 1.0 / 3.0;
+
+/// Margin kept around garments in a grid cell, as a fraction of the cell
+const GRID_PADDING: f64 = 0.05;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutfitImageFormat {
+    #[default]
+    Png,
+    /// Lossless WebP
+    Webp,
+}
+
+impl OutfitImageFormat {
+    #[must_use]
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Format of a rendered image from its storage path
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "png" => Some(Self::Png),
+            "webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+/// Where a garment is drawn, in canvas pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub center_x: f64,
+    pub center_y: f64,
+    /// The garment is scaled to fit into this box, keeping its aspect ratio
+    pub width: f64,
+    pub height: f64,
+    /// Degrees, clockwise
+    pub rotation: f64,
+}
+
+/// Garment image and its layout in the coordinate
+#[derive(Debug, Clone)]
+pub struct OutfitImageItem {
+    pub layout: ClothesLayout,
+    pub data: Vec<u8>,
+}
+
+/// Canvas the garments are composed on
+#[derive(Debug, Clone)]
+pub struct OutfitCanvas {
+    pub width: u32,
+    pub height: u32,
+    pub background: [u8; 3],
+}
+
+impl OutfitCanvas {
+    /// # Errors
+    ///
+    /// When the configured size is empty or the background is not a hex
+    /// color
+    pub fn from_settings(settings: &OutfitImageSettings) -> ModelResult<Self> {
+        if settings.width == 0 || settings.height == 0 {
+            return Err(ModelError::msg("outfit image size must not be empty"));
+        }
+        let background = colors::parse_hex(&settings.background).ok_or_else(|| {
+            ModelError::msg(&format!(
+                "invalid outfit image background: {}",
+                settings.background
+            ))
+        })?;
+        Ok(Self {
+            width: settings.width,
+            height: settings.height,
+            background,
+        })
+    }
+
+    /// Placement of each layout, in the same order. `x` and `y` are the
+    /// center of the garment as fractions of the canvas size; garments
+    /// without both are arranged in a grid ordered by slot.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn placements(&self, layouts: &[ClothesLayout]) -> Vec<Placement> {
+        let width = f64::from(self.width);
+        let height = f64::from(self.height);
+
+        let mut unplaced: Vec<usize> = (0..layouts.len())
+            .filter(|&index| layouts[index].x.is_none() || layouts[index].y.is_none())
+            .collect();
+        // Garments without a slot go last
+        unplaced.sort_by_key(|&index| (layouts[index].slot.is_none(), layouts[index].slot));
+        let columns = grid_columns(unplaced.len());
+        let rows = unplaced.len().div_ceil(columns.max(1));
+        let cell_width = width / columns.max(1) as f64;
+        let cell_height = height / rows.max(1) as f64;
+
+        let mut placements = vec![
+            Placement {
+                center_x: 0.0,
+                center_y: 0.0,
+                width: 0.0,
+                height: 0.0,
+                rotation: 0.0,
+            };
+            layouts.len()
+        ];
+        for (cell, &index) in unplaced.iter().enumerate() {
+            let (row, column) = (cell / columns, cell % columns);
+            placements[index] = Placement {
+                center_x: (column as f64 + 0.5) * cell_width,
+                center_y: (row as f64 + 0.5) * cell_height,
+                width: cell_width * (1.0 - 2.0 * GRID_PADDING),
+                height: cell_height * (1.0 - 2.0 * GRID_PADDING),
+                rotation: layouts[index].rotation.unwrap_or(0.0),
+            };
+        }
+        for (index, layout) in layouts.iter().enumerate() {
+            if let (Some(x), Some(y)) = (layout.x, layout.y) {
+                let scale = layout.scale.unwrap_or(1.0);
+                placements[index] = Placement {
+                    center_x: x * width,
+                    center_y: y * height,
+                    width: width * BASE_SIZE * scale,
+                    height: height * BASE_SIZE * scale,
+                    rotation: layout.rotation.unwrap_or(0.0),
+                };
+            }
+        }
+        placements
+    }
+
+    /// Compose the garments, drawn in `z_index` order, and encode the image.
+    /// Images that cannot be decoded are left out.
+    ///
+    /// # Errors
+    ///
+    /// When none of the images can be decoded or encoding fails
+    pub fn render(
+        &self,
+        items: &[OutfitImageItem],
+        format: OutfitImageFormat,
+    ) -> ModelResult<Vec<u8>> {
+        let layouts: Vec<ClothesLayout> = items.iter().map(|item| item.layout.clone()).collect();
+        let placements = self.placements(&layouts);
+
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&index| items[index].layout.z_index.unwrap_or(0));
+
+        let [r, g, b] = self.background;
+        let mut canvas = RgbaImage::from_pixel(self.width, self.height, Rgba([r, g, b, 255]));
+        let mut drawn = 0;
+        for index in order {
+            let garment = match image::load_from_memory(&items[index].data) {
+                Ok(garment) => garment.to_rgba8(),
+                Err(err) => {
+                    tracing::warn!(error = %err, "skipping undecodable garment image");
+                    continue;
+                }
+            };
+            draw(&mut canvas, &garment, &placements[index]);
+            drawn += 1;
+        }
+        if drawn == 0 {
+            return Err(ModelError::msg(
+                "none of the garment images could be decoded",
+            ));
+        }
+
+        let mut data = Vec::new();
+        DynamicImage::ImageRgba8(canvas)
+            .to_rgb8()
+            .write_to(
+                &mut Cursor::new(&mut data),
+                match format {
+                    OutfitImageFormat::Png => ImageFormat::Png,
+                    OutfitImageFormat::Webp => ImageFormat::WebP,
+                },
+            )
+            .map_err(|e| ModelError::msg(&format!("Could not encode outfit image: {e}")))?;
+        Ok(data)
+    }
+}
+
+/// Columns of a grid for `count` garments, as square as possible
+fn grid_columns(count: usize) -> usize {
+    (1..=count)
+        .find(|columns| columns * columns >= count)
+        .unwrap_or(1)
+}
+
+/// Scale `garment` into the placement box, rotate it and draw it centered on
+/// the placement
+fn draw(canvas: &mut RgbaImage, garment: &RgbaImage, placement: &Placement) {
+    let fit = (placement.width / f64::from(garment.width()))
+        .min(placement.height / f64::from(garment.height()));
+    let width = (f64::from(garment.width()) * fit).round();
+    let height = (f64::from(garment.height()) * fit).round();
+    if !(width >= 1.0 && height >= 1.0) {
+        return;
+    }
+    // Bounded by the canvas-relative box size
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let resized = imageops::resize(garment, width as u32, height as u32, FilterType::Triangle);
+    let rotated = rotate(&resized, placement.rotation);
+
+    #[allow(clippy::cast_possible_truncation)]
+    let (left, top) = (
+        (placement.center_x - f64::from(rotated.width()) / 2.0).round() as i64,
+        (placement.center_y - f64::from(rotated.height()) / 2.0).round() as i64,
+    );
+    imageops::overlay(canvas, &rotated, left, top);
+}
+
+/// Rotate clockwise by `degrees` around the center, growing the image to fit
+/// and leaving the corners transparent
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn rotate(image: &RgbaImage, degrees: f64) -> RgbaImage {
+    let degrees = degrees.rem_euclid(360.0);
+    if degrees == 0.0 {
+        return image.clone();
+    }
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (width, height) = (f64::from(image.width()), f64::from(image.height()));
+    let out_width = (width * cos.abs() + height * sin.abs()).ceil().max(1.0);
+    let out_height = (width * sin.abs() + height * cos.abs()).ceil().max(1.0);
+
+    let mut rotated = RgbaImage::new(out_width as u32, out_height as u32);
+    for (x, y, pixel) in rotated.enumerate_pixels_mut() {
+        // Map back to the source pixel, nearest neighbour
+        let dx = f64::from(x) + 0.5 - out_width / 2.0;
+        let dy = f64::from(y) + 0.5 - out_height / 2.0;
+        let source_x = dx * cos + dy * sin + width / 2.0;
+        let source_y = -dx * sin + dy * cos + height / 2.0;
+        if source_x >= 0.0 && source_y >= 0.0 && source_x < width && source_y < height {
+            *pixel = *image.get_pixel(source_x as u32, source_y as u32);
+        }
+    }
+    rotated
+}
+
+/// Storage path of the rendered image of a coordinate
+#[must_use]
+pub fn storage_path(coordinate_pid: &Uuid, format: OutfitImageFormat) -> String {
+    format!("coordinates/{coordinate_pid}/outfit.{}", format.extension())
+}
+
+/// Whether the coordinate's image was rendered by this module rather than
+/// supplied by the user
+#[must_use]
+pub fn is_rendered(coordinate: &coordinates::Model) -> bool {
+    coordinate.image_url.as_deref().is_some_and(|url| {
+        url.starts_with(&format!("coordinates/{}/outfit.", coordinate.pid))
+            && OutfitImageFormat::from_path(url).is_some()
+    })
+}
+
+/// Render the composite image of a coordinate, store it and set it as the
+/// coordinate's image.
+///
+/// An image supplied by the user is only replaced when `replace` is set.
+/// Returns `None` when nothing was rendered: the coordinate keeps a user
+/// image, or none of its garments has an image. A stale rendered image is
+/// cleared when the coordinate no longer has garment images.
+///
+/// # Errors
+///
+/// When the coordinate is not found or the image cannot be stored
+pub async fn render_coordinate(
+    db: &DatabaseConnection,
+    storage: &Storage,
+    fetcher: &ImageFetcher,
+    settings: &OutfitImageSettings,
+    pid: &str,
+    replace: bool,
+) -> ModelResult<Option<coordinates::Model>> {
+    let coordinate = coordinates::Model::find_by_pid(db, pid).await?;
+    if coordinate.image_url.is_some() && !is_rendered(&coordinate) && !replace {
+        return Ok(None);
+    }
+
+    let links = clothes_coordinates::Entity::find()
+        .filter(clothes_coordinates::Column::CoordinateId.eq(coordinate.id))
+        .find_also_related(clothes::Entity)
+        .all(db)
+        .await?;
+    let mut items = Vec::new();
+    for (link, item) in links {
+        let Some(source) = item.and_then(|item| item.image_url) else {
+            continue;
+        };
+        match fetcher.fetch(&source, storage).await {
+            Ok(data) => items.push(OutfitImageItem {
+                layout: link.layout(),
+                data,
+            }),
+            Err(err) => {
+                tracing::warn!(source, error = err, "skipping garment image");
+            }
+        }
+    }
+
+    let canvas = OutfitCanvas::from_settings(settings)?;
+    let rendered = if items.is_empty() {
+        None
+    } else {
+        match canvas.render(&items, settings.format) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                tracing::warn!(coordinate_pid = pid, error = %err, "outfit image not rendered");
+                None
+            }
+        }
+    };
+    let Some(data) = rendered else {
+        if is_rendered(&coordinate) {
+            let mut active_model = coordinate.into_active_model();
+            active_model.image_url = ActiveValue::set(None);
+            active_model.update(db).await?;
+        }
+        return Ok(None);
+    };
+
+    let path = storage_path(&coordinate.pid, settings.format);
+    storage
+        .upload(Path::new(&path), &data.into())
+        .await
+        .map_err(|e| ModelError::msg(&format!("Could not store outfit image: {e}")))?;
+    let mut active_model = coordinate.into_active_model();
+    active_model.image_url = ActiveValue::set(Some(path));
+    Ok(Some(active_model.update(db).await?))
+}
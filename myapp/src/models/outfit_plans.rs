@@ -0,0 +1,303 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, Months, Utc};
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::outfit_plans::{self, ActiveModel, Entity, Model};
+use super::{_entities::coordinates, coordinates::CoordinateWithClothes, wear_events};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatePlanParams {
+    pub user_id: i32,
+    pub coordinate_pid: String,
+    pub planned_on: Date,
+    pub occasion: Option<String>,
+    pub note: Option<String>,
+}
+
+/// Reschedule a plan or change its details
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdatePlanParams {
+    pub planned_on: Option<Date>,
+    pub occasion: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarView {
+    Week,
+    Month,
+}
+
+/// Calendar range: either explicit `from`/`to` dates, or the week or month
+/// (default) containing `date` (default today)
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CalendarQuery {
+    pub view: Option<CalendarView>,
+    pub date: Option<Date>,
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+}
+
+impl CalendarQuery {
+    /// First and last day (inclusive) of the requested range. Weeks start on
+    /// Monday.
+    ///
+    /// # Errors
+    ///
+    /// When only one of `from`/`to` is given or `from` is after `to`
+    pub fn range(&self, today: Date) -> ModelResult<(Date, Date)> {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) if from <= to => return Ok((from, to)),
+            (Some(_), Some(_)) => return Err(ModelError::msg("from must not be after to")),
+            (Some(_), None) | (None, Some(_)) => {
+                return Err(ModelError::msg("from and to must be given together"));
+            }
+            (None, None) => {}
+        }
+
+        let date = self.date.unwrap_or(today);
+        match self.view.unwrap_or(CalendarView::Month) {
+            CalendarView::Week => {
+                let monday =
+                    date - Duration::days(i64::from(date.weekday().num_days_from_monday()));
+                Ok((monday, monday + Duration::days(6)))
+            }
+            CalendarView::Month => {
+                let first = date.with_day(1).unwrap_or(date);
+                let last = first
+                    .checked_add_months(Months::new(1))
+                    .and_then(|next| next.pred_opt())
+                    .unwrap_or(date);
+                Ok((first, last))
+            }
+        }
+    }
+}
+
+/// A plan with its coordinate and clothes
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlannedOutfit {
+    #[serde(flatten)]
+    pub plan: Model,
+    pub coordinate: CoordinateWithClothes,
+}
+
+impl PlannedOutfit {
+    /// Leave out the garments of an upcoming plan that cannot be worn right
+    /// now; done plans keep what was worn
+    #[must_use]
+    pub fn without_unavailable(mut self) -> Self {
+        if self.plan.done_at.is_none() {
+            self.coordinate.retain_available();
+        }
+        self
+    }
+}
+
+/// A garment that appears in more than one plan on the same day
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlanConflict {
+    pub date: Date,
+    pub clothes_id: i32,
+    pub clothes_name: String,
+    pub plans: Vec<Uuid>,
+}
+
+/// Find garments planned more than once on the same day
+#[must_use]
+pub fn find_conflicts(plans: &[PlannedOutfit]) -> Vec<PlanConflict> {
+    let mut usage: BTreeMap<(Date, i32), PlanConflict> = BTreeMap::new();
+    for planned in plans {
+        for item in &planned.coordinate.clothes {
+            let conflict = usage
+                .entry((planned.plan.planned_on, item.id))
+                .or_insert_with(|| PlanConflict {
+                    date: planned.plan.planned_on,
+                    clothes_id: item.id,
+                    clothes_name: item.name.clone(),
+                    plans: Vec::new(),
+                });
+            if !conflict.plans.contains(&planned.plan.pid) {
+                conflict.plans.push(planned.plan.pid);
+            }
+        }
+    }
+    usage
+        .into_values()
+        .filter(|conflict| conflict.plans.len() > 1)
+        .collect()
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Plan a coordinate of the user on a date
+    pub async fn create(db: &DatabaseConnection, params: &CreatePlanParams) -> ModelResult<Self> {
+        let coordinate = coordinates::Model::find_by_pid(db, &params.coordinate_pid).await?;
+        if coordinate.user_id != params.user_id {
+            return Err(ModelError::EntityNotFound);
+        }
+
+        let plan = outfit_plans::ActiveModel {
+            user_id: ActiveValue::set(params.user_id),
+            coordinate_id: ActiveValue::set(coordinate.id),
+            planned_on: ActiveValue::set(params.planned_on),
+            occasion: ActiveValue::set(params.occasion.clone()),
+            note: ActiveValue::set(params.note.clone()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(plan)
+    }
+
+    /// Find plan by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let plan = outfit_plans::Entity::find()
+            .filter(outfit_plans::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        plan.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Plans of a user between two dates (inclusive), with the clothes that
+    /// can be worn
+    pub async fn find_in_range(
+        db: &DatabaseConnection,
+        user_id: i32,
+        from: Date,
+        to: Date,
+    ) -> ModelResult<Vec<PlannedOutfit>> {
+        let plans = outfit_plans::Entity::find()
+            .filter(
+                model::query::condition()
+                    .eq(outfit_plans::Column::UserId, user_id)
+                    .build(),
+            )
+            .filter(outfit_plans::Column::PlannedOn.between(from, to))
+            .order_by_asc(outfit_plans::Column::PlannedOn)
+            .order_by_asc(outfit_plans::Column::Id)
+            .all(db)
+            .await?;
+        Ok(Self::with_coordinates(db, plans)
+            .await?
+            .into_iter()
+            .map(PlannedOutfit::without_unavailable)
+            .collect())
+    }
+
+    /// Attach the coordinate and clothes of each plan
+    pub async fn with_coordinates(
+        db: &DatabaseConnection,
+        plans: Vec<Self>,
+    ) -> ModelResult<Vec<PlannedOutfit>> {
+        let coordinates = coordinates::Entity::find_live()
+            .filter(coordinates::Column::Id.is_in(plans.iter().map(|plan| plan.coordinate_id)))
+            .all(db)
+            .await?;
+        let coordinates = coordinates::Model::with_clothes(db, coordinates).await?;
+
+        Ok(plans
+            .into_iter()
+            .filter_map(|plan| {
+                let coordinate = coordinates
+                    .iter()
+                    .find(|c| c.coordinate.id == plan.coordinate_id)?
+                    .clone();
+                Some(PlannedOutfit { plan, coordinate })
+            })
+            .collect())
+    }
+
+    /// Garments of this plan that are also planned elsewhere on the same day
+    pub async fn conflicts(&self, db: &DatabaseConnection) -> ModelResult<Vec<PlanConflict>> {
+        let same_day =
+            Self::find_in_range(db, self.user_id, self.planned_on, self.planned_on).await?;
+        Ok(find_conflicts(&same_day)
+            .into_iter()
+            .filter(|conflict| conflict.plans.contains(&self.pid))
+            .collect())
+    }
+
+    /// Reschedule or edit a plan
+    pub async fn update_by_pid(
+        db: &DatabaseConnection,
+        pid: &str,
+        params: &UpdatePlanParams,
+    ) -> ModelResult<Self> {
+        let plan = Self::find_by_pid(db, pid).await?;
+        let mut active_model = plan.into_active_model();
+
+        if let Some(planned_on) = params.planned_on {
+            active_model.planned_on = ActiveValue::set(planned_on);
+        }
+        if let Some(occasion) = &params.occasion {
+            active_model.occasion = ActiveValue::set(Some(occasion.clone()));
+        }
+        if let Some(note) = &params.note {
+            active_model.note = ActiveValue::set(Some(note.clone()));
+        }
+
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Delete plan by PID
+    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+        let plan = Self::find_by_pid(db, pid).await?;
+        plan.delete(db).await?;
+        Ok(())
+    }
+
+    /// Mark the planned day as done, logging a wear of every garment of the
+    /// coordinate that can be worn right now on the planned date, the same
+    /// garments the calendar shows. Returns `None` when the plan is already
+    /// done.
+    pub async fn mark_done(
+        self,
+        db: &DatabaseConnection,
+    ) -> ModelResult<Option<(Self, Vec<wear_events::Model>)>> {
+        let coordinate = coordinates::Entity::find_by_id(self.coordinate_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+
+        let txn = db.begin().await?;
+        // Lock the plan, a concurrent request may have marked it done
+        let plan = outfit_plans::Entity::find_by_id(self.id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        if plan.done_at.is_some() {
+            return Ok(None);
+        }
+        let planned_on = plan.planned_on;
+        let mut active_model = plan.into_active_model();
+        active_model.done_at = ActiveValue::set(Some(Utc::now().into()));
+        let plan = active_model.update(&txn).await?;
+        let events = wear_events::Model::log_coordinate(&txn, &coordinate, planned_on).await?;
+        txn.commit().await?;
+
+        Ok(Some((plan, events)))
+    }
+}
@@ -0,0 +1,326 @@
+//! Purchases and spending.
+//!
+//! Every garment with a purchase date counts as a purchase of its price
+//! times the units bought, which are the purchases in its inventory ledger,
+//! or a single unit when none were recorded. Sold and donated garments still
+//! count, trashed ones do not. Clothes have no owner, so the purchases of a
+//! user are the clothes in their coordinates. Spending is reported in one currency, grouped
+//! by month, brand, category and store, and checked against the user's
+//! monthly budgets.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashMap},
+};
+
+use chrono::{Datelike, Months};
+use loco_rs::prelude::*;
+use sea_orm::{QueryOrder, prelude::Decimal};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    _entities::{clothes, inventory_movements, users},
+    budgets::{self, BudgetStatus},
+    exchange_rates::Rates,
+    inventory_movements::MovementReason,
+};
+use crate::data::money::{Currency, Money};
+
+/// File types accepted as receipts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptType {
+    Pdf,
+    Jpeg,
+    Png,
+    Webp,
+}
+
+impl ReceiptType {
+    #[must_use]
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Jpeg => "jpg",
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Type of an uploaded receipt from its `Content-Type`, ignoring
+    /// parameters such as `charset`
+    #[must_use]
+    pub fn from_content_type(value: &str) -> Option<Self> {
+        let essence = value.split(';').next()?.trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "application/pdf" => Some(Self::Pdf),
+            "image/jpeg" | "image/jpg" => Some(Self::Jpeg),
+            "image/png" => Some(Self::Png),
+            "image/webp" => Some(Self::Webp),
+            _ => None,
+        }
+    }
+}
+
+/// Storage path of the receipt of a garment
+#[must_use]
+pub fn receipt_path(pid: &Uuid, kind: ReceiptType) -> String {
+    format!("receipts/{pid}.{}", kind.extension())
+}
+
+/// First day of the month of `date`
+#[must_use]
+pub fn month_start(date: Date) -> Date {
+    date.with_day(1).unwrap_or(date)
+}
+
+/// Last day of the month of `date`
+#[must_use]
+pub fn month_end(date: Date) -> Date {
+    month_start(date)
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .unwrap_or(date)
+}
+
+/// A garment bought on its purchase date
+#[derive(Debug, Clone)]
+pub struct Purchase {
+    pub clothes: clothes::Model,
+    /// Units bought, at least one
+    pub units: i32,
+}
+
+impl Purchase {
+    /// First day of the month the garment was bought in
+    #[must_use]
+    pub fn month(&self) -> Option<Date> {
+        self.clothes.purchase_date.map(month_start)
+    }
+
+    /// Price of every unit bought, in the garment's currency, `None` when
+    /// it is too large for a decimal
+    #[must_use]
+    pub fn cost(&self) -> Option<Money> {
+        let price = self.clothes.money();
+        price
+            .amount
+            .checked_mul(Decimal::from(self.units.max(1)))
+            .map(|amount| Money::new(amount, price.currency))
+    }
+
+    /// Purchases of a user made between `from` and `to`, both included,
+    /// oldest first
+    pub async fn find(
+        db: &DatabaseConnection,
+        user_id: i32,
+        from: Option<Date>,
+        to: Option<Date>,
+    ) -> ModelResult<Vec<Self>> {
+        let mut select = clothes::Entity::find_owned(user_id)
+            .filter(clothes::Column::PurchaseDate.is_not_null());
+        if let Some(from) = from {
+            select = select.filter(clothes::Column::PurchaseDate.gte(from));
+        }
+        if let Some(to) = to {
+            select = select.filter(clothes::Column::PurchaseDate.lte(to));
+        }
+        let clothes = select
+            .order_by_asc(clothes::Column::PurchaseDate)
+            .order_by_asc(clothes::Column::Id)
+            .all(db)
+            .await?;
+
+        let mut units: HashMap<i32, i32> = HashMap::new();
+        let bought = inventory_movements::Entity::find()
+            .filter(inventory_movements::Column::ClothesId.is_in(clothes.iter().map(|c| c.id)))
+            .filter(inventory_movements::Column::Reason.eq(MovementReason::Purchase.as_str()))
+            .all(db)
+            .await?;
+        for movement in bought {
+            *units.entry(movement.clothes_id).or_default() += movement.quantity;
+        }
+
+        Ok(clothes
+            .into_iter()
+            .map(|clothes| Self {
+                units: units.get(&clothes.id).copied().unwrap_or_default().max(1),
+                clothes,
+            })
+            .collect())
+    }
+}
+
+/// How `budget` stands in the month of `month`. `purchases` may span other
+/// months; those without a rate to the budget's currency, or too large to
+/// convert, are left out.
+#[must_use]
+pub fn budget_status(
+    budget: &budgets::Model,
+    month: Date,
+    purchases: &[Purchase],
+    rates: &Rates,
+) -> BudgetStatus {
+    let month = month_start(month);
+    let currency = budget.limit().currency;
+    let spent = purchases
+        .iter()
+        .filter(|purchase| purchase.month() == Some(month))
+        .filter(|purchase| budget.covers(&purchase.clothes.category))
+        .filter_map(|purchase| rates.convert(purchase.cost()?, currency))
+        .fold(Decimal::ZERO, |sum, cost| sum.saturating_add(cost.amount));
+    budget.status(month, Money::new(spent, currency))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SpendingQuery {
+    /// First purchase date to include
+    pub from: Option<Date>,
+    /// Last purchase date to include
+    pub to: Option<Date>,
+    /// The user's home currency when omitted
+    pub currency: Option<Currency>,
+}
+
+/// Spending on purchases sharing a month, brand, category or store
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingGroup {
+    pub key: String,
+    pub purchases: usize,
+    pub total: Money,
+}
+
+/// Spending on purchases, in one currency
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingReport {
+    pub currency: Currency,
+    pub purchases: usize,
+    /// Sum of every purchase that could be converted
+    pub total: Money,
+    /// Keyed `YYYY-MM`, oldest first
+    pub by_month: Vec<SpendingGroup>,
+    /// Largest first, as are categories and stores
+    pub by_brand: Vec<SpendingGroup>,
+    pub by_category: Vec<SpendingGroup>,
+    /// Purchases without a store are grouped as `unknown`
+    pub by_store: Vec<SpendingGroup>,
+    /// Every budget in every month with purchases
+    pub budgets: Vec<BudgetStatus>,
+    /// Currencies without a rate to `currency`, or of purchases too large to
+    /// convert, left out of the totals
+    pub unconverted: Vec<Currency>,
+}
+
+impl SpendingReport {
+    #[must_use]
+    pub fn new(
+        purchases: &[Purchase],
+        currency: Currency,
+        rates: &Rates,
+        budgets: &[budgets::Model],
+    ) -> Self {
+        let mut unconverted = BTreeSet::new();
+        let converted: Vec<(&Purchase, Decimal)> = purchases
+            .iter()
+            .filter_map(|purchase| {
+                let converted = purchase
+                    .cost()
+                    .and_then(|cost| rates.convert(cost, currency));
+                if converted.is_none() {
+                    unconverted.insert(purchase.clothes.money().currency);
+                }
+                converted.map(|converted| (purchase, converted.amount))
+            })
+            .collect();
+
+        let group = |key: &dyn Fn(&Purchase) -> String| {
+            let mut groups: BTreeMap<String, (usize, Decimal)> = BTreeMap::new();
+            for (purchase, amount) in &converted {
+                let entry = groups.entry(key(purchase)).or_default();
+                entry.0 += 1;
+                entry.1 = entry.1.saturating_add(*amount);
+            }
+            groups
+                .into_iter()
+                .map(|(key, (purchases, total))| SpendingGroup {
+                    key,
+                    purchases,
+                    total: Money::new(total, currency),
+                })
+                .collect::<Vec<_>>()
+        };
+        let largest_first = |mut groups: Vec<SpendingGroup>| {
+            groups.sort_by_key(|group| Reverse(group.total.amount));
+            groups
+        };
+
+        let months: BTreeSet<Date> = purchases.iter().filter_map(Purchase::month).collect();
+        let statuses = months
+            .iter()
+            .flat_map(|month| {
+                budgets
+                    .iter()
+                    .map(|budget| budget_status(budget, *month, purchases, rates))
+            })
+            .collect();
+
+        Self {
+            currency,
+            purchases: purchases.len(),
+            total: Money::new(
+                converted.iter().fold(Decimal::ZERO, |sum, (_, amount)| {
+                    sum.saturating_add(*amount)
+                }),
+                currency,
+            ),
+            by_month: group(&|purchase| {
+                purchase
+                    .month()
+                    .map(|month| month.format("%Y-%m").to_string())
+                    .unwrap_or_default()
+            }),
+            by_brand: largest_first(group(&|purchase| purchase.clothes.brand.clone())),
+            by_category: largest_first(group(&|purchase| purchase.clothes.category.clone())),
+            by_store: largest_first(group(&|purchase| {
+                purchase
+                    .clothes
+                    .store
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|store| !store.is_empty())
+                    .unwrap_or("unknown")
+                    .to_string()
+            })),
+            budgets: statuses,
+            unconverted: unconverted.into_iter().collect(),
+        }
+    }
+
+    /// Spending in `query.currency` or the user's home currency, checked
+    /// against the user's budgets
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        query: &SpendingQuery,
+    ) -> ModelResult<Self> {
+        let user = users::Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| ModelError::EntityNotFound)?;
+        let currency = query.currency.unwrap_or_else(|| user.home_currency());
+        let purchases = Purchase::find(db, user.id, query.from, query.to).await?;
+        let rates = Rates::load(db).await?;
+        let budgets = budgets::Model::find_by_user(db, user.id).await?;
+        Ok(Self::new(&purchases, currency, &rates, &budgets))
+    }
+}
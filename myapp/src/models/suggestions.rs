@@ -0,0 +1,776 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use chrono::{Duration, Utc};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    _entities::{clothes, coordinates},
+    coordinates::CoordinateWithClothes,
+    wear_events::{self, WearRangeQuery},
+};
+use crate::{
+    common::weather::{Location, Weather},
+    data::{
+        colors::{self, ColorFamily},
+        seasons::{self, Season},
+        slots::Slot,
+        vocabularies::Occasion,
+    },
+};
+
+/// Garments per slot kept when combining outfits, to bound the search
+const MAX_CANDIDATES_PER_SLOT: usize = 6;
+
+/// Partial outfits kept after each slot is filled. Without it, every slot
+/// multiplies the outfits to score by up to `MAX_CANDIDATES_PER_SLOT`.
+const BEAM_WIDTH: usize = 120;
+
+/// Partial outfits a single garment may appear in, so the kept ones do not
+/// all share the best few garments
+const BEAM_REPEATS: usize = BEAM_WIDTH / MAX_CANDIDATES_PER_SLOT;
+
+/// Times a single garment may appear across the returned suggestions
+const MAX_REPEATS: usize = 2;
+
+/// Hue order of the color wheel used for harmony, neutrals excluded
+const COLOR_WHEEL: [ColorFamily; 7] = [
+    ColorFamily::Red,
+    ColorFamily::Orange,
+    ColorFamily::Yellow,
+    ColorFamily::Green,
+    ColorFamily::Blue,
+    ColorFamily::Purple,
+    ColorFamily::Pink,
+];
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SuggestionQuery {
+    pub user_id: i32,
+    pub season: Option<String>,
+    pub occasion: Option<String>,
+    /// `latitude,longitude` to suggest for the weather at
+    pub location: Option<String>,
+    /// Day to dress for, defaults to today. Also implies the season when
+    /// none is given.
+    pub date: Option<Date>,
+    /// Comma separated slots every suggestion must fill. Defaults to top,
+    /// bottom and shoes, plus outerwear in autumn, winter and cold weather.
+    pub slots: Option<String>,
+    /// Garments worn within this many days are avoided
+    pub recent_days: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+impl SuggestionQuery {
+    pub const DEFAULT_LIMIT: usize = 5;
+    pub const MAX_LIMIT: usize = 20;
+    pub const DEFAULT_RECENT_DAYS: i64 = 7;
+    pub const MAX_RECENT_DAYS: i64 = 365;
+
+    /// # Errors
+    ///
+    /// When the season is unknown
+    pub fn season(&self) -> ModelResult<Option<Season>> {
+        self.season
+            .as_deref()
+            .map(|value| {
+                Season::parse(value)
+                    .ok_or_else(|| ModelError::msg(&format!("unknown season: {value}")))
+            })
+            .transpose()
+    }
+
+    /// Requested season, or the season of the requested date
+    ///
+    /// # Errors
+    ///
+    /// When the season is unknown
+    pub fn season_or_date(&self) -> ModelResult<Option<Season>> {
+        let southern = self
+            .location
+            .as_deref()
+            .and_then(|location| Location::parse(location).ok())
+            .is_some_and(|location| location.latitude < 0.0);
+        Ok(self
+            .season()?
+            .or_else(|| self.date.map(|date| Season::from_date(date, southern))))
+    }
+
+    /// Slots to fill, in outfit order
+    ///
+    /// # Errors
+    ///
+    /// When a slot is unknown or none is given
+    pub fn slots(&self, needs_outerwear: bool) -> ModelResult<Vec<Slot>> {
+        let mut slots = match &self.slots {
+            Some(value) => value
+                .split(',')
+                .filter(|slot| !slot.trim().is_empty())
+                .map(|slot| {
+                    Slot::parse(slot)
+                        .ok_or_else(|| ModelError::msg(&format!("unknown slot: {slot}")))
+                })
+                .collect::<ModelResult<Vec<_>>>()?,
+            None => {
+                let mut slots = vec![Slot::Top, Slot::Bottom, Slot::Shoes];
+                if needs_outerwear {
+                    slots.push(Slot::Outer);
+                }
+                slots
+            }
+        };
+        if slots.is_empty() {
+            return Err(ModelError::msg("at least one slot is required"));
+        }
+        slots.sort();
+        slots.dedup();
+        Ok(slots)
+    }
+
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(Self::DEFAULT_LIMIT)
+            .clamp(1, Self::MAX_LIMIT)
+    }
+
+    #[must_use]
+    pub fn recent_days(&self) -> i64 {
+        self.recent_days
+            .unwrap_or(Self::DEFAULT_RECENT_DAYS)
+            .clamp(0, Self::MAX_RECENT_DAYS)
+    }
+}
+
+/// Seasons and occasions of the user's coordinates a garment appears in
+#[derive(Debug, Default, Clone)]
+pub struct GarmentUsage {
+    pub seasons: HashSet<Season>,
+    /// Occasion keys, or lowercased occasions outside the vocabulary
+    pub occasions: HashSet<String>,
+}
+
+/// Key of a known occasion, otherwise the lowercased text
+fn occasion_key(occasion: &str) -> Option<String> {
+    match Occasion::parse(occasion) {
+        Some(occasion) => Some(occasion.as_str().to_string()),
+        None => Some(occasion.trim().to_lowercase()).filter(|occasion| !occasion.is_empty()),
+    }
+}
+
+/// What a suggestion is scored against
+#[derive(Debug, Default, Clone)]
+pub struct SuggestionContext {
+    pub season: Option<Season>,
+    pub weather: Option<Weather>,
+    pub occasion: Option<String>,
+    /// Garments worn recently, with the last date they were worn
+    pub recently_worn: HashMap<i32, Date>,
+    pub usage: HashMap<i32, GarmentUsage>,
+    /// Sorted garment ids of the user's existing coordinates, which are not
+    /// suggested again
+    pub existing: HashSet<Vec<i32>>,
+    /// Average overall feedback rating, from 1 to 5, of the coordinates a
+    /// garment was worn in
+    pub ratings: HashMap<i32, f64>,
+}
+
+impl SuggestionContext {
+    #[must_use]
+    pub fn new(
+        season: Option<Season>,
+        occasion: Option<&str>,
+        coordinates: &[CoordinateWithClothes],
+        recent_wears: &[wear_events::Model],
+    ) -> Self {
+        let mut context = Self {
+            season,
+            occasion: occasion.and_then(occasion_key),
+            ..Self::default()
+        };
+
+        for event in recent_wears {
+            let last = context
+                .recently_worn
+                .entry(event.clothes_id)
+                .or_insert(event.worn_on);
+            *last = (*last).max(event.worn_on);
+        }
+
+        let mut rated: HashMap<i32, (f64, usize)> = HashMap::new();
+        for coordinate in coordinates {
+            if let Some(overall) = coordinate.ratings.overall {
+                for item in &coordinate.clothes {
+                    let (sum, count) = rated.entry(item.id).or_default();
+                    #[allow(clippy::cast_precision_loss)]
+                    let weight = coordinate.ratings.count as f64;
+                    *sum += overall * weight;
+                    *count += coordinate.ratings.count;
+                }
+            }
+
+            let seasons = coordinate.coordinate.seasons();
+            let occasion = coordinate.coordinate.occasion.as_deref().and_then(occasion_key);
+            for item in &coordinate.clothes {
+                let usage = context.usage.entry(item.id).or_default();
+                usage.seasons.extend(seasons.iter().copied());
+                usage.occasions.extend(occasion.clone());
+            }
+
+            let mut ids: Vec<i32> = coordinate.clothes.iter().map(|item| item.id).collect();
+            ids.sort_unstable();
+            context.existing.insert(ids);
+        }
+
+        for (id, (sum, count)) in rated {
+            if count > 0 {
+                #[allow(clippy::cast_precision_loss)]
+                let average = sum / count as f64;
+                context.ratings.insert(id, average);
+            }
+        }
+        context
+    }
+
+    /// How a garment suits the weather: `Some(false)` for garments left out
+    /// (light garments in the cold, warm ones in the heat, rain-sensitive
+    /// shoes in the rain), `Some(true)` for garments made for it
+    #[must_use]
+    pub fn weather_fit(&self, item: &clothes::Model) -> Option<bool> {
+        let weather = self.weather?;
+        let material = item.material.as_deref();
+        let light = seasons::is_light(&item.category, material);
+        let warm = seasons::is_warm(&item.category, material);
+        let shoes = Slot::from_category(&item.category) == Some(Slot::Shoes);
+
+        if (weather.is_cold() && light) || (weather.is_hot() && warm) {
+            return Some(false);
+        }
+        if weather.is_rainy() && shoes {
+            if seasons::is_rain_sensitive(&item.category, material) {
+                return Some(false);
+            }
+            if seasons::is_rain_proof(&item.category, material) {
+                return Some(true);
+            }
+        }
+        ((weather.is_cold() && warm) || (weather.is_hot() && light)).then_some(true)
+    }
+}
+
+/// A score between 0 and 1 with the reasons behind it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub value: f64,
+    pub reasons: Vec<String>,
+}
+
+/// Strategy for scoring a candidate outfit
+pub trait OutfitScorer: Send + Sync {
+    /// Score an outfit, or a single garment when pre-selecting candidates
+    fn score(&self, outfit: &[&clothes::Model], context: &SuggestionContext) -> Score;
+}
+
+/// Default scorer: weighted color harmony, season and occasion fit,
+/// feedback on past outfits and avoidance of recently worn garments
+#[derive(Debug, Clone)]
+pub struct HarmonyScorer {
+    pub color_weight: f64,
+    pub season_weight: f64,
+    pub weather_weight: f64,
+    pub occasion_weight: f64,
+    pub rating_weight: f64,
+    pub freshness_weight: f64,
+}
+
+impl Default for HarmonyScorer {
+    fn default() -> Self {
+        Self {
+            color_weight: 0.4,
+            season_weight: 0.25,
+            weather_weight: 0.25,
+            occasion_weight: 0.2,
+            rating_weight: 0.2,
+            freshness_weight: 0.15,
+        }
+    }
+}
+
+/// Color family of a garment, from its structured color or its name
+#[must_use]
+pub fn color_family(item: &clothes::Model) -> Option<ColorFamily> {
+    item.primary_color_family
+        .as_deref()
+        .and_then(ColorFamily::parse)
+        .or_else(|| colors::resolve(&item.color).map(|color| color.family))
+}
+
+/// Harmony of a set of color families. Neutrals and browns go with
+/// everything; one accent color, analogous or contrasting pairs score well
+/// and three or more accent colors poorly.
+#[must_use]
+pub fn color_harmony(families: &[ColorFamily]) -> Score {
+    let mut accents: Vec<usize> = families
+        .iter()
+        .filter_map(|family| COLOR_WHEEL.iter().position(|hue| hue == family))
+        .collect();
+    accents.sort_unstable();
+    accents.dedup();
+
+    let name = |index: usize| COLOR_WHEEL[index].as_str();
+    let (value, reason) = match accents.as_slice() {
+        [] => (0.8, "Neutral colors that go with anything".to_string()),
+        [accent] => (1.0, format!("A single {} accent", name(*accent))),
+        [a, b] => {
+            let distance = (b - a).min(COLOR_WHEEL.len() - (b - a));
+            match distance {
+                1 => (0.9, format!("Analogous {} and {}", name(*a), name(*b))),
+                3 => (0.8, format!("Contrasting {} and {}", name(*a), name(*b))),
+                _ => (0.5, format!("{} and {} may clash", name(*a), name(*b))),
+            }
+        }
+        _ => (0.2, format!("Mixes {} accent colors", accents.len())),
+    };
+    Score {
+        value,
+        reasons: vec![reason],
+    }
+}
+
+impl HarmonyScorer {
+    fn season_fit(
+        item: &clothes::Model,
+        season: Season,
+        context: &SuggestionContext,
+    ) -> Option<bool> {
+        let material = item
+            .material
+            .as_deref()
+            .and_then(|material| seasons::material_suits(material, season));
+        let used = context
+            .usage
+            .get(&item.id)
+            .is_some_and(|usage| usage.seasons.contains(&season));
+        match material {
+            Some(false) => Some(false),
+            Some(true) => Some(true),
+            None => used.then_some(true),
+        }
+    }
+
+    fn season_score(
+        outfit: &[&clothes::Model],
+        season: Season,
+        context: &SuggestionContext,
+    ) -> Score {
+        let mut suited = Vec::new();
+        let mut unsuited = Vec::new();
+        for item in outfit {
+            match Self::season_fit(item, season, context) {
+                Some(true) => suited.push(item.name.as_str()),
+                Some(false) => unsuited.push(item.name.as_str()),
+                None => {}
+            }
+        }
+
+        let neutral = outfit.len() - suited.len() - unsuited.len();
+        #[allow(clippy::cast_precision_loss)]
+        let value = 0.5f64.mul_add(neutral as f64, suited.len() as f64) / outfit.len() as f64;
+        let mut reasons = Vec::new();
+        if !suited.is_empty() {
+            reasons.push(format!("Suits {}: {}", season.as_str(), suited.join(", ")));
+        }
+        if !unsuited.is_empty() {
+            reasons.push(format!(
+                "Out of season for {}: {}",
+                season.as_str(),
+                unsuited.join(", ")
+            ));
+        }
+        Score { value, reasons }
+    }
+
+    fn weather_score(
+        outfit: &[&clothes::Model],
+        weather: &Weather,
+        context: &SuggestionContext,
+    ) -> Score {
+        let suited: Vec<&str> = outfit
+            .iter()
+            .filter(|item| context.weather_fit(item) == Some(true))
+            .map(|item| item.name.as_str())
+            .collect();
+
+        #[allow(clippy::cast_precision_loss)]
+        let value = 0.5f64.mul_add(suited.len() as f64 / outfit.len() as f64, 0.5);
+        let mut conditions = format!("{:.0}°C", weather.temperature_c);
+        if weather.is_rainy() {
+            conditions.push_str(" and rain");
+        }
+        let reasons = if suited.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("Made for {conditions}: {}", suited.join(", "))]
+        };
+        Score { value, reasons }
+    }
+
+    fn occasion_score(
+        outfit: &[&clothes::Model],
+        occasion: &str,
+        context: &SuggestionContext,
+    ) -> Score {
+        let matching: Vec<&str> = outfit
+            .iter()
+            .filter(|item| {
+                context
+                    .usage
+                    .get(&item.id)
+                    .is_some_and(|usage| usage.occasions.contains(occasion))
+            })
+            .map(|item| item.name.as_str())
+            .collect();
+
+        #[allow(clippy::cast_precision_loss)]
+        let value = 0.5f64.mul_add(matching.len() as f64 / outfit.len() as f64, 0.5);
+        let reasons = if matching.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!(
+                "Already worn for {occasion}: {}",
+                matching.join(", ")
+            )]
+        };
+        Score { value, reasons }
+    }
+
+    /// Score from the feedback on outfits the garments were worn in, `None`
+    /// when none of them was rated
+    fn rating_score(outfit: &[&clothes::Model], context: &SuggestionContext) -> Option<Score> {
+        let rated: Vec<(&str, f64)> = outfit
+            .iter()
+            .filter_map(|item| Some((item.name.as_str(), *context.ratings.get(&item.id)?)))
+            .collect();
+        if rated.is_empty() {
+            return None;
+        }
+
+        let names = |keep: fn(f64) -> bool| -> Vec<&str> {
+            rated
+                .iter()
+                .filter(|(_, rating)| keep(*rating))
+                .map(|(name, _)| *name)
+                .collect()
+        };
+        let liked = names(|rating| rating >= 4.0);
+        let disliked = names(|rating| rating <= 2.0);
+
+        // Unrated garments count as average
+        let sum: f64 = rated.iter().map(|(_, rating)| (rating - 1.0) / 4.0).sum();
+        #[allow(clippy::cast_precision_loss)]
+        let value = 0.5f64.mul_add((outfit.len() - rated.len()) as f64, sum) / outfit.len() as f64;
+        let mut reasons = Vec::new();
+        if !liked.is_empty() {
+            reasons.push(format!("Rated well when worn: {}", liked.join(", ")));
+        }
+        if !disliked.is_empty() {
+            reasons.push(format!("Rated poorly when worn: {}", disliked.join(", ")));
+        }
+        Some(Score { value, reasons })
+    }
+
+    fn freshness_score(outfit: &[&clothes::Model], context: &SuggestionContext) -> Score {
+        let worn: Vec<String> = outfit
+            .iter()
+            .filter_map(|item| {
+                context
+                    .recently_worn
+                    .get(&item.id)
+                    .map(|worn_on| format!("{} (worn {worn_on})", item.name))
+            })
+            .collect();
+
+        #[allow(clippy::cast_precision_loss)]
+        let value = 1.0 - worn.len() as f64 / outfit.len() as f64;
+        let reason = if worn.is_empty() {
+            "Nothing worn recently".to_string()
+        } else {
+            format!("Recently worn: {}", worn.join(", "))
+        };
+        Score {
+            value,
+            reasons: vec![reason],
+        }
+    }
+}
+
+impl OutfitScorer for HarmonyScorer {
+    fn score(&self, outfit: &[&clothes::Model], context: &SuggestionContext) -> Score {
+        if outfit.is_empty() {
+            return Score::default();
+        }
+
+        let families: Vec<ColorFamily> = outfit
+            .iter()
+            .filter_map(|item| color_family(item))
+            .collect();
+        let mut parts = vec![(self.color_weight, color_harmony(&families))];
+        if let Some(season) = context.season {
+            parts.push((
+                self.season_weight,
+                Self::season_score(outfit, season, context),
+            ));
+        }
+        if let Some(weather) = &context.weather {
+            parts.push((
+                self.weather_weight,
+                Self::weather_score(outfit, weather, context),
+            ));
+        }
+        if let Some(occasion) = &context.occasion {
+            parts.push((
+                self.occasion_weight,
+                Self::occasion_score(outfit, occasion, context),
+            ));
+        }
+        if let Some(score) = Self::rating_score(outfit, context) {
+            parts.push((self.rating_weight, score));
+        }
+        parts.push((
+            self.freshness_weight,
+            Self::freshness_score(outfit, context),
+        ));
+
+        let total_weight: f64 = parts.iter().map(|(weight, _)| weight).sum();
+        let value = if total_weight > 0.0 {
+            parts
+                .iter()
+                .map(|(weight, score)| weight * score.value)
+                .sum::<f64>()
+                / total_weight
+        } else {
+            0.0
+        };
+        Score {
+            value,
+            reasons: parts
+                .into_iter()
+                .flat_map(|(_, score)| score.reasons)
+                .collect(),
+        }
+    }
+}
+
+/// A garment of a suggestion and the slot it fills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestedClothes {
+    pub slot: Slot,
+    #[serde(flatten, with = "crate::models::clothes::priced")]
+    pub clothes: clothes::Model,
+}
+
+/// A new candidate coordinate with its explanation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub score: f64,
+    pub clothes: Vec<SuggestedClothes>,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Suggestions {
+    pub suggestions: Vec<Suggestion>,
+    /// Requested slots the wardrobe has no garment for
+    pub missing_slots: Vec<Slot>,
+    /// Weather the suggestions were made for
+    pub weather: Option<Weather>,
+}
+
+/// Build new outfits filling every slot from `clothes`, best first.
+/// Garments unsuited to the weather and combinations matching an existing
+/// coordinate are skipped, and a garment appears in at most two suggestions.
+/// Slots are filled one at a time, keeping the `BEAM_WIDTH` best partial
+/// outfits in between.
+#[must_use]
+pub fn suggest(
+    clothes: &[clothes::Model],
+    slots: &[Slot],
+    context: &SuggestionContext,
+    scorer: &dyn OutfitScorer,
+    limit: usize,
+) -> Suggestions {
+    let mut by_slot: BTreeMap<Slot, Vec<(&clothes::Model, f64)>> =
+        slots.iter().map(|slot| (*slot, Vec::new())).collect();
+    for item in clothes
+        .iter()
+        .filter(|item| context.weather_fit(item) != Some(false))
+    {
+        if let Some(candidates) =
+            Slot::from_category(&item.category).and_then(|slot| by_slot.get_mut(&slot))
+        {
+            candidates.push((item, scorer.score(&[item], context).value));
+        }
+    }
+
+    let missing_slots: Vec<Slot> = by_slot
+        .iter()
+        .filter(|(_, candidates)| candidates.is_empty())
+        .map(|(slot, _)| *slot)
+        .collect();
+    if !missing_slots.is_empty() {
+        return Suggestions {
+            suggestions: Vec::new(),
+            missing_slots,
+            weather: context.weather,
+        };
+    }
+
+    let mut combinations: Vec<Vec<(Slot, &clothes::Model)>> = vec![Vec::new()];
+    for (slot, mut candidates) in by_slot {
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.truncate(MAX_CANDIDATES_PER_SLOT);
+        combinations = combinations
+            .into_iter()
+            .flat_map(|outfit| {
+                candidates.iter().map(move |(item, _)| {
+                    let mut outfit = outfit.clone();
+                    outfit.push((slot, *item));
+                    outfit
+                })
+            })
+            .collect();
+        if combinations.len() > BEAM_WIDTH {
+            combinations = best_partial_outfits(combinations, context, scorer);
+        }
+    }
+
+    let mut scored: Vec<(Score, Vec<(Slot, &clothes::Model)>)> = combinations
+        .into_iter()
+        .filter(|outfit| {
+            let mut ids: Vec<i32> = outfit.iter().map(|(_, item)| item.id).collect();
+            ids.sort_unstable();
+            !context.existing.contains(&ids)
+        })
+        .map(|outfit| {
+            let items: Vec<&clothes::Model> = outfit.iter().map(|(_, item)| *item).collect();
+            (scorer.score(&items, context), outfit)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.value.total_cmp(&a.0.value));
+
+    let mut repeats: HashMap<i32, usize> = HashMap::new();
+    let mut suggestions = Vec::new();
+    for (score, outfit) in scored {
+        if suggestions.len() >= limit {
+            break;
+        }
+        if outfit
+            .iter()
+            .any(|(_, item)| repeats.get(&item.id).copied().unwrap_or(0) >= MAX_REPEATS)
+        {
+            continue;
+        }
+        for (_, item) in &outfit {
+            *repeats.entry(item.id).or_default() += 1;
+        }
+        suggestions.push(Suggestion {
+            score: (score.value * 100.0).round() / 100.0,
+            clothes: outfit
+                .into_iter()
+                .map(|(slot, item)| SuggestedClothes {
+                    slot,
+                    clothes: item.clone(),
+                })
+                .collect(),
+            reasons: score.reasons,
+        });
+    }
+
+    Suggestions {
+        suggestions,
+        missing_slots,
+        weather: context.weather,
+    }
+}
+
+/// The `BEAM_WIDTH` best of `outfits`, each garment in at most
+/// `BEAM_REPEATS` of them
+fn best_partial_outfits<'a>(
+    outfits: Vec<Vec<(Slot, &'a clothes::Model)>>,
+    context: &SuggestionContext,
+    scorer: &dyn OutfitScorer,
+) -> Vec<Vec<(Slot, &'a clothes::Model)>> {
+    let mut scored: Vec<(f64, Vec<(Slot, &clothes::Model)>)> = outfits
+        .into_iter()
+        .map(|outfit| {
+            let items: Vec<&clothes::Model> = outfit.iter().map(|(_, item)| *item).collect();
+            (scorer.score(&items, context).value, outfit)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut repeats: HashMap<i32, usize> = HashMap::new();
+    let mut kept = Vec::new();
+    for (_, outfit) in scored {
+        if kept.len() >= BEAM_WIDTH {
+            break;
+        }
+        if outfit
+            .iter()
+            .any(|(_, item)| repeats.get(&item.id).copied().unwrap_or(0) >= BEAM_REPEATS)
+        {
+            continue;
+        }
+        for (_, item) in &outfit {
+            *repeats.entry(item.id).or_default() += 1;
+        }
+        kept.push(outfit);
+    }
+    kept
+}
+
+/// Suggest new coordinates for a user from the clothes in stock, for the
+/// weather when known
+pub async fn suggest_for_user(
+    db: &DatabaseConnection,
+    query: &SuggestionQuery,
+    weather: Option<Weather>,
+    scorer: &dyn OutfitScorer,
+) -> ModelResult<Suggestions> {
+    let season = query.season_or_date()?;
+    let needs_outerwear = season.is_some_and(|season| season.needs_outerwear())
+        || weather.is_some_and(|weather| weather.needs_outerwear());
+    let slots = query.slots(needs_outerwear)?;
+    let recent_days = query.recent_days();
+
+    let clothes = clothes::Entity::find_available()
+        .filter(clothes::Column::InStock.eq(true))
+        .all(db)
+        .await?;
+    let coordinates = coordinates::Entity::find_live()
+        .filter(coordinates::Column::UserId.eq(query.user_id))
+        .all(db)
+        .await?;
+    let coordinates = coordinates::Model::with_clothes(db, coordinates).await?;
+    let recent_wears = wear_events::Model::find_by_user(
+        db,
+        query.user_id,
+        &WearRangeQuery {
+            from: Some(Utc::now().date_naive() - Duration::days(recent_days)),
+            to: None,
+        },
+    )
+    .await?;
+
+    let context = SuggestionContext {
+        weather,
+        ..SuggestionContext::new(
+            season,
+            query.occasion.as_deref(),
+            &coordinates,
+            &recent_wears,
+        )
+    };
+    Ok(suggest(&clothes, &slots, &context, scorer, query.limit()))
+}
@@ -0,0 +1,127 @@
+//! The trash: clothes and coordinates that were deleted but can be restored
+//! until they have been there for longer than the retention period and are
+//! purged for good.
+
+use chrono::{DateTime, Duration, Utc};
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    _entities::{clothes, coordinate_tombstones, coordinates},
+    clothes::Priced,
+};
+
+/// An item in the trash
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrashEntry<T> {
+    #[serde(flatten)]
+    pub item: T,
+    /// When the item is purged for good
+    pub purge_after: DateTimeWithTimeZone,
+}
+
+impl<T> TrashEntry<T> {
+    #[must_use]
+    pub fn new(item: T, deleted_at: DateTimeWithTimeZone, retention_days: i64) -> Self {
+        Self {
+            item,
+            purge_after: deleted_at + Duration::days(retention_days),
+        }
+    }
+}
+
+/// The trash of a user, most recently deleted first. Clothes are shared, so
+/// every trashed garment is listed.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Trash {
+    pub clothes: Vec<TrashEntry<Priced>>,
+    pub coordinates: Vec<TrashEntry<coordinates::Model>>,
+}
+
+impl Trash {
+    /// Load the trash of a user
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        retention_days: i64,
+    ) -> ModelResult<Self> {
+        let clothes = clothes::Entity::find_trashed()
+            .order_by_desc(clothes::Column::DeletedAt)
+            .all(db)
+            .await?;
+        let coordinates = coordinates::Entity::find_trashed()
+            .filter(coordinates::Column::UserId.eq(user_id))
+            .order_by_desc(coordinates::Column::DeletedAt)
+            .all(db)
+            .await?;
+        Ok(Self {
+            clothes: clothes
+                .into_iter()
+                .map(|item| {
+                    let deleted_at = item.deleted_at.unwrap_or(item.updated_at);
+                    TrashEntry::new(Priced(item), deleted_at, retention_days)
+                })
+                .collect(),
+            coordinates: coordinates
+                .into_iter()
+                .map(|item| {
+                    let deleted_at = item.deleted_at.unwrap_or(item.updated_at);
+                    TrashEntry::new(item, deleted_at, retention_days)
+                })
+                .collect(),
+        })
+    }
+}
+
+/// How many items a purge deleted for good
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+pub struct PurgeReport {
+    pub clothes: u64,
+    pub coordinates: u64,
+}
+
+/// Delete the items that were put in the trash more than `retention_days`
+/// before `now`
+pub async fn purge(
+    db: &DatabaseConnection,
+    retention_days: i64,
+    now: DateTime<Utc>,
+) -> ModelResult<PurgeReport> {
+    let cutoff = now - Duration::days(retention_days);
+    let txn = db.begin().await?;
+
+    let expired = coordinates::Entity::find_trashed()
+        .filter(coordinates::Column::DeletedAt.lt(cutoff))
+        .all(&txn)
+        .await?;
+    let mut report = PurgeReport {
+        coordinates: u64::try_from(expired.len()).unwrap_or(u64::MAX),
+        ..PurgeReport::default()
+    };
+    for coordinate in expired {
+        coordinate.purge(&txn).await?;
+    }
+    // Trashed clothes are no longer linked to any coordinate. Their kept
+    // links go with them, except the "item removed" placeholders.
+    let expired_pids: Vec<Uuid> = clothes::Entity::find_trashed()
+        .filter(clothes::Column::DeletedAt.lt(cutoff))
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|item| item.pid)
+        .collect();
+    coordinate_tombstones::Entity::delete_many()
+        .filter(coordinate_tombstones::Column::ClothesPid.is_in(expired_pids))
+        .filter(coordinate_tombstones::Column::Placeholder.eq(false))
+        .exec(&txn)
+        .await?;
+    report.clothes = clothes::Entity::delete_many()
+        .filter(clothes::Column::DeletedAt.lt(cutoff))
+        .exec(&txn)
+        .await?
+        .rows_affected;
+
+    txn.commit().await?;
+    Ok(report)
+}
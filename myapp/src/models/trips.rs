@@ -0,0 +1,700 @@
+//! Trips and their packing lists.
+//!
+//! A trip covers a date range at a destination, with planned activities.
+//! The planner picks one of the user's coordinates for every outfit the trip
+//! needs, preferring garments that are packed for earlier days, and stores
+//! the picks as outfit plans of the trip. The packing list is the set of
+//! garments of those plans, each listed once with a packed flag kept per
+//! trip.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Utc};
+use loco_rs::prelude::*;
+use sea_orm::QueryOrder;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::trips::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes, outfit_plans, trip_packing_items},
+    coordinate_rules::FieldError,
+    coordinates::CoordinateWithClothes,
+    outfit_plans::PlannedOutfit,
+};
+use crate::{
+    common::weather::Location,
+    data::{lifecycle::LifecycleState, seasons::Season, vocabularies::Occasion},
+};
+
+/// Longest trip the planner handles
+pub const MAX_TRIP_DAYS: i64 = 60;
+
+/// Score of a coordinate made for the outfit's occasion
+const OCCASION_MATCH: f64 = 4.0;
+/// Score of a coordinate made for another occasion
+const OCCASION_MISMATCH: f64 = -2.0;
+/// Score of a coordinate made for the season of the day, or for others
+const SEASON_MATCH: f64 = 1.0;
+const SEASON_MISMATCH: f64 = -1.0;
+/// Score per garment that is already on the packing list
+const PACKED_GARMENT: f64 = 1.0;
+/// Score per garment that would be added to the packing list
+const NEW_GARMENT: f64 = -1.0;
+/// Score per garment that is in the wash or lent out
+const UNAVAILABLE_GARMENT: f64 = -3.0;
+/// Score per earlier pick of the same coordinate, for some variety
+const REPEATED_COORDINATE: f64 = -0.5;
+/// Weight of the share of required slots a coordinate fills
+const COMPLETENESS_WEIGHT: f64 = 2.0;
+
+/// Something planned during a trip
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TripActivity {
+    /// Day of the activity, every day of the trip when not given
+    #[serde(default)]
+    pub on: Option<Date>,
+    pub occasion: Occasion,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTripParams {
+    pub user_id: i32,
+    pub name: String,
+    pub destination: Option<String>,
+    /// `latitude,longitude` of the destination
+    pub location: Option<String>,
+    pub starts_on: Date,
+    pub ends_on: Date,
+    #[serde(default)]
+    pub activities: Vec<TripActivity>,
+}
+
+impl CreateTripParams {
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        trip_problems(
+            &self.name,
+            self.location.as_deref(),
+            self.starts_on,
+            self.ends_on,
+            &self.activities,
+        )
+    }
+}
+
+/// Change the details of a trip. Fields that are not given are kept;
+/// `activities` replaces every activity.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct UpdateTripParams {
+    pub name: Option<String>,
+    pub destination: Option<String>,
+    pub location: Option<String>,
+    pub starts_on: Option<Date>,
+    pub ends_on: Option<Date>,
+    pub activities: Option<Vec<TripActivity>>,
+}
+
+/// Mark a garment of the packing list as packed or not
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PackParams {
+    pub packed: bool,
+}
+
+fn trip_problems(
+    name: &str,
+    location: Option<&str>,
+    starts_on: Date,
+    ends_on: Date,
+    activities: &[TripActivity],
+) -> Vec<FieldError> {
+    let mut problems = Vec::new();
+    if name.trim().is_empty() {
+        problems.push(FieldError::new("name", "must not be empty"));
+    }
+    if let Some(location) = location.filter(|location| !location.trim().is_empty())
+        && Location::parse(location).is_err()
+    {
+        problems.push(FieldError::new(
+            "location",
+            "must be latitude,longitude in decimal degrees",
+        ));
+    }
+    if ends_on < starts_on {
+        problems.push(FieldError::new("ends_on", "must not be before starts_on"));
+    } else if (ends_on - starts_on).num_days() >= MAX_TRIP_DAYS {
+        problems.push(FieldError::new(
+            "ends_on",
+            format!("a trip can last at most {MAX_TRIP_DAYS} days"),
+        ));
+    }
+    for (index, activity) in activities.iter().enumerate() {
+        if let Some(on) = activity.on
+            && !(starts_on..=ends_on).contains(&on)
+        {
+            problems.push(FieldError::new(
+                format!("activities[{index}].on"),
+                "must be within the trip",
+            ));
+        }
+    }
+    problems
+}
+
+/// An outfit a trip needs: one per day and occasion, or one per day without
+/// activities
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TripOutfit {
+    pub on: Date,
+    pub occasion: Option<Occasion>,
+    pub season: Season,
+    pub note: Option<String>,
+}
+
+/// Outfits needed on every day from `starts_on` to `ends_on`, in day order
+#[must_use]
+pub fn trip_outfits(
+    starts_on: Date,
+    ends_on: Date,
+    activities: &[TripActivity],
+    southern: bool,
+) -> Vec<TripOutfit> {
+    let mut outfits = Vec::new();
+    let mut on = starts_on;
+    while on <= ends_on {
+        let season = Season::from_date(on, southern);
+        let mut day: Vec<TripOutfit> = Vec::new();
+        for activity in activities
+            .iter()
+            .filter(|activity| activity.on.is_none_or(|date| date == on))
+        {
+            match day
+                .iter_mut()
+                .find(|outfit| outfit.occasion == Some(activity.occasion))
+            {
+                Some(outfit) => {
+                    if let Some(note) = &activity.note {
+                        outfit.note = Some(match outfit.note.take() {
+                            Some(earlier) => format!("{earlier}; {note}"),
+                            None => note.clone(),
+                        });
+                    }
+                }
+                None => day.push(TripOutfit {
+                    on,
+                    occasion: Some(activity.occasion),
+                    season,
+                    note: activity.note.clone(),
+                }),
+            }
+        }
+        if day.is_empty() {
+            day.push(TripOutfit {
+                on,
+                occasion: None,
+                season,
+                note: None,
+            });
+        }
+        outfits.extend(day);
+        on += Duration::days(1);
+    }
+    outfits
+}
+
+fn plan_score(
+    outfit: &TripOutfit,
+    candidate: &CoordinateWithClothes,
+    packed: &HashSet<i32>,
+    picks: usize,
+) -> f64 {
+    let mut score = COMPLETENESS_WEIGHT * candidate.completeness.score;
+    if let Some(occasion) = outfit.occasion
+        && let Some(made_for) = candidate.coordinate.occasion()
+    {
+        score += if made_for == occasion {
+            OCCASION_MATCH
+        } else {
+            OCCASION_MISMATCH
+        };
+    }
+    let seasons = candidate.coordinate.seasons();
+    if !seasons.is_empty() {
+        score += if seasons.contains(&outfit.season) {
+            SEASON_MATCH
+        } else {
+            SEASON_MISMATCH
+        };
+    }
+    for item in &candidate.clothes {
+        score += if packed.contains(&item.id) {
+            PACKED_GARMENT
+        } else {
+            NEW_GARMENT
+        };
+        if !item.is_available() {
+            score += UNAVAILABLE_GARMENT;
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let repeated = picks as f64 * REPEATED_COORDINATE;
+    score + repeated
+}
+
+/// Pick a coordinate for every outfit, in order. Each pick favors garments
+/// already packed for earlier outfits, so that few garments cover the whole
+/// trip. Coordinates without clothes are never picked; `None` when there is
+/// no coordinate at all.
+#[must_use]
+pub fn pick_coordinates<'a>(
+    outfits: &[TripOutfit],
+    coordinates: &'a [CoordinateWithClothes],
+) -> Vec<Option<&'a CoordinateWithClothes>> {
+    let mut packed: HashSet<i32> = HashSet::new();
+    let mut picks: HashMap<i32, usize> = HashMap::new();
+    outfits
+        .iter()
+        .map(|outfit| {
+            let mut best: Option<(f64, &CoordinateWithClothes)> = None;
+            for candidate in coordinates.iter().filter(|c| !c.clothes.is_empty()) {
+                let times = picks.get(&candidate.coordinate.id).copied().unwrap_or(0);
+                let score = plan_score(outfit, candidate, &packed, times);
+                if best.is_none_or(|(best_score, _)| score > best_score) {
+                    best = Some((score, candidate));
+                }
+            }
+            let (_, chosen) = best?;
+            packed.extend(chosen.clothes.iter().map(|item| item.id));
+            *picks.entry(chosen.coordinate.id).or_default() += 1;
+            Some(chosen)
+        })
+        .collect()
+}
+
+/// A garment to take on a trip
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PackingItem {
+    #[serde(flatten, with = "crate::models::clothes::priced")]
+    pub clothes: clothes::Model,
+    /// Days it is planned for, in order
+    pub days: Vec<Date>,
+    pub packed: bool,
+    /// Lifecycle state of a garment that is not at hand
+    pub flag: Option<LifecycleState>,
+}
+
+/// Garments of the planned outfits, each listed once in order of first use
+#[must_use]
+pub fn packing_list(plans: &[PlannedOutfit], packed: &HashSet<i32>) -> Vec<PackingItem> {
+    let mut items: Vec<PackingItem> = Vec::new();
+    for planned in plans {
+        for item in &planned.coordinate.clothes {
+            match items.iter_mut().find(|listed| listed.clothes.id == item.id) {
+                Some(listed) => {
+                    if !listed.days.contains(&planned.plan.planned_on) {
+                        listed.days.push(planned.plan.planned_on);
+                    }
+                }
+                None => items.push(PackingItem {
+                    clothes: item.clone(),
+                    days: vec![planned.plan.planned_on],
+                    packed: packed.contains(&item.id),
+                    flag: (!item.is_available()).then(|| item.lifecycle()),
+                }),
+            }
+        }
+    }
+    for item in &mut items {
+        item.days.sort();
+    }
+    items
+}
+
+/// Format of an exported packing checklist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChecklistFormat {
+    #[default]
+    Markdown,
+    Csv,
+}
+
+impl ChecklistFormat {
+    #[must_use]
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    #[must_use]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ChecklistQuery {
+    pub format: Option<ChecklistFormat>,
+}
+
+/// A trip with its planned outfits and packing list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TripDetails {
+    #[serde(flatten)]
+    pub trip: Model,
+    pub outfits: Vec<PlannedOutfit>,
+    pub packing_list: Vec<PackingItem>,
+}
+
+impl TripDetails {
+    /// The packing list as a checklist
+    ///
+    /// # Errors
+    ///
+    /// When the CSV cannot be written
+    pub fn checklist(&self, format: ChecklistFormat) -> ModelResult<Vec<u8>> {
+        match format {
+            ChecklistFormat::Markdown => Ok(self.checklist_markdown().into_bytes()),
+            ChecklistFormat::Csv => self.checklist_csv(),
+        }
+    }
+
+    fn checklist_markdown(&self) -> String {
+        let mut lines = vec![format!("# {}", self.trip.name)];
+        let dates = format!("{} to {}", self.trip.starts_on, self.trip.ends_on);
+        lines.push(match &self.trip.destination {
+            Some(destination) => format!("{destination}, {dates}"),
+            None => dates,
+        });
+        lines.push(String::new());
+        for item in &self.packing_list {
+            let days = match item.days.len() {
+                1 => "1 day".to_string(),
+                count => format!("{count} days"),
+            };
+            let mut line = format!(
+                "- [{}] {} ({days})",
+                if item.packed { "x" } else { " " },
+                item.clothes.name
+            );
+            if let Some(reason) = item.clothes.unavailable_reason() {
+                line.push_str(&format!(" - {reason}"));
+            }
+            lines.push(line);
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn checklist_csv(&self) -> ModelResult<Vec<u8>> {
+        let csv_error = |e: csv::Error| ModelError::Any(e.into());
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record(["clothes_pid", "name", "category", "days", "packed", "flag"])
+            .map_err(csv_error)?;
+        for item in &self.packing_list {
+            writer
+                .write_record([
+                    item.clothes.pid.to_string(),
+                    item.clothes.name.clone(),
+                    item.clothes.category.clone(),
+                    item.days
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    item.packed.to_string(),
+                    item.clothes.unavailable_reason().unwrap_or_default(),
+                ])
+                .map_err(csv_error)?;
+        }
+        writer
+            .into_inner()
+            .map_err(|e| ModelError::Any(e.into_error().into()))
+    }
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else if self.updated_at.is_unchanged() {
+            let mut this = self;
+            this.updated_at = sea_orm::ActiveValue::Set(Utc::now().into());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+fn activity_list(activities: &[TripActivity]) -> ModelResult<serde_json::Value> {
+    serde_json::to_value(activities).map_err(|e| ModelError::Any(e.into()))
+}
+
+fn blank_to_none(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl Model {
+    /// The planned activities
+    ///
+    /// # Errors
+    ///
+    /// When the stored JSON does not match [`TripActivity`]
+    pub fn activities(&self) -> ModelResult<Vec<TripActivity>> {
+        serde_json::from_value(self.activities.clone())
+            .map_err(|e| ModelError::msg(&format!("Invalid trip activities: {e}")))
+    }
+
+    /// Outfits the trip needs, with the seasons of the destination
+    ///
+    /// # Errors
+    ///
+    /// When the stored activities are invalid
+    pub fn outfits(&self) -> ModelResult<Vec<TripOutfit>> {
+        let southern = self
+            .location
+            .as_deref()
+            .and_then(|location| Location::parse(location).ok())
+            .is_some_and(|location| location.latitude < 0.0);
+        Ok(trip_outfits(
+            self.starts_on,
+            self.ends_on,
+            &self.activities()?,
+            southern,
+        ))
+    }
+
+    /// Problems with the trip once `params` are applied
+    ///
+    /// # Errors
+    ///
+    /// When the stored activities are invalid
+    pub fn update_problems(&self, params: &UpdateTripParams) -> ModelResult<Vec<FieldError>> {
+        let activities = match &params.activities {
+            Some(activities) => activities.clone(),
+            None => self.activities()?,
+        };
+        let location = params.location.as_deref().or(self.location.as_deref());
+        Ok(trip_problems(
+            params.name.as_deref().unwrap_or(&self.name),
+            location,
+            params.starts_on.unwrap_or(self.starts_on),
+            params.ends_on.unwrap_or(self.ends_on),
+            &activities,
+        ))
+    }
+
+    /// Find trip by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let trip = trips::Entity::find()
+            .filter(trips::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        trip.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Trips of a user, latest first
+    pub async fn find_by_user(db: &DatabaseConnection, user_id: i32) -> ModelResult<Vec<Self>> {
+        Ok(trips::Entity::find()
+            .filter(trips::Column::UserId.eq(user_id))
+            .order_by_desc(trips::Column::StartsOn)
+            .order_by_desc(trips::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Create a trip. Outfits are picked separately with [`Self::plan`].
+    pub async fn create(db: &DatabaseConnection, params: &CreateTripParams) -> ModelResult<Self> {
+        let trip = trips::ActiveModel {
+            user_id: ActiveValue::set(params.user_id),
+            name: ActiveValue::set(params.name.trim().to_string()),
+            destination: ActiveValue::set(params.destination.as_deref().and_then(blank_to_none)),
+            location: ActiveValue::set(params.location.as_deref().and_then(blank_to_none)),
+            starts_on: ActiveValue::set(params.starts_on),
+            ends_on: ActiveValue::set(params.ends_on),
+            activities: ActiveValue::set(activity_list(&params.activities)?),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(trip)
+    }
+
+    /// Change the details of a trip. Planned outfits are kept until the trip
+    /// is planned again.
+    pub async fn update_by_pid(
+        db: &DatabaseConnection,
+        pid: &str,
+        params: &UpdateTripParams,
+    ) -> ModelResult<Self> {
+        let trip = Self::find_by_pid(db, pid).await?;
+        let mut active_model = trip.into_active_model();
+
+        if let Some(name) = &params.name {
+            active_model.name = ActiveValue::set(name.trim().to_string());
+        }
+        if let Some(destination) = &params.destination {
+            active_model.destination = ActiveValue::set(blank_to_none(destination));
+        }
+        if let Some(location) = &params.location {
+            active_model.location = ActiveValue::set(blank_to_none(location));
+        }
+        if let Some(starts_on) = params.starts_on {
+            active_model.starts_on = ActiveValue::set(starts_on);
+        }
+        if let Some(ends_on) = params.ends_on {
+            active_model.ends_on = ActiveValue::set(ends_on);
+        }
+        if let Some(activities) = &params.activities {
+            active_model.activities = ActiveValue::set(activity_list(activities)?);
+        }
+
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Delete a trip with its planned outfits
+    pub async fn delete_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<()> {
+        let trip = Self::find_by_pid(db, pid).await?;
+        trip.delete(db).await?;
+        Ok(())
+    }
+
+    /// The planned outfits and packing list
+    pub async fn details(self, db: &DatabaseConnection) -> ModelResult<TripDetails> {
+        let plans = outfit_plans::Entity::find()
+            .filter(outfit_plans::Column::TripId.eq(self.id))
+            .order_by_asc(outfit_plans::Column::PlannedOn)
+            .order_by_asc(outfit_plans::Column::Id)
+            .all(db)
+            .await?;
+        let outfits = outfit_plans::Model::with_coordinates(db, plans).await?;
+        let packed: HashSet<i32> = trip_packing_items::Entity::find()
+            .filter(trip_packing_items::Column::TripId.eq(self.id))
+            .filter(trip_packing_items::Column::Packed.eq(true))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|item| item.clothes_id)
+            .collect();
+        let packing_list = packing_list(&outfits, &packed);
+        Ok(TripDetails {
+            trip: self,
+            outfits,
+            packing_list,
+        })
+    }
+
+    /// Pick a coordinate for every outfit of the trip, replacing the outfits
+    /// planned before. Outfits already marked as done are kept, and the
+    /// packed state of garments is remembered.
+    pub async fn plan(self, db: &DatabaseConnection) -> ModelResult<TripDetails> {
+        let outfits = self.outfits()?;
+        let coordinates =
+            super::coordinates::Model::find_by_user_with_clothes(db, self.user_id).await?;
+
+        let done = outfit_plans::Entity::find()
+            .filter(outfit_plans::Column::TripId.eq(self.id))
+            .filter(outfit_plans::Column::DoneAt.is_not_null())
+            .all(db)
+            .await?;
+        let open: Vec<TripOutfit> = outfits
+            .into_iter()
+            .filter(|outfit| {
+                let occasion = outfit.occasion.map(|occasion| occasion.as_str());
+                !done.iter().any(|plan| {
+                    plan.planned_on == outfit.on && plan.occasion.as_deref() == occasion
+                })
+            })
+            .collect();
+        let picks = pick_coordinates(&open, &coordinates);
+
+        let txn = db.begin().await?;
+        outfit_plans::Entity::delete_many()
+            .filter(outfit_plans::Column::TripId.eq(self.id))
+            .filter(outfit_plans::Column::DoneAt.is_null())
+            .exec(&txn)
+            .await?;
+        for (outfit, pick) in open.iter().zip(picks) {
+            let Some(pick) = pick else {
+                continue;
+            };
+            outfit_plans::ActiveModel {
+                user_id: ActiveValue::set(self.user_id),
+                coordinate_id: ActiveValue::set(pick.coordinate.id),
+                trip_id: ActiveValue::set(Some(self.id)),
+                planned_on: ActiveValue::set(outfit.on),
+                occasion: ActiveValue::set(
+                    outfit
+                        .occasion
+                        .map(|occasion| occasion.as_str().to_string()),
+                ),
+                note: ActiveValue::set(outfit.note.clone()),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+        }
+        txn.commit().await?;
+
+        self.details(db).await
+    }
+
+    /// Mark a garment of the packing list as packed or not
+    pub async fn set_packed(
+        &self,
+        db: &DatabaseConnection,
+        clothes_pid: &str,
+        packed: bool,
+    ) -> ModelResult<trip_packing_items::Model> {
+        let item = super::clothes::Model::find_by_pid(db, clothes_pid).await?;
+        let listed = self
+            .clone()
+            .details(db)
+            .await?
+            .packing_list
+            .iter()
+            .any(|listed| listed.clothes.id == item.id);
+        if !listed {
+            return Err(ModelError::EntityNotFound);
+        }
+
+        let existing = trip_packing_items::Entity::find()
+            .filter(trip_packing_items::Column::TripId.eq(self.id))
+            .filter(trip_packing_items::Column::ClothesId.eq(item.id))
+            .one(db)
+            .await?;
+        Ok(match existing {
+            Some(existing) => {
+                let mut active_model = existing.into_active_model();
+                active_model.packed = ActiveValue::set(packed);
+                active_model.update(db).await?
+            }
+            None => {
+                trip_packing_items::ActiveModel {
+                    trip_id: ActiveValue::set(self.id),
+                    clothes_id: ActiveValue::set(item.id),
+                    packed: ActiveValue::set(packed),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?
+            }
+        })
+    }
+}
@@ -6,6 +6,7 @@ use serde_json::Map;
 use uuid::Uuid;
 
 pub use super::_entities::users::{self, ActiveModel, Entity, Model};
+use crate::data::money::Currency;
 
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -178,6 +179,12 @@ impl Model {
         result
     }
 
+    /// Currency totals are reported in
+    #[must_use]
+    pub fn home_currency(&self) -> Currency {
+        Currency::parse(&self.home_currency).unwrap_or_default()
+    }
+
     /// Asynchronously creates a user with a password and saves it to the
     /// database.
     ///
@@ -271,4 +278,18 @@ impl ActiveModel {
         Ok(self.update(db).await?)
     }
 
+    /// Sets the currency totals are reported in for the user
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_home_currency(
+        mut self,
+        db: &DatabaseConnection,
+        currency: Currency,
+    ) -> ModelResult<Model> {
+        self.home_currency = ActiveValue::set(currency.code().to_string());
+        Ok(self.update(db).await?)
+    }
+
 }
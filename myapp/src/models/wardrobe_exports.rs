@@ -0,0 +1,710 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use loco_rs::{prelude::*, storage::Storage};
+use sea_orm::{PaginatorTrait, QueryOrder, Select};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+pub use super::_entities::wardrobe_exports::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes, clothes_coordinates, coordinates},
+    clothes_coordinates::ClothesLayout,
+};
+use crate::{
+    common::images::ImageFetcher,
+    data::seasons::{self, Season},
+};
+
+/// Version of the wardrobe document, bumped on incompatible changes
+pub const FORMAT_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// The clothes sheet, in the column layout accepted by the CSV import
+    Csv,
+    /// The full wardrobe document, accepted as-is by the JSON import
+    Json,
+    /// The JSON document, CSV sheets, a manifest and an `images/` folder
+    Zip,
+}
+
+impl ExportFormat {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Zip => "zip",
+        }
+    }
+
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "zip" => Some(Self::Zip),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Json => "application/json",
+            Self::Zip => "application/zip",
+        }
+    }
+
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("wardrobe.{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ExportStatus {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportWardrobeParams {
+    pub format: ExportFormat,
+}
+
+/// A clothes item of the wardrobe document. Field names match
+/// `CreateClothesParams` so the document can be imported again.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportClothes {
+    pub pid: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub brand: String,
+    pub category: String,
+    pub size: String,
+    pub color: String,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
+    pub material: Option<String>,
+    pub price: Decimal,
+    pub currency: String,
+    pub in_stock: bool,
+    pub stock_quantity: i32,
+    pub image_url: Option<String>,
+    pub purchase_date: Option<Date>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
+}
+
+impl From<&clothes::Model> for ExportClothes {
+    fn from(item: &clothes::Model) -> Self {
+        Self {
+            pid: item.pid,
+            name: item.name.clone(),
+            description: item.description.clone(),
+            brand: item.brand.clone(),
+            category: item.category.clone(),
+            size: item.size.clone(),
+            color: item.color.clone(),
+            primary_color: item.primary_color_hex.clone(),
+            secondary_color: item.secondary_color_hex.clone(),
+            material: item.material.clone(),
+            price: item.price,
+            currency: item.currency.clone(),
+            in_stock: item.in_stock,
+            stock_quantity: item.stock_quantity,
+            image_url: item.image_url.clone(),
+            purchase_date: item.purchase_date,
+            store: item.store.clone(),
+            order_number: item.order_number.clone(),
+        }
+    }
+}
+
+/// A clothes item placed in a coordinate, referenced by its exported pid
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportCoordinateClothes {
+    pub clothes_pid: Uuid,
+    #[serde(flatten)]
+    pub layout: ClothesLayout,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportCoordinate {
+    pub pid: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub occasion: Option<String>,
+    /// Documents before version 3 hold a single free-form `season`
+    #[serde(
+        default,
+        alias = "season",
+        deserialize_with = "seasons::deserialize_list"
+    )]
+    pub seasons: Vec<Season>,
+    pub style: Option<String>,
+    #[serde(default)]
+    pub is_favorite: bool,
+    pub image_url: Option<String>,
+    #[serde(default)]
+    pub clothes: Vec<ExportCoordinateClothes>,
+}
+
+/// A user's whole wardrobe: every clothes item and the user's coordinates
+/// with the layout and notes of each item
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WardrobeDocument {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub clothes: Vec<ExportClothes>,
+    pub coordinates: Vec<ExportCoordinate>,
+}
+
+/// An image referenced by the wardrobe and its place in the ZIP archive
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExportImage {
+    /// `clothes` or `coordinates`
+    pub owner: String,
+    pub pid: Uuid,
+    pub source: String,
+    /// Path inside the archive, `None` when the image could not be fetched
+    pub file: Option<String>,
+    pub error: Option<String>,
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    version: u32,
+    exported_at: DateTime<Utc>,
+    clothes: usize,
+    coordinates: usize,
+    files: &'a [&'a str],
+    images: &'a [ExportImage],
+}
+
+const CLOTHES_COLUMNS: [&str; 19] = [
+    "pid",
+    "name",
+    "description",
+    "brand",
+    "category",
+    "size",
+    "color",
+    "primary_color",
+    "secondary_color",
+    "material",
+    "price",
+    "currency",
+    "in_stock",
+    "stock_quantity",
+    "image_url",
+    "purchase_date",
+    "store",
+    "order_number",
+    "coordinates",
+];
+
+const ARCHIVE_FILES: [&str; 4] = [
+    "wardrobe.json",
+    "clothes.csv",
+    "coordinates.csv",
+    "coordinate_clothes.csv",
+];
+
+/// Empty cell for a missing layout value
+fn number(value: Option<impl ToString>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn csv_error(err: impl std::fmt::Display) -> ModelError {
+    ModelError::msg(&format!("Could not write CSV: {err}"))
+}
+
+fn zip_error(err: impl std::fmt::Display) -> ModelError {
+    ModelError::msg(&format!("Could not write archive: {err}"))
+}
+
+impl WardrobeDocument {
+    /// Build the document from loaded rows. `links` are the
+    /// `clothes_coordinates` rows of the given coordinates.
+    #[must_use]
+    pub fn new(
+        clothes: &[clothes::Model],
+        coordinates: &[coordinates::Model],
+        links: &[clothes_coordinates::Model],
+    ) -> Self {
+        let clothes_pids: HashMap<i32, Uuid> =
+            clothes.iter().map(|item| (item.id, item.pid)).collect();
+        let coordinates = coordinates
+            .iter()
+            .map(|coordinate| ExportCoordinate {
+                pid: coordinate.pid,
+                name: coordinate.name.clone(),
+                description: coordinate.description.clone(),
+                occasion: coordinate.occasion.clone(),
+                seasons: coordinate.seasons(),
+                style: coordinate.style.clone(),
+                is_favorite: coordinate.is_favorite,
+                image_url: coordinate.image_url.clone(),
+                clothes: links
+                    .iter()
+                    .filter(|link| link.coordinate_id == coordinate.id)
+                    .filter_map(|link| {
+                        Some(ExportCoordinateClothes {
+                            clothes_pid: *clothes_pids.get(&link.clothes_id)?,
+                            layout: link.layout(),
+                            notes: link.notes.clone(),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            version: FORMAT_VERSION,
+            exported_at: Utc::now(),
+            clothes: clothes.iter().map(ExportClothes::from).collect(),
+            coordinates,
+        }
+    }
+
+    /// Live coordinates of a user with their `clothes_coordinates` rows
+    async fn coordinates_of(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ModelResult<(Vec<coordinates::Model>, Vec<clothes_coordinates::Model>)> {
+        let coordinates = coordinates::Entity::find_live()
+            .filter(coordinates::Column::UserId.eq(user_id))
+            .order_by_asc(coordinates::Column::Id)
+            .all(db)
+            .await?;
+        let links = clothes_coordinates::Entity::find()
+            .filter(
+                clothes_coordinates::Column::CoordinateId
+                    .is_in(coordinates.iter().map(|coordinate| coordinate.id)),
+            )
+            .order_by_asc(clothes_coordinates::Column::Id)
+            .all(db)
+            .await?;
+        Ok((coordinates, links))
+    }
+
+    /// Live clothes that are part of one of `links`
+    fn linked_clothes(links: &[clothes_coordinates::Model]) -> Select<clothes::Entity> {
+        clothes::Entity::find_live()
+            .filter(clothes::Column::Id.is_in(links.iter().map(|link| link.clothes_id)))
+    }
+
+    /// Load the wardrobe of a user: their coordinates and the clothes in
+    /// them. Clothes have no owner, so garments in none of the user's
+    /// coordinates are not part of it.
+    pub async fn collect(db: &DatabaseConnection, user_id: i32) -> ModelResult<Self> {
+        let (coordinates, links) = Self::coordinates_of(db, user_id).await?;
+        let clothes = Self::linked_clothes(&links)
+            .order_by_asc(clothes::Column::Id)
+            .all(db)
+            .await?;
+        Ok(Self::new(&clothes, &coordinates, &links))
+    }
+
+    /// Number of clothes and coordinates an export of the user would contain
+    pub async fn count(db: &DatabaseConnection, user_id: i32) -> ModelResult<u64> {
+        let (coordinates, links) = Self::coordinates_of(db, user_id).await?;
+        let clothes = Self::linked_clothes(&links).count(db).await?;
+        Ok(clothes + u64::try_from(coordinates.len()).unwrap_or(u64::MAX))
+    }
+
+    /// # Errors
+    ///
+    /// When the document cannot be serialized
+    pub fn to_json(&self) -> ModelResult<Vec<u8>> {
+        serde_json::to_vec_pretty(self).map_err(|e| ModelError::Any(e.into()))
+    }
+
+    /// The clothes sheet. The trailing `coordinates` column lists the names
+    /// of the coordinates an item is part of and is ignored by the import.
+    ///
+    /// # Errors
+    ///
+    /// When the CSV cannot be written
+    pub fn clothes_csv(&self) -> ModelResult<Vec<u8>> {
+        let mut names: HashMap<Uuid, Vec<&str>> = HashMap::new();
+        for coordinate in &self.coordinates {
+            for item in &coordinate.clothes {
+                names
+                    .entry(item.clothes_pid)
+                    .or_default()
+                    .push(&coordinate.name);
+            }
+        }
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(CLOTHES_COLUMNS).map_err(csv_error)?;
+        for item in &self.clothes {
+            let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+            writer
+                .write_record([
+                    item.pid.to_string(),
+                    item.name.clone(),
+                    optional(&item.description),
+                    item.brand.clone(),
+                    item.category.clone(),
+                    item.size.clone(),
+                    item.color.clone(),
+                    optional(&item.primary_color),
+                    optional(&item.secondary_color),
+                    optional(&item.material),
+                    item.price.to_string(),
+                    item.currency.clone(),
+                    item.in_stock.to_string(),
+                    item.stock_quantity.to_string(),
+                    optional(&item.image_url),
+                    item.purchase_date
+                        .map(|date| date.to_string())
+                        .unwrap_or_default(),
+                    optional(&item.store),
+                    optional(&item.order_number),
+                    names
+                        .get(&item.pid)
+                        .map(|n| n.join("; "))
+                        .unwrap_or_default(),
+                ])
+                .map_err(csv_error)?;
+        }
+        writer.into_inner().map_err(csv_error)
+    }
+
+    /// One row per coordinate
+    ///
+    /// # Errors
+    ///
+    /// When the CSV cannot be written
+    pub fn coordinates_csv(&self) -> ModelResult<Vec<u8>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record([
+                "pid",
+                "name",
+                "description",
+                "occasion",
+                "seasons",
+                "style",
+                "is_favorite",
+                "image_url",
+            ])
+            .map_err(csv_error)?;
+        for coordinate in &self.coordinates {
+            let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+            writer
+                .write_record([
+                    coordinate.pid.to_string(),
+                    coordinate.name.clone(),
+                    optional(&coordinate.description),
+                    optional(&coordinate.occasion),
+                    coordinate
+                        .seasons
+                        .iter()
+                        .map(Season::as_str)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    optional(&coordinate.style),
+                    coordinate.is_favorite.to_string(),
+                    optional(&coordinate.image_url),
+                ])
+                .map_err(csv_error)?;
+        }
+        writer.into_inner().map_err(csv_error)
+    }
+
+    /// One row per clothes item placed in a coordinate
+    ///
+    /// # Errors
+    ///
+    /// When the CSV cannot be written
+    pub fn coordinate_clothes_csv(&self) -> ModelResult<Vec<u8>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record([
+                "coordinate_pid",
+                "clothes_pid",
+                "slot",
+                "x",
+                "y",
+                "scale",
+                "rotation",
+                "z_index",
+                "notes",
+            ])
+            .map_err(csv_error)?;
+        for coordinate in &self.coordinates {
+            for item in &coordinate.clothes {
+                writer
+                    .write_record([
+                        coordinate.pid.to_string(),
+                        item.clothes_pid.to_string(),
+                        item.layout
+                            .slot
+                            .map(|slot| slot.as_str().to_string())
+                            .unwrap_or_default(),
+                        number(item.layout.x),
+                        number(item.layout.y),
+                        number(item.layout.scale),
+                        number(item.layout.rotation),
+                        number(item.layout.z_index),
+                        item.notes.clone().unwrap_or_default(),
+                    ])
+                    .map_err(csv_error)?;
+            }
+        }
+        writer.into_inner().map_err(csv_error)
+    }
+
+    /// Images referenced by clothes and coordinates, not yet fetched
+    #[must_use]
+    pub fn images(&self) -> Vec<ExportImage> {
+        let clothes = self
+            .clothes
+            .iter()
+            .filter_map(|item| Some(("clothes", item.pid, item.image_url.as_ref()?)));
+        let coordinates = self
+            .coordinates
+            .iter()
+            .filter_map(|item| Some(("coordinates", item.pid, item.image_url.as_ref()?)));
+        clothes
+            .chain(coordinates)
+            .filter(|(_, _, source)| !source.trim().is_empty())
+            .map(|(owner, pid, source)| ExportImage {
+                owner: owner.to_string(),
+                pid,
+                source: source.clone(),
+                file: None,
+                error: None,
+                data: Vec::new(),
+            })
+            .collect()
+    }
+
+    /// Write the ZIP archive: the JSON document, the CSV sheets, a manifest
+    /// and every fetched image under `images/`
+    ///
+    /// # Errors
+    ///
+    /// When the archive cannot be written
+    pub fn to_zip(&self, images: &[ExportImage]) -> ModelResult<Vec<u8>> {
+        let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        let manifest = Manifest {
+            version: self.version,
+            exported_at: self.exported_at,
+            clothes: self.clothes.len(),
+            coordinates: self.coordinates.len(),
+            files: &ARCHIVE_FILES,
+            images,
+        };
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let documents = [
+            (
+                "manifest.json",
+                serde_json::to_vec_pretty(&manifest).map_err(|e| ModelError::Any(e.into()))?,
+            ),
+            ("wardrobe.json", self.to_json()?),
+            ("clothes.csv", self.clothes_csv()?),
+            ("coordinates.csv", self.coordinates_csv()?),
+            ("coordinate_clothes.csv", self.coordinate_clothes_csv()?),
+        ];
+        for (name, data) in documents {
+            zip.start_file(name, deflated).map_err(zip_error)?;
+            zip.write_all(&data).map_err(zip_error)?;
+        }
+        // Images are already compressed
+        for image in images {
+            if let Some(file) = &image.file {
+                zip.start_file(file.as_str(), stored).map_err(zip_error)?;
+                zip.write_all(&image.data).map_err(zip_error)?;
+            }
+        }
+        Ok(zip.finish().map_err(zip_error)?.into_inner())
+    }
+
+    /// Render the document in the requested format, fetching images for ZIP
+    /// archives
+    ///
+    /// # Errors
+    ///
+    /// When the file cannot be written
+    pub async fn render(
+        &self,
+        format: ExportFormat,
+        storage: &Storage,
+        fetcher: &ImageFetcher,
+    ) -> ModelResult<Vec<u8>> {
+        match format {
+            ExportFormat::Csv => self.clothes_csv(),
+            ExportFormat::Json => self.to_json(),
+            ExportFormat::Zip => {
+                let images = fetch_images(self.images(), storage, fetcher).await;
+                self.to_zip(&images)
+            }
+        }
+    }
+}
+
+/// Fetch image data, from allowed hosts for `http(s)` URLs and from the app
+/// storage otherwise. Failures are recorded on the image instead of failing the
+/// export.
+pub async fn fetch_images(
+    images: Vec<ExportImage>,
+    storage: &Storage,
+    fetcher: &ImageFetcher,
+) -> Vec<ExportImage> {
+    let mut fetched = Vec::with_capacity(images.len());
+    for mut image in images {
+        let source = image.source.trim();
+        match fetcher.fetch(source, storage).await {
+            Ok(data) => {
+                image.file = Some(format!(
+                    "images/{}/{}.{}",
+                    image.owner,
+                    image.pid,
+                    image_extension(source)
+                ));
+                image.data = data;
+            }
+            Err(err) => image.error = Some(err),
+        }
+        fetched.push(image);
+    }
+    fetched
+}
+
+/// File extension of an image URL or path, ignoring any query string
+fn image_extension(source: &str) -> String {
+    let path = source.split(['?', '#']).next().unwrap_or_default();
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map_or_else(|| "bin".to_string(), str::to_lowercase)
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Create a queued export for a background worker
+    pub async fn create_queued(
+        db: &DatabaseConnection,
+        user_id: i32,
+        format: ExportFormat,
+    ) -> ModelResult<Self> {
+        let export = wardrobe_exports::ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            format: ActiveValue::set(format.as_str().to_string()),
+            status: ActiveValue::set(ExportStatus::Queued.as_str().to_string()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok(export)
+    }
+
+    /// Find export by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let export = wardrobe_exports::Entity::find()
+            .filter(wardrobe_exports::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        export.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    #[must_use]
+    pub fn export_format(&self) -> Option<ExportFormat> {
+        ExportFormat::parse(&self.format)
+    }
+
+    /// Storage path of the export file
+    #[must_use]
+    pub fn storage_path(&self) -> String {
+        format!("exports/{}.{}", self.pid, self.format)
+    }
+
+    pub async fn mark_running(self, db: &DatabaseConnection) -> ModelResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = ActiveValue::set(ExportStatus::Running.as_str().to_string());
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Mark the export as completed and issue a download token valid for
+    /// `ttl`
+    pub async fn complete(
+        self,
+        db: &DatabaseConnection,
+        file_path: String,
+        ttl: Duration,
+    ) -> ModelResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = ActiveValue::set(ExportStatus::Completed.as_str().to_string());
+        active_model.file_path = ActiveValue::set(Some(file_path));
+        active_model.download_token = ActiveValue::set(Some(Uuid::new_v4().to_string()));
+        active_model.expires_at = ActiveValue::set(Some((Utc::now() + ttl).into()));
+        Ok(active_model.update(db).await?)
+    }
+
+    pub async fn fail(self, db: &DatabaseConnection, error: &str) -> ModelResult<Self> {
+        let mut active_model = self.into_active_model();
+        active_model.status = ActiveValue::set(ExportStatus::Failed.as_str().to_string());
+        active_model.error = ActiveValue::set(Some(error.to_string()));
+        Ok(active_model.update(db).await?)
+    }
+
+    /// Whether `token` grants access to the export file at `now`
+    #[must_use]
+    pub fn is_downloadable(&self, token: &str, now: DateTime<Utc>) -> bool {
+        self.status == ExportStatus::Completed.as_str()
+            && self.file_path.is_some()
+            && self.download_token.as_deref() == Some(token)
+            && self.expires_at.is_some_and(|expires_at| now < expires_at)
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use chrono::{Months, Utc};
+use loco_rs::prelude::*;
+use sea_orm::{FromQueryResult, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub use super::_entities::wear_events::{self, ActiveModel, Entity, Model};
+use super::{
+    _entities::{clothes, clothes_coordinates, coordinates},
+    coordinate_rules::FieldError,
+};
+use crate::data::money::Money;
+
+/// Log a wear of a single garment (`clothes_id`) or of every garment of a
+/// coordinate (`coordinate_pid`)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LogWearParams {
+    pub user_id: i32,
+    pub clothes_id: Option<i32>,
+    pub coordinate_pid: Option<String>,
+    /// Defaults to today
+    pub worn_on: Option<Date>,
+}
+
+impl LogWearParams {
+    /// Problems with the target of the wear: exactly one of `clothes_id`
+    /// and `coordinate_pid` must be given
+    #[must_use]
+    pub fn problems(&self) -> Vec<FieldError> {
+        if self.clothes_id.is_some() == self.coordinate_pid.is_some() {
+            vec![FieldError::new(
+                "clothes_id",
+                "specify either clothes_id or coordinate_pid",
+            )]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct WearRangeQuery {
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotWornQuery {
+    pub months: Option<u32>,
+}
+
+impl NotWornQuery {
+    pub const DEFAULT_MONTHS: u32 = 6;
+}
+
+/// A clothes item with its wear statistics for one user
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClothesWithWear {
+    #[serde(flatten, with = "crate::models::clothes::priced")]
+    pub clothes: clothes::Model,
+    pub wear_count: i64,
+    pub last_worn_on: Option<Date>,
+    /// Price divided by the number of wears, `None` until first worn
+    pub cost_per_wear: Option<Money>,
+}
+
+impl ClothesWithWear {
+    #[must_use]
+    pub fn new(clothes: clothes::Model, wear_count: i64, last_worn_on: Option<Date>) -> Self {
+        let cost_per_wear = cost_per_wear(clothes.money(), wear_count);
+        Self {
+            clothes,
+            wear_count,
+            last_worn_on,
+            cost_per_wear,
+        }
+    }
+
+    /// Whether the item has not been worn since `cutoff`. Items that were
+    /// never worn count once they have been owned since before `cutoff`.
+    #[must_use]
+    pub fn not_worn_since(&self, cutoff: Date) -> bool {
+        match self.last_worn_on {
+            Some(last_worn_on) => last_worn_on < cutoff,
+            None => self.clothes.created_at.date_naive() < cutoff,
+        }
+    }
+}
+
+/// Price per wear rounded to the decimals of its currency, `None` when never
+/// worn
+#[must_use]
+pub fn cost_per_wear(price: Money, wear_count: i64) -> Option<Money> {
+    (wear_count > 0)
+        .then(|| Money::new(price.amount / Decimal::from(wear_count), price.currency))
+}
+
+#[derive(Debug, FromQueryResult)]
+struct WearAggregate {
+    clothes_id: i32,
+    wear_count: i64,
+    last_worn_on: Option<Date>,
+}
+
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(self, _db: &C, insert: bool) -> std::result::Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if insert {
+            let mut this = self;
+            this.pid = ActiveValue::Set(Uuid::new_v4());
+            Ok(this)
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+impl Model {
+    /// Record a wear. Wearing a coordinate logs one event per garment in it
+    /// that can be worn, which may be none. `None` when the garment is not
+    /// in one of the user's coordinates or the coordinate is not theirs.
+    pub async fn log(
+        db: &DatabaseConnection,
+        params: &LogWearParams,
+    ) -> ModelResult<Option<Vec<Self>>> {
+        let worn_on = params.worn_on.unwrap_or_else(|| Utc::now().date_naive());
+        if let Some(clothes_id) = params.clothes_id {
+            let Some(item) = clothes::Entity::find_owned(params.user_id)
+                .filter(clothes::Column::Id.eq(clothes_id))
+                .one(db)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let event = Self::insert_event(db, params.user_id, item.id, None, worn_on).await?;
+            return Ok(Some(vec![event]));
+        }
+
+        let Some(pid) = params
+            .coordinate_pid
+            .as_deref()
+            .and_then(|pid| Uuid::parse_str(pid.trim()).ok())
+        else {
+            return Ok(None);
+        };
+        let Some(coordinate) = coordinates::Entity::find_live()
+            .filter(coordinates::Column::Pid.eq(pid))
+            .filter(coordinates::Column::UserId.eq(params.user_id))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Self::log_coordinate(db, &coordinate, worn_on).await?))
+    }
+
+    /// Log a wear for every garment of a coordinate that can be worn right
+    /// now, in a single transaction. Nothing is logged for a coordinate
+    /// without such garments.
+    pub async fn log_coordinate<C: ConnectionTrait + TransactionTrait>(
+        db: &C,
+        coordinate: &coordinates::Model,
+        worn_on: Date,
+    ) -> ModelResult<Vec<Self>> {
+        let worn: Vec<clothes::Model> = clothes_coordinates::Entity::find()
+            .filter(clothes_coordinates::Column::CoordinateId.eq(coordinate.id))
+            .find_also_related(clothes::Entity)
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|(_, item)| item)
+            .filter(|item| item.deleted_at.is_none() && item.is_available())
+            .collect();
+
+        let txn = db.begin().await?;
+        let mut events = Vec::with_capacity(worn.len());
+        for item in worn {
+            events.push(
+                Self::insert_event(
+                    &txn,
+                    coordinate.user_id,
+                    item.id,
+                    Some(coordinate.id),
+                    worn_on,
+                )
+                .await?,
+            );
+        }
+        txn.commit().await?;
+        Ok(events)
+    }
+
+    async fn insert_event<C: ConnectionTrait>(
+        db: &C,
+        user_id: i32,
+        clothes_id: i32,
+        coordinate_id: Option<i32>,
+        worn_on: Date,
+    ) -> ModelResult<Self> {
+        Ok(wear_events::ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            clothes_id: ActiveValue::set(clothes_id),
+            coordinate_id: ActiveValue::set(coordinate_id),
+            worn_on: ActiveValue::set(worn_on),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?)
+    }
+
+    /// Find wear event by PID
+    pub async fn find_by_pid(db: &DatabaseConnection, pid: &str) -> ModelResult<Self> {
+        let parse_uuid = Uuid::parse_str(pid).map_err(|e| ModelError::Any(e.into()))?;
+        let event = wear_events::Entity::find()
+            .filter(wear_events::Column::Pid.eq(parse_uuid))
+            .one(db)
+            .await?;
+        event.ok_or_else(|| ModelError::EntityNotFound)
+    }
+
+    /// Undo a logged wear. Undoing one garment of a coordinate wear removes
+    /// the whole coordinate wear of that day. Returns the number of events
+    /// removed.
+    pub async fn undo(db: &DatabaseConnection, pid: &str) -> ModelResult<u64> {
+        let event = Self::find_by_pid(db, pid).await?;
+        let condition = match event.coordinate_id {
+            Some(coordinate_id) => model::query::condition()
+                .eq(wear_events::Column::UserId, event.user_id)
+                .eq(wear_events::Column::CoordinateId, coordinate_id)
+                .eq(wear_events::Column::WornOn, event.worn_on)
+                .build(),
+            None => model::query::condition()
+                .eq(wear_events::Column::Id, event.id)
+                .build(),
+        };
+        let result = wear_events::Entity::delete_many()
+            .filter(condition)
+            .exec(db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+
+    /// Wear events of a user, most recent first, optionally limited to a
+    /// date range (inclusive)
+    pub async fn find_by_user(
+        db: &DatabaseConnection,
+        user_id: i32,
+        range: &WearRangeQuery,
+    ) -> ModelResult<Vec<Self>> {
+        let mut select =
+            wear_events::Entity::find().filter(wear_events::Column::UserId.eq(user_id));
+        if let Some(from) = range.from {
+            select = select.filter(wear_events::Column::WornOn.gte(from));
+        }
+        if let Some(to) = range.to {
+            select = select.filter(wear_events::Column::WornOn.lte(to));
+        }
+        Ok(select
+            .order_by_desc(wear_events::Column::WornOn)
+            .order_by_desc(wear_events::Column::Id)
+            .all(db)
+            .await?)
+    }
+
+    /// Wear count, last worn date and cost-per-wear of every clothes item
+    /// in the user's coordinates
+    pub async fn clothes_stats(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ModelResult<Vec<ClothesWithWear>> {
+        let aggregates: HashMap<i32, WearAggregate> = wear_events::Entity::find()
+            .select_only()
+            .column(wear_events::Column::ClothesId)
+            .column_as(wear_events::Column::Id.count(), "wear_count")
+            .column_as(wear_events::Column::WornOn.max(), "last_worn_on")
+            .filter(wear_events::Column::UserId.eq(user_id))
+            .group_by(wear_events::Column::ClothesId)
+            .into_model::<WearAggregate>()
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|aggregate| (aggregate.clothes_id, aggregate))
+            .collect();
+
+        let clothes = clothes::Entity::find_owned(user_id)
+            .order_by_asc(clothes::Column::Id)
+            .all(db)
+            .await?;
+        Ok(clothes
+            .into_iter()
+            .map(|item| match aggregates.get(&item.id) {
+                Some(aggregate) => {
+                    ClothesWithWear::new(item, aggregate.wear_count, aggregate.last_worn_on)
+                }
+                None => ClothesWithWear::new(item, 0, None),
+            })
+            .collect())
+    }
+
+    /// Clothes in the user's coordinates that they have never worn
+    pub async fn never_worn(
+        db: &DatabaseConnection,
+        user_id: i32,
+    ) -> ModelResult<Vec<ClothesWithWear>> {
+        let stats = Self::clothes_stats(db, user_id).await?;
+        Ok(stats
+            .into_iter()
+            .filter(|item| item.wear_count == 0)
+            .collect())
+    }
+
+    /// Clothes the user has not worn in the last `months` months, least
+    /// recently worn first
+    pub async fn not_worn_in_months(
+        db: &DatabaseConnection,
+        user_id: i32,
+        months: u32,
+    ) -> ModelResult<Vec<ClothesWithWear>> {
+        let today = Utc::now().date_naive();
+        let cutoff = today
+            .checked_sub_months(Months::new(months))
+            .ok_or_else(|| ModelError::msg("months is out of range"))?;
+        let mut stats: Vec<ClothesWithWear> = Self::clothes_stats(db, user_id)
+            .await?
+            .into_iter()
+            .filter(|item| item.not_worn_since(cutoff))
+            .collect();
+        stats.sort_by_key(|item| item.last_worn_on);
+        Ok(stats)
+    }
+}
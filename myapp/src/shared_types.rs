@@ -35,12 +35,30 @@ pub struct ClothesItem {
     pub brand: String,
     pub category: String,
     pub size: String,
+    pub size_system: Option<String>,
+    pub size_letter: Option<String>,
+    pub display_size: Option<String>,
     pub color: String,
+    pub primary_color_hex: Option<String>,
+    pub primary_color_family: Option<String>,
+    pub secondary_color_hex: Option<String>,
+    pub secondary_color_family: Option<String>,
     pub material: Option<String>,
-    pub price: f64,
+    /// Decimal string in the minor units of `currency`
+    pub price: String,
+    pub currency: String,
     pub in_stock: bool,
     pub stock_quantity: i32,
     pub image_url: Option<String>,
+    pub lifecycle_state: String,
+    pub condition: Option<String>,
+    pub lent_to: Option<String>,
+    /// `YYYY-MM-DD`
+    pub purchase_date: Option<String>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
+    /// Content type of the receipt at `/api/clothes/{pid}/receipt`
+    pub receipt_content_type: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -55,10 +73,16 @@ pub struct CreateClothesRequest {
     pub category: String,
     pub size: String,
     pub color: String,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
     pub material: Option<String>,
-    pub price: f64,
+    pub price: String,
+    pub currency: Option<String>,
     pub stock_quantity: i32,
     pub image_url: Option<String>,
+    pub purchase_date: Option<String>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -71,10 +95,16 @@ pub struct UpdateClothesRequest {
     pub category: Option<String>,
     pub size: Option<String>,
     pub color: Option<String>,
+    pub primary_color: Option<String>,
+    pub secondary_color: Option<String>,
     pub material: Option<String>,
-    pub price: Option<f64>,
-    pub stock_quantity: Option<i32>,
+    pub price: Option<String>,
+    pub currency: Option<String>,
     pub image_url: Option<String>,
+    pub condition: Option<String>,
+    pub purchase_date: Option<String>,
+    pub store: Option<String>,
+    pub order_number: Option<String>,
 }
 
 // Coordinates関連の型
@@ -86,7 +116,7 @@ pub struct Coordinate {
     pub pid: String,
     pub name: String,
     pub description: Option<String>,
-    pub season: Option<String>,
+    pub seasons: Vec<String>,
     pub occasion: Option<String>,
     pub style: Option<String>,
     pub user_id: i32,
@@ -102,7 +132,7 @@ pub struct Coordinate {
 pub struct CreateCoordinateRequest {
     pub name: String,
     pub description: Option<String>,
-    pub season: Option<String>,
+    pub seasons: Vec<String>,
     pub occasion: Option<String>,
     pub style: Option<String>,
     pub is_favorite: bool,
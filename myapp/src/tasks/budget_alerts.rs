@@ -0,0 +1,34 @@
+use loco_rs::prelude::*;
+
+use crate::workers::budget_alert;
+
+/// Alert users of the budgets their purchases went over this month, once
+/// per budget and month. Scheduled daily; `month:` checks another month.
+///
+/// ```sh
+/// myapp-cli task budget_alerts
+/// myapp-cli task budget_alerts month:2025-06
+/// ```
+pub struct BudgetAlerts;
+
+#[async_trait]
+impl Task for BudgetAlerts {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "budget_alerts".to_string(),
+            detail: "Alert users of exceeded monthly budgets ([month:<YYYY-MM>])".to_string(),
+        }
+    }
+
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let month = match vars.cli_arg("month") {
+            Ok(month) => Date::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+                .map_err(|_| Error::string("month must be written YYYY-MM"))?,
+            Err(_) => chrono::Utc::now().date_naive(),
+        };
+
+        let sent = budget_alert::alert_exceeded(ctx, month).await?;
+        tracing::info!(sent, month = %month.format("%Y-%m"), "budget alerts sent");
+        Ok(())
+    }
+}
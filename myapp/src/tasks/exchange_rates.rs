@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use loco_rs::prelude::*;
+
+use crate::{
+    data::money::Currency,
+    models::{
+        clothes_imports::ImportFormat,
+        exchange_rates::{self, RateParams},
+    },
+};
+
+/// Maintain the exchange rates totals are converted with: set a single pair
+/// or import a CSV (`base,quote,rate`) or JSON file. Rates of the same pairs
+/// are replaced, without arguments the stored rates are listed.
+///
+/// ```sh
+/// myapp-cli task exchange_rates base:EUR quote:JPY rate:161.52
+/// myapp-cli task exchange_rates file:rates.csv
+/// myapp-cli task exchange_rates
+/// ```
+pub struct ExchangeRates;
+
+#[async_trait]
+impl Task for ExchangeRates {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "exchange_rates".to_string(),
+            detail: "Set or import exchange rates (base:<code> quote:<code> rate:<decimal> | file:<path> [format:csv|json])"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let currency = |name: &str| -> Result<Currency> {
+            let code = vars.cli_arg(name)?;
+            Currency::parse(code).ok_or_else(|| {
+                Error::string(&format!(
+                    "{name}: '{code}' is not an ISO-4217 currency code"
+                ))
+            })
+        };
+
+        let (rates, source) = if let Ok(file) = vars.cli_arg("file") {
+            let format = vars
+                .cli_arg("format")
+                .map(String::as_str)
+                .ok()
+                .or_else(|| Path::new(file).extension().and_then(|ext| ext.to_str()))
+                .and_then(ImportFormat::parse)
+                .ok_or_else(|| {
+                    Error::string("could not determine format, pass format:csv or format:json")
+                })?;
+            let data = std::fs::read_to_string(file)?;
+            (exchange_rates::parse(format, &data)?, file.clone())
+        } else if let Ok(rate) = vars.cli_arg("rate") {
+            let rate = RateParams {
+                base: currency("base")?,
+                quote: currency("quote")?,
+                rate: rate.clone(),
+            };
+            (vec![rate], "manual".to_string())
+        } else {
+            for rate in exchange_rates::Model::list(&ctx.db).await? {
+                tracing::info!(
+                    base = rate.base,
+                    quote = rate.quote,
+                    rate = %rate.rate,
+                    source = rate.source,
+                    "exchange rate"
+                );
+            }
+            return Ok(());
+        };
+
+        let stored = exchange_rates::Model::store(&ctx.db, &rates, &source).await?;
+        tracing::info!(stored, source, "stored exchange rates");
+        Ok(())
+    }
+}
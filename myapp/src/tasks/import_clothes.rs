@@ -0,0 +1,67 @@
+use std::{collections::HashMap, path::Path};
+
+use loco_rs::prelude::*;
+
+use crate::models::clothes_imports::{self, ImportFormat};
+
+/// Import clothes from a CSV or JSON file.
+///
+/// ```sh
+/// myapp-cli task import_clothes file:wardrobe.csv
+/// myapp-cli task import_clothes file:wardrobe.json format:json dry_run:true
+/// myapp-cli task import_clothes file:wardrobe.json user_id:1
+/// ```
+///
+/// The format defaults to the file extension. Column mapping follows the
+/// import endpoint: columns are matched to `CreateClothesParams` fields by
+/// name. Coordinates of a wardrobe export are restored for `user_id`.
+pub struct ImportClothes;
+
+#[async_trait]
+impl Task for ImportClothes {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "import_clothes".to_string(),
+            detail: "Import clothes from a CSV or JSON file (file:<path> [format:csv|json] [dry_run:true] [user_id:<id>])"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let file = vars.cli_arg("file")?;
+        let format = vars
+            .cli_arg("format")
+            .map(String::as_str)
+            .ok()
+            .or_else(|| Path::new(file).extension().and_then(|ext| ext.to_str()))
+            .and_then(ImportFormat::parse)
+            .ok_or_else(|| Error::string("could not determine format, pass format:csv or format:json"))?;
+        let dry_run = vars.cli_arg("dry_run").is_ok_and(|value| value == "true");
+        let user_id = vars
+            .cli_arg("user_id")
+            .ok()
+            .map(|value| value.parse::<i32>())
+            .transpose()
+            .map_err(|_| Error::string("user_id must be a number"))?;
+
+        let data = std::fs::read_to_string(file)?;
+        let parsed = clothes_imports::parse(format, &data, &HashMap::new())?;
+
+        let imported = if dry_run || !parsed.errors().is_empty() {
+            0
+        } else {
+            clothes_imports::import(&ctx.db, &parsed, user_id).await?
+        };
+
+        let report = parsed.report(dry_run, imported);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        if !report.errors.is_empty() {
+            return Err(Error::string(&format!(
+                "{} of {} rows are invalid, nothing was imported",
+                report.total_rows - report.valid_rows,
+                report.total_rows
+            )));
+        }
+        Ok(())
+    }
+}
@@ -1 +1,5 @@
-
+pub mod import_clothes;
+pub mod normalize_sizes;
+pub mod purge_trash;
+pub mod exchange_rates;
+pub mod budget_alerts;
@@ -0,0 +1,37 @@
+use loco_rs::prelude::*;
+
+use crate::models::_entities::clothes;
+
+/// Recompute the normalized size columns of every clothes item, e.g. after
+/// the conversion tables changed or for rows created before sizes were
+/// normalized.
+pub struct NormalizeSizes;
+
+#[async_trait]
+impl Task for NormalizeSizes {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "normalize_sizes".to_string(),
+            detail: "Recompute normalized sizes for all clothes".to_string(),
+        }
+    }
+
+    async fn run(&self, ctx: &AppContext, _vars: &task::Vars) -> Result<()> {
+        let items = clothes::Entity::find().all(&ctx.db).await?;
+        let total = items.len();
+        let mut unparsed = 0;
+
+        for item in items {
+            let mut active_model = item.into_active_model();
+            active_model.set_normalized_size();
+            let item = active_model.update(&ctx.db).await?;
+            if item.size_system.is_none() {
+                tracing::warn!(pid = %item.pid, size = %item.size, "could not parse size");
+                unparsed += 1;
+            }
+        }
+
+        tracing::info!(total, unparsed, "normalized clothes sizes");
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use loco_rs::prelude::*;
+
+use crate::{common::settings::Settings, models::trash};
+
+/// Delete clothes and coordinates that have been in the trash for longer
+/// than `settings.trash.retention_days`. Scheduled daily; `days:` overrides
+/// the retention for a single run.
+///
+/// ```sh
+/// myapp-cli task purge_trash
+/// myapp-cli task purge_trash days:7
+/// ```
+pub struct PurgeTrash;
+
+#[async_trait]
+impl Task for PurgeTrash {
+    fn task(&self) -> TaskInfo {
+        TaskInfo {
+            name: "purge_trash".to_string(),
+            detail: "Delete trashed clothes and coordinates past their retention ([days:<n>])"
+                .to_string(),
+        }
+    }
+
+    async fn run(&self, ctx: &AppContext, vars: &task::Vars) -> Result<()> {
+        let retention_days = match vars.cli_arg("days") {
+            Ok(days) => days
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days >= 0)
+                .ok_or_else(|| Error::string("days must be a non-negative number"))?,
+            Err(_) => Settings::from_context(ctx)?.trash.retention_days,
+        };
+
+        let report = trash::purge(&ctx.db, retention_days, chrono::Utc::now()).await?;
+        tracing::info!(
+            clothes = report.clothes,
+            coordinates = report.coordinates,
+            retention_days,
+            "purged trash"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    _entities::{calendar_feeds, wear_events},
+    outfit_plans::{PlanConflict, PlannedOutfit},
+};
+
+/// Plans of a week or month view
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CalendarResponse {
+    pub from: chrono::NaiveDate,
+    pub to: chrono::NaiveDate,
+    pub plans: Vec<PlannedOutfit>,
+    pub conflicts: Vec<PlanConflict>,
+}
+
+/// A created or rescheduled plan, with warnings for garments planned twice
+/// on the same day
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlanResponse {
+    #[serde(flatten)]
+    pub plan: PlannedOutfit,
+    pub warnings: Vec<PlanConflict>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlanDoneResponse {
+    #[serde(flatten)]
+    pub plan: PlannedOutfit,
+    pub wear_events: Vec<wear_events::Model>,
+}
+
+/// A calendar subscription feed with its URL
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FeedResponse {
+    #[serde(flatten)]
+    pub feed: calendar_feeds::Model,
+    /// Subscription URL for calendar apps
+    pub url: String,
+}
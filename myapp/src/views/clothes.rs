@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize, Serializer, ser};
+
+use crate::{
+    data::sizes::SizeSystem,
+    models::{_entities::clothes_imports, clothes},
+};
+
+/// A clothes item with its price as [`Money`](crate::data::money::Money),
+/// `{"amount", "currency"}`, in place of the bare `price` and `currency`
+/// columns
+#[derive(Debug)]
+pub struct ClothesResponse {
+    pub clothes: clothes::Model,
+    /// Size rendered in the requested sizing system
+    pub display_size: Option<String>,
+}
+
+impl Serialize for ClothesResponse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = clothes::priced::to_value(&self.clothes).map_err(ser::Error::custom)?;
+        if let (Some(fields), Some(display_size)) = (value.as_object_mut(), &self.display_size) {
+            fields.insert("display_size".to_string(), display_size.clone().into());
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl ClothesResponse {
+    #[must_use]
+    pub fn new(clothes: clothes::Model, size_system: Option<SizeSystem>) -> Self {
+        let display_size = size_system.and_then(|system| {
+            clothes
+                .normalized_size()
+                .and_then(|size| size.display(system))
+        });
+        Self {
+            clothes,
+            display_size,
+        }
+    }
+
+    #[must_use]
+    pub fn list(clothes: Vec<clothes::Model>, size_system: Option<SizeSystem>) -> Vec<Self> {
+        clothes
+            .into_iter()
+            .map(|item| Self::new(item, size_system))
+            .collect()
+    }
+}
+
+/// Progress of a background clothes import
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ClothesImportResponse {
+    pub pid: String,
+    pub format: String,
+    pub status: String,
+    pub total_rows: i32,
+    pub processed_rows: i32,
+    pub imported_rows: i32,
+    pub errors: Option<serde_json::Value>,
+}
+
+impl ClothesImportResponse {
+    #[must_use]
+    pub fn new(import: &clothes_imports::Model) -> Self {
+        Self {
+            pid: import.pid.to_string(),
+            format: import.format.clone(),
+            status: import.status.clone(),
+            total_rows: import.total_rows,
+            processed_rows: import.processed_rows,
+            imported_rows: import.imported_rows,
+            errors: import.errors.clone(),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::_entities::wardrobe_exports;
+
+/// Status of a background wardrobe export. The download token is only sent
+/// by email.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WardrobeExportResponse {
+    pub pid: String,
+    pub format: String,
+    pub status: String,
+    pub expires_at: Option<String>,
+    pub error: Option<String>,
+}
+
+impl WardrobeExportResponse {
+    #[must_use]
+    pub fn new(export: &wardrobe_exports::Model) -> Self {
+        Self {
+            pid: export.pid.to_string(),
+            format: export.format.clone(),
+            status: export.status.clone(),
+            expires_at: export.expires_at.map(|at| at.to_rfc3339()),
+            error: export.error.clone(),
+        }
+    }
+}
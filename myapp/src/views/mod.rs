@@ -1 +1,4 @@
 pub mod auth;
+pub mod clothes;
+pub mod exports;
+pub mod calendar;
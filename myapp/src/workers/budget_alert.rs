@@ -0,0 +1,97 @@
+use std::collections::{HashMap, hash_map::Entry};
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    mailers::budget::BudgetMailer,
+    models::{
+        budgets,
+        exchange_rates::Rates,
+        notifications::{self, NotificationKind},
+        purchases::{self, Purchase},
+        users,
+    },
+};
+
+/// Checks budgets against a month's purchases and alerts the owners of the
+/// exceeded ones, in the app and by email
+pub struct BudgetAlertWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct BudgetAlertWorkerArgs {
+    /// Any day of the month to check
+    pub month: Date,
+}
+
+/// Alert the owner of every budget exceeded in the month of `month` that
+/// was not alerted of it yet, counting only the owner's purchases. Returns the number of alerts sent.
+///
+/// # Errors
+///
+/// When the database is unavailable or an email cannot be sent. Alerts sent
+/// before the failure are not repeated.
+pub async fn alert_exceeded(ctx: &AppContext, month: Date) -> Result<usize> {
+    let month = purchases::month_start(month);
+    let rates = Rates::load(&ctx.db).await?;
+
+    let mut by_user: HashMap<i32, Vec<Purchase>> = HashMap::new();
+    let mut sent = 0;
+    for budget in budgets::Entity::find().all(&ctx.db).await? {
+        if budget.alerted(month) {
+            continue;
+        }
+        let bought = match by_user.entry(budget.user_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                Purchase::find(
+                    &ctx.db,
+                    budget.user_id,
+                    Some(month),
+                    Some(purchases::month_end(month)),
+                )
+                .await?,
+            ),
+        };
+        let status = purchases::budget_status(&budget, month, bought, &rates);
+        if !status.exceeded {
+            continue;
+        }
+        let Some(user) = users::Entity::find_by_id(budget.user_id)
+            .one(&ctx.db)
+            .await?
+        else {
+            continue;
+        };
+
+        notifications::Model::notify(
+            &ctx.db,
+            user.id,
+            NotificationKind::BudgetExceeded,
+            "Budget exceeded",
+            &status.message(),
+            Some(json!(status)),
+        )
+        .await?;
+        budget.mark_alerted(&ctx.db, month).await?;
+        BudgetMailer::send_exceeded(ctx, &user, &status).await?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+#[async_trait]
+impl BackgroundWorker<BudgetAlertWorkerArgs> for BudgetAlertWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: BudgetAlertWorkerArgs) -> Result<()> {
+        let sent = alert_exceeded(&self.ctx, args.month).await?;
+        tracing::info!(month = %args.month, sent, "budget alerts checked");
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::clothes_imports;
+
+/// Runs large clothes imports queued by the import endpoint
+pub struct ClothesImportWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct ClothesImportWorkerArgs {
+    pub import_pid: String,
+}
+
+#[async_trait]
+impl BackgroundWorker<ClothesImportWorkerArgs> for ClothesImportWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: ClothesImportWorkerArgs) -> Result<()> {
+        let import = clothes_imports::Model::find_by_pid(&self.ctx.db, &args.import_pid).await?;
+        let import = import.run(&self.ctx.db).await?;
+        tracing::info!(
+            import_pid = %import.pid,
+            status = %import.status,
+            imported_rows = import.imported_rows,
+            "clothes import finished"
+        );
+        Ok(())
+    }
+}
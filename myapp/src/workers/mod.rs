@@ -1 +1,5 @@
+pub mod budget_alert;
+pub mod clothes_import;
 pub mod downloader;
+pub mod outfit_image;
+pub mod wardrobe_export;
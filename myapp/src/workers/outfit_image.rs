@@ -0,0 +1,46 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{images::ImageFetcher, settings::Settings},
+    models::outfit_images,
+};
+
+/// Renders the composite image of a coordinate after its clothes or layout
+/// changed
+pub struct OutfitImageWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct OutfitImageWorkerArgs {
+    pub coordinate_pid: String,
+    /// Replace an image supplied by the user
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[async_trait]
+impl BackgroundWorker<OutfitImageWorkerArgs> for OutfitImageWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: OutfitImageWorkerArgs) -> Result<()> {
+        let settings = Settings::from_context(&self.ctx)?;
+        let rendered = outfit_images::render_coordinate(
+            &self.ctx.db,
+            &self.ctx.storage,
+            &ImageFetcher::new(&settings.images),
+            &settings.outfit_image,
+            &args.coordinate_pid,
+            args.replace,
+        )
+        .await?;
+
+        if let Some(coordinate) = rendered {
+            tracing::info!(coordinate_pid = %coordinate.pid, "outfit image rendered");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    common::{images::ImageFetcher, settings::Settings},
+    mailers::export::ExportMailer,
+    models::{
+        users,
+        wardrobe_exports::{self, WardrobeDocument},
+    },
+};
+
+/// Builds large wardrobe exports and emails the download link to the owner
+pub struct WardrobeExportWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct WardrobeExportWorkerArgs {
+    pub export_pid: String,
+}
+
+impl WardrobeExportWorker {
+    async fn build_file(&self, export: &wardrobe_exports::Model) -> Result<String> {
+        let format = export
+            .export_format()
+            .ok_or_else(|| Error::string(&format!("Unknown export format: {}", export.format)))?;
+        let settings = Settings::from_context(&self.ctx)?;
+        let document = WardrobeDocument::collect(&self.ctx.db, export.user_id).await?;
+        let fetcher = ImageFetcher::new(&settings.images);
+        let data = document
+            .render(format, &self.ctx.storage, &fetcher)
+            .await?;
+
+        let path = export.storage_path();
+        self.ctx
+            .storage
+            .upload(Path::new(&path), &data.into())
+            .await?;
+        Ok(path)
+    }
+}
+
+#[async_trait]
+impl BackgroundWorker<WardrobeExportWorkerArgs> for WardrobeExportWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+
+    async fn perform(&self, args: WardrobeExportWorkerArgs) -> Result<()> {
+        let settings = Settings::from_context(&self.ctx)?;
+        let export = wardrobe_exports::Model::find_by_pid(&self.ctx.db, &args.export_pid)
+            .await?
+            .mark_running(&self.ctx.db)
+            .await?;
+
+        let export = match self.build_file(&export).await {
+            Ok(path) => {
+                let ttl = chrono::Duration::hours(settings.export.link_ttl_hours);
+                export.complete(&self.ctx.db, path, ttl).await?
+            }
+            Err(err) => {
+                export.fail(&self.ctx.db, &err.to_string()).await?;
+                return Err(err);
+            }
+        };
+
+        let user = users::Entity::find_by_id(export.user_id)
+            .one(&self.ctx.db)
+            .await?
+            .ok_or_else(|| Error::NotFound)?;
+        ExportMailer::send_ready(&self.ctx, &user, &export).await?;
+
+        tracing::info!(export_pid = %export.pid, format = %export.format, "wardrobe export finished");
+        Ok(())
+    }
+}
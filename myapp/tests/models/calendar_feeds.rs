@@ -0,0 +1,182 @@
+use chrono::{DateTime, NaiveDate};
+use insta::assert_snapshot;
+use myapp::models::{
+    _entities::{calendar_feeds, clothes, coordinates, outfit_plans},
+    calendar_feeds::{OutfitCalendar, escape_text},
+    coordinate_rules::Completeness,
+    coordinates::CoordinateWithClothes,
+    outfit_feedback::RatingSummary,
+    outfit_plans::PlannedOutfit,
+};
+use sea_orm::prelude::{DateTimeWithTimeZone, Uuid};
+use serde_json::json;
+
+use super::support;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("calendar_feeds");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+fn timestamp() -> DateTimeWithTimeZone {
+    DateTime::parse_from_rfc3339("2025-06-01T09:30:00+09:00").unwrap()
+}
+
+fn uuid(n: u128) -> Uuid {
+    Uuid::from_u128(n)
+}
+
+fn clothes_item(id: i32, name: &str) -> clothes::Model {
+    clothes::Model {
+        pid: uuid(100 + id as u128),
+        name: name.to_string(),
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        ..support::garment(id)
+    }
+}
+
+fn planned(
+    id: i32,
+    name: &str,
+    planned_on: NaiveDate,
+    occasion: Option<&str>,
+    note: Option<&str>,
+    clothes: Vec<clothes::Model>,
+) -> PlannedOutfit {
+    PlannedOutfit {
+        plan: outfit_plans::Model {
+            created_at: timestamp(),
+            updated_at: timestamp(),
+            id,
+            pid: uuid(id as u128),
+            planned_on,
+            occasion: occasion.map(ToString::to_string),
+            note: note.map(ToString::to_string),
+            done_at: None,
+            user_id: 1,
+            coordinate_id: id,
+            trip_id: None,
+        },
+        coordinate: CoordinateWithClothes {
+            coordinate: coordinates::Model {
+                id,
+                pid: uuid(200 + id as u128),
+                name: name.to_string(),
+                description: None,
+                occasion: None,
+                style: None,
+                seasons: json!([]),
+                user_id: 1,
+                is_favorite: false,
+                image_url: None,
+                created_at: timestamp(),
+                updated_at: timestamp(),
+                deleted_at: None,
+            },
+            clothes,
+            placements: Vec::new(),
+            completeness: Completeness {
+                score: 1.0,
+                missing_slots: Vec::new(),
+            },
+            ratings: RatingSummary::default(),
+            removed_clothes: Vec::new(),
+        },
+    }
+}
+
+#[test]
+fn renders_empty_calendar() {
+    configure_insta!();
+
+    let calendar = OutfitCalendar {
+        app_url: "http://localhost:3000",
+        plans: &[],
+    };
+    assert_snapshot!(calendar.render());
+}
+
+#[test]
+fn renders_planned_outfits() {
+    configure_insta!();
+
+    let plans = vec![
+        planned(
+            1,
+            "Office basics",
+            NaiveDate::from_ymd_opt(2025, 6, 2).unwrap(),
+            Some("work"),
+            None,
+            vec![
+                clothes_item(1, "White shirt"),
+                clothes_item(2, "Navy chinos"),
+            ],
+        ),
+        planned(
+            2,
+            "Weekend; casual, relaxed",
+            NaiveDate::from_ymd_opt(2025, 6, 7).unwrap(),
+            None,
+            Some("Bring an umbrella\\just in case"),
+            vec![clothes_item(3, "Striped tee")],
+        ),
+    ];
+    let calendar = OutfitCalendar {
+        app_url: "http://localhost:3000/",
+        plans: &plans,
+    };
+    assert_snapshot!(calendar.render());
+}
+
+#[test]
+fn folds_long_lines_at_75_octets() {
+    configure_insta!();
+
+    let plans = vec![planned(
+        3,
+        "夏のリゾートコーディネート・リネンシャツとショートパンツの組み合わせ",
+        NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+        Some("vacation"),
+        None,
+        vec![clothes_item(4, "Linen shirt")],
+    )];
+    let ics = OutfitCalendar {
+        app_url: "http://localhost:3000",
+        plans: &plans,
+    }
+    .render();
+
+    assert!(ics.ends_with("\r\n"));
+    assert_eq!(ics.matches('\n').count(), ics.matches("\r\n").count());
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75, "line longer than 75 octets: {line}");
+    }
+    assert_snapshot!(ics);
+}
+
+#[test]
+fn escapes_text_values() {
+    assert_eq!(escape_text("a,b;c\\d\r\ne"), r"a\,b\;c\\d\ne");
+}
+
+#[test]
+fn feed_url_contains_token() {
+    let feed = calendar_feeds::Model {
+        created_at: timestamp(),
+        updated_at: timestamp(),
+        id: 1,
+        pid: uuid(1),
+        token: "abc123".to_string(),
+        revoked_at: None,
+        user_id: 1,
+    };
+    assert_eq!(
+        feed.url("https://example.com/"),
+        "https://example.com/api/calendar/feeds/abc123/outfits.ics"
+    );
+}
@@ -0,0 +1,138 @@
+use chrono::Utc;
+use myapp::{
+    data::slots::Slot,
+    models::{
+        _entities::{clothes, clothes_coordinates},
+        capsules::{CapsuleHistory, analyze},
+        coordinate_rules::{ForbiddenCombination, SlotRules},
+    },
+};
+
+use super::support;
+
+fn garment(id: i32, category: &str, color: &str) -> clothes::Model {
+    clothes::Model {
+        name: format!("{color} {category}"),
+        category: category.to_string(),
+        color: color.to_string(),
+        ..support::garment(id)
+    }
+}
+
+fn link(coordinate_id: i32, clothes_id: i32, slot: Option<&str>) -> clothes_coordinates::Model {
+    let now = Utc::now().into();
+    clothes_coordinates::Model {
+        id: coordinate_id * 100 + clothes_id,
+        clothes_id,
+        coordinate_id,
+        notes: None,
+        created_at: now,
+        updated_at: now,
+        slot: slot.map(ToString::to_string),
+        x: None,
+        y: None,
+        scale: None,
+        rotation: None,
+        z_index: None,
+    }
+}
+
+#[test]
+fn counts_outfits_under_the_slot_rules() {
+    let capsule = [
+        garment(1, "shirt", "white"),
+        garment(2, "tee", "black"),
+        garment(3, "pants", "black"),
+        garment(4, "jeans", "navy"),
+        garment(5, "sneakers", "white"),
+        garment(6, "coat", "grey"),
+    ];
+    let analysis = analyze(
+        &capsule,
+        &[],
+        &CapsuleHistory::default(),
+        &SlotRules::default(),
+    );
+
+    // 2 tops x 2 bottoms x 1 pair of shoes x (no coat or the coat)
+    assert_eq!(analysis.outfits, 8);
+    assert!(!analysis.capped);
+    assert!(analysis.missing_slots.is_empty());
+    assert!(analysis.orphans.is_empty());
+    let coat = analysis.items.iter().find(|i| i.clothes_id == 6).unwrap();
+    assert_eq!((coat.slot, coat.outfits), (Some(Slot::Outer), 4));
+    let shoes = analysis.items.iter().find(|i| i.clothes_id == 5).unwrap();
+    assert_eq!(shoes.outfits, 8);
+    assert!(analysis.best_addition.is_none());
+}
+
+#[test]
+fn forbidden_combinations_are_not_counted() {
+    let capsule = [
+        garment(1, "shirt", "white"),
+        garment(2, "shorts", "beige"),
+        garment(3, "jeans", "navy"),
+        garment(4, "sneakers", "white"),
+        garment(5, "coat", "grey"),
+    ];
+    let rules = SlotRules {
+        forbidden_combinations: vec![ForbiddenCombination {
+            items: vec!["shorts".to_string(), "coat".to_string()],
+            message: None,
+        }],
+        ..SlotRules::default()
+    };
+    let analysis = analyze(&capsule, &[], &CapsuleHistory::default(), &rules);
+    assert_eq!(analysis.outfits, 3);
+}
+
+#[test]
+fn finds_orphans_and_the_best_addition() {
+    let capsule = [
+        garment(1, "shirt", "white"),
+        garment(2, "pants", "black"),
+        garment(3, "umbrella", "black"),
+    ];
+    let others = [
+        garment(10, "hat", "black"),
+        garment(11, "sneakers", "white"),
+        garment(12, "boots", "black"),
+    ];
+    let history =
+        CapsuleHistory::from_links(&[link(1, 12, None), link(1, 1, None), link(1, 2, None)]);
+    let analysis = analyze(&capsule, &others, &history, &SlotRules::default());
+
+    assert_eq!(analysis.outfits, 0);
+    assert_eq!(analysis.missing_slots, vec![Slot::Shoes]);
+    assert_eq!(analysis.orphans.len(), 3);
+    assert_eq!(
+        analysis.orphans[2].reason,
+        "its category does not fill an outfit slot"
+    );
+
+    // Both pairs of shoes complete one outfit; the boots were worn with the
+    // capsule before
+    let best = analysis.best_addition.unwrap();
+    assert_eq!(best.clothes.id, 12);
+    assert_eq!(
+        (best.slot, best.new_outfits, best.worn_with),
+        (Slot::Shoes, 1, 2)
+    );
+}
+
+#[test]
+fn history_decides_the_slot_of_a_garment() {
+    let history = CapsuleHistory::from_links(&[
+        link(1, 7, Some("top")),
+        link(2, 7, Some("top")),
+        link(3, 7, Some("outer")),
+        link(1, 8, None),
+    ]);
+    let cardigan = garment(7, "cardigan", "grey");
+    assert_eq!(history.slot(&cardigan), Some(Slot::Top));
+    assert_eq!(
+        history.slot(&garment(9, "cardigan", "grey")),
+        Some(Slot::Outer)
+    );
+    assert!(history.worn_with[&8].contains(&7));
+}
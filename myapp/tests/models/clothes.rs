@@ -0,0 +1,222 @@
+use chrono::Utc;
+use myapp::{
+    common::settings::Settings,
+    data::slots::Slot,
+    models::{
+        _entities::{clothes, coordinate_tombstones},
+        clothes::{
+            AffectedCoordinate, ColorQuery, CreateClothesParams, DeleteImpact, DeletePolicy, Model,
+            SizeQuery, UpdateClothesParams, priced,
+        },
+        coordinate_tombstones::{REMOVED_LABEL, RemovedClothes},
+        coordinates,
+        wear_events::ClothesWithWear,
+    },
+};
+use sea_orm::prelude::{Decimal, Uuid};
+use serde_json::json;
+use serial_test::serial;
+
+use super::support;
+
+fn garment() -> clothes::Model {
+    clothes::Model {
+        name: "Grey wool coat".to_string(),
+        category: "coat".to_string(),
+        color: "grey".to_string(),
+        price: Decimal::new(12000, 2),
+        ..support::garment(1)
+    }
+}
+
+fn affected(name: &str, remaining_clothes: usize) -> AffectedCoordinate {
+    AffectedCoordinate {
+        pid: Uuid::new_v4(),
+        name: name.to_string(),
+        user_id: 1,
+        remaining_clothes,
+    }
+}
+
+#[test]
+fn only_the_block_policy_refuses_a_used_garment() {
+    let item = garment();
+    let used = || vec![affected("Office", 3), affected("Rainy day", 0)];
+
+    let blocked = DeleteImpact::new(&item, DeletePolicy::Block, used());
+    assert!(blocked.blocked);
+    assert_eq!(blocked.coordinates.len(), 2);
+    assert_eq!(blocked.clothes_pid, item.pid);
+
+    assert!(!DeleteImpact::new(&item, DeletePolicy::Block, Vec::new()).blocked);
+    assert!(!DeleteImpact::new(&item, DeletePolicy::Detach, used()).blocked);
+    assert!(!DeleteImpact::new(&item, DeletePolicy::Cascade, used()).blocked);
+}
+
+#[test]
+fn delete_policy_defaults_to_block_and_is_configurable() {
+    assert_eq!(
+        Settings::default().clothes.delete_policy,
+        DeletePolicy::Block
+    );
+    let settings = Settings::from_json(&json!({"clothes": {"delete_policy": "detach"}})).unwrap();
+    assert_eq!(settings.clothes.delete_policy, DeletePolicy::Detach);
+}
+
+#[test]
+fn tombstones_read_as_removed_items() {
+    let now = Utc::now().into();
+    let tombstone = coordinate_tombstones::Model {
+        id: 1,
+        coordinate_id: 2,
+        clothes_pid: Uuid::new_v4(),
+        name: "Grey wool coat".to_string(),
+        category: "coat".to_string(),
+        slot: Some("outer".to_string()),
+        created_at: now,
+        updated_at: now,
+        placeholder: true,
+        notes: None,
+        x: None,
+        y: None,
+        scale: None,
+        rotation: None,
+        z_index: None,
+    };
+
+    let removed = RemovedClothes::from(&tombstone);
+    assert_eq!(removed.label, REMOVED_LABEL);
+    assert_eq!(removed.name, "Grey wool coat");
+    assert_eq!(removed.slot, Some(Slot::Outer));
+    assert_eq!(removed.removed_at, tombstone.created_at);
+}
+
+#[test]
+fn resolves_the_near_color_once_and_rejects_unknown_ones() {
+    let query = |near: &str| ColorQuery {
+        near: Some(near.to_string()),
+        ..Default::default()
+    };
+
+    assert_eq!(query("#F00").near_color().unwrap().unwrap().hex, "#FF0000");
+    assert!(query("no-such-color").near_color().is_err());
+    assert!(ColorQuery::default().near_color().unwrap().is_none());
+}
+
+#[test]
+fn reports_unknown_colors_per_field() {
+    let params: CreateClothesParams = serde_json::from_value(json!({
+        "name": "Oxford Shirt",
+        "brand": "Acme",
+        "category": "tops",
+        "size": "M",
+        "color": "white",
+        "primary_color": "#FFFFFF",
+        "secondary_color": "no-such-color",
+        "price": "30.00",
+        "stock_quantity": 1,
+    }))
+    .unwrap();
+    let problems = params.problems();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].field, "secondary_color");
+
+    let update: UpdateClothesParams =
+        serde_json::from_value(json!({"primary_color": "#GG0000"})).unwrap();
+    assert_eq!(update.problems()[0].field, "primary_color");
+    let update: UpdateClothesParams = serde_json::from_value(json!({"name": "Shirt"})).unwrap();
+    assert!(update.problems().is_empty());
+}
+
+#[test]
+fn rejects_a_negative_opening_stock() {
+    let params: CreateClothesParams = serde_json::from_value(json!({
+        "name": "Oxford Shirt",
+        "brand": "Acme",
+        "category": "tops",
+        "size": "M",
+        "color": "white",
+        "price": "30.00",
+        "stock_quantity": -1,
+    }))
+    .unwrap();
+    let problems = params.problems();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].field, "stock_quantity");
+    assert_eq!(problems[0].message, "must not be negative");
+}
+
+#[test]
+fn writes_prices_as_money_in_garment_responses() {
+    let item = clothes::Model {
+        price: Decimal::new(1200, 0),
+        currency: "JPY".to_string(),
+        ..garment()
+    };
+
+    let value = priced::to_value(&item).unwrap();
+    assert_eq!(value["price"], json!({"amount": "1200", "currency": "JPY"}));
+    assert!(value.get("currency").is_none());
+    assert_eq!(priced::from_value(value).unwrap(), item);
+
+    let with_wear = serde_json::to_value(ClothesWithWear::new(item.clone(), 0, None)).unwrap();
+    assert_eq!(with_wear["price"]["currency"], "JPY");
+    assert_eq!(with_wear["wear_count"], 0);
+    let read: ClothesWithWear = serde_json::from_value(with_wear).unwrap();
+    assert_eq!(read.clothes, item);
+}
+
+#[test]
+fn unscoped_size_filters_read_each_garment_in_its_own_group() {
+    let query = |size: &str| SizeQuery {
+        size: Some(size.to_string()),
+        size_system: None,
+    };
+    let shoes = |cm: i64| clothes::Model {
+        category: "sneakers".to_string(),
+        size_cm: Some(Decimal::new(cm, 1)),
+        ..garment()
+    };
+    let coat = clothes::Model {
+        size_letter: Some("M".to_string()),
+        ..garment()
+    };
+
+    assert!(query("27cm").matches(&shoes(270)));
+    assert!(!query("27cm").matches(&shoes(240)));
+    assert!(query("M").matches(&coat));
+    assert!(!query("M").matches(&shoes(270)));
+    assert!(query("not a size").matches(&coat));
+}
+
+#[tokio::test]
+#[serial]
+async fn restoring_a_cascade_deleted_garment_puts_it_back_into_its_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let coat = support::insert_garment(db, garment()).await;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    let coordinate = support::insert_coordinate(db, &[coat.id, shirt.id]).await;
+    let pid = coordinate.pid.to_string();
+
+    let impact = Model::delete_by_pid(db, &coat.pid.to_string(), DeletePolicy::Cascade)
+        .await
+        .unwrap();
+    assert!(!impact.blocked);
+    let outfit = coordinates::Model::find_by_pid_with_clothes(db, &pid)
+        .await
+        .unwrap();
+    assert_eq!(outfit.clothes.len(), 1);
+    assert!(outfit.removed_clothes.is_empty());
+
+    Model::restore_by_pid(db, &coat.pid.to_string())
+        .await
+        .unwrap();
+    let outfit = coordinates::Model::find_by_pid_with_clothes(db, &pid)
+        .await
+        .unwrap();
+    let names: Vec<&str> = outfit.clothes.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"Grey wool coat"));
+    assert!(outfit.removed_clothes.is_empty());
+}
@@ -0,0 +1,83 @@
+use chrono::Utc;
+use myapp::{
+    data::slots::Slot,
+    models::{_entities::clothes_coordinates, clothes_coordinates::ClothesLayout},
+};
+
+#[test]
+fn layout_rejects_invalid_canvas_values() {
+    let layout = ClothesLayout {
+        x: Some(f64::NAN),
+        scale: Some(0.0),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        layout.problems(),
+        vec![
+            "x must be a finite number".to_string(),
+            "scale must be greater than 0".to_string(),
+        ]
+    );
+    assert!(ClothesLayout::default().problems().is_empty());
+}
+
+#[test]
+fn slot_must_match_the_garment_category() {
+    let layout = ClothesLayout {
+        slot: Some(Slot::Shoes),
+        ..Default::default()
+    };
+
+    assert!(layout.category_problems("スニーカー").is_empty());
+    assert_eq!(
+        layout.category_problems("coat"),
+        vec!["a coat garment cannot be placed in the shoes slot".to_string()]
+    );
+    // Unknown categories fit any slot
+    assert!(layout.category_problems("dress").is_empty());
+}
+
+#[test]
+fn slots_accept_the_legacy_outerwear_name() {
+    assert_eq!(Slot::parse("Outerwear"), Some(Slot::Outer));
+    assert_eq!(
+        serde_json::from_str::<Slot>("\"outerwear\"").unwrap(),
+        Slot::Outer
+    );
+    assert_eq!(serde_json::to_string(&Slot::Outer).unwrap(), "\"outer\"");
+}
+
+#[test]
+fn layout_is_read_from_the_link() {
+    let now = Utc::now().into();
+    let link = clothes_coordinates::Model {
+        id: 1,
+        clothes_id: 2,
+        coordinate_id: 3,
+        slot: Some("outer".to_string()),
+        x: Some(0.5),
+        y: None,
+        scale: Some(1.5),
+        rotation: Some(-10.0),
+        z_index: Some(2),
+        notes: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let layout = link.layout();
+    assert_eq!(layout.slot, Some(Slot::Outer));
+    assert_eq!(layout.scale, Some(1.5));
+    assert_eq!(
+        serde_json::to_value(&layout).unwrap(),
+        serde_json::json!({
+            "slot": "outer",
+            "x": 0.5,
+            "y": null,
+            "scale": 1.5,
+            "rotation": -10.0,
+            "z_index": 2,
+        })
+    );
+}
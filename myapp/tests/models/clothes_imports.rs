@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use myapp::models::clothes_imports::{self, ImportClothesParams, ImportFormat, ImportStatus};
+use serial_test::serial;
+
+use super::support;
+
+const CSV: &str = "\
+Name,Brand,Category,Size,Color,Price,Stock Quantity
+Oxford Shirt,Acme,tops,M,white,49.90,1
+,Acme,bottoms,W32 L30,navy,abc,
+Chinos,Acme,bottoms,W32 L30,beige,59,
+";
+
+#[test]
+fn can_report_row_errors() {
+    let parsed = clothes_imports::parse(ImportFormat::Csv, CSV, &HashMap::new()).unwrap();
+    let report = parsed.report(true, 0);
+
+    assert_eq!(report.total_rows, 3);
+    assert_eq!(report.valid_rows, 2);
+    let mut fields: Vec<_> = report
+        .errors
+        .iter()
+        .map(|e| (e.row, e.field.clone().unwrap_or_default()))
+        .collect();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec![(2, "name".to_string()), (2, "price".to_string())]
+    );
+}
+
+#[test]
+fn can_map_columns_and_default_stock() {
+    let json = r#"[{"Item": "Loafers", "brand": "Acme", "category": "shoes",
+        "size": "26.5cm", "color": "黒", "price": 120}]"#;
+    let mapping = HashMap::from([("item".to_string(), "name".to_string())]);
+
+    let parsed = clothes_imports::parse(ImportFormat::Json, json, &mapping).unwrap();
+    let params = parsed.rows[0].as_ref().unwrap();
+
+    assert_eq!(params.name, "Loafers");
+    assert_eq!(params.stock_quantity, 1);
+}
+
+#[test]
+fn rejects_negative_stock_per_row() {
+    let csv = "\
+Name,Brand,Category,Size,Color,Price,Stock Quantity
+Oxford Shirt,Acme,tops,M,white,49.90,-1
+";
+    let json = r#"[{"name": "Chinos", "brand": "Acme", "category": "bottoms",
+        "size": "M", "color": "beige", "price": 59, "stock_quantity": -2}]"#;
+
+    for (format, data) in [(ImportFormat::Csv, csv), (ImportFormat::Json, json)] {
+        let parsed = clothes_imports::parse(format, data, &HashMap::new()).unwrap();
+        let report = parsed.report(true, 0);
+        assert_eq!(report.valid_rows, 0);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].row, 1);
+        assert_eq!(report.errors[0].field.as_deref(), Some("stock_quantity"));
+    }
+}
+
+#[test]
+fn rejects_malformed_json() {
+    assert!(clothes_imports::parse(ImportFormat::Json, "{}", &HashMap::new()).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn fails_an_import_whose_source_cannot_be_parsed() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let params = ImportClothesParams {
+        format: ImportFormat::Json,
+        data: "not json".to_string(),
+        mapping: HashMap::new(),
+        dry_run: false,
+        user_id: None,
+    };
+    let queued = clothes_imports::Model::create_queued(db, &params, 0)
+        .await
+        .unwrap();
+
+    let import = queued.run(db).await.unwrap();
+    assert_eq!(import.status, ImportStatus::Failed.as_str());
+    assert!(import.source.is_none());
+    let errors = import.errors.unwrap();
+    assert_eq!(errors.as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,51 @@
+use myapp::data::colors::{self, ciede2000, ColorFamily, Lab};
+
+#[test]
+fn can_resolve_palette_names() {
+    let navy = colors::resolve("Navy").unwrap();
+    assert_eq!(navy.hex, "#1F2A44");
+    assert_eq!(navy.family, ColorFamily::Blue);
+
+    let black = colors::resolve(" 黒 ").unwrap();
+    assert_eq!(black.family, ColorFamily::Neutral);
+    assert_eq!(colors::resolve("ベージュ").unwrap().family, ColorFamily::Neutral);
+
+    assert!(colors::resolve("not-a-color").is_none());
+}
+
+#[test]
+fn can_resolve_hex_values() {
+    let red = colors::resolve("#f00").unwrap();
+    assert_eq!(red.hex, "#FF0000");
+    assert_eq!(red.family, ColorFamily::Red);
+
+    assert_eq!(colors::resolve("2E8B57").unwrap().family, ColorFamily::Green);
+    assert_eq!(colors::resolve("#777777").unwrap().family, ColorFamily::Neutral);
+    assert!(colors::resolve("#12345").is_none());
+}
+
+#[test]
+fn ciede2000_matches_reference_values() {
+    // Reference pairs from Sharma, Wu & Dalal (2005)
+    let cases = [
+        ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+        ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0000),
+        ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+        ((60.2574, -34.0099, 36.2677), (60.4626, -34.1751, 39.4387), 1.2644),
+    ];
+    for ((l1, a1, b1), (l2, a2, b2), expected) in cases {
+        let delta = ciede2000(
+            Lab { l: l1, a: a1, b: b1 },
+            Lab { l: l2, a: a2, b: b2 },
+        );
+        assert!((delta - expected).abs() < 1e-4, "{delta} != {expected}");
+    }
+}
+
+#[test]
+fn similar_colors_are_close() {
+    let navy_vs_denim = colors::distance("#1F2A44", "#3B4F7A").unwrap();
+    let navy_vs_red = colors::distance("#1F2A44", "#D0202E").unwrap();
+    assert!(navy_vs_denim < navy_vs_red);
+    assert!(colors::distance("#FFFFFF", "#FFFFFF").unwrap() < f64::EPSILON);
+}
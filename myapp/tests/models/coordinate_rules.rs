@@ -0,0 +1,187 @@
+use chrono::Utc;
+use myapp::{
+    data::slots::Slot,
+    models::{
+        _entities::{clothes, clothes_coordinates, coordinates},
+        coordinate_rules::{FieldError, ForbiddenCombination, OutfitGarment, SlotRules},
+        coordinates::CoordinateWithClothes,
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+use super::support;
+
+fn garment(id: i32, category: &str) -> clothes::Model {
+    clothes::Model {
+        name: category.to_string(),
+        category: category.to_string(),
+        color: "black".to_string(),
+        ..support::garment(id)
+    }
+}
+
+fn outfit(clothes: &[clothes::Model]) -> Vec<OutfitGarment<'_>> {
+    clothes
+        .iter()
+        .map(|item| OutfitGarment {
+            slot: Slot::from_category(&item.category),
+            clothes: item,
+        })
+        .collect()
+}
+
+#[test]
+fn default_rules_allow_one_pair_of_trousers() {
+    let clothes = [
+        garment(1, "shirt"),
+        garment(2, "pants"),
+        garment(3, "jeans"),
+    ];
+
+    let violations = SlotRules::default().violations(&outfit(&clothes), "clothes_ids");
+
+    assert_eq!(
+        violations,
+        vec![FieldError::new(
+            "clothes_ids",
+            "at most 1 bottom allowed, got 2"
+        )]
+    );
+    assert!(
+        SlotRules::default()
+            .violations(&outfit(&clothes[..2]), "clothes_ids")
+            .is_empty()
+    );
+}
+
+#[test]
+fn forbidden_combinations_match_slots_and_categories() {
+    let rules = SlotRules {
+        forbidden_combinations: vec![
+            ForbiddenCombination {
+                items: vec!["sandals".to_string(), "socks".to_string()],
+                message: Some("no socks with sandals".to_string()),
+            },
+            ForbiddenCombination {
+                items: vec!["head".to_string(), "outer".to_string()],
+                message: None,
+            },
+        ],
+        ..SlotRules::default()
+    };
+    let clothes = [
+        garment(1, "sandals"),
+        garment(2, "socks"),
+        garment(3, "cap"),
+        garment(4, "coat"),
+    ];
+
+    let messages: Vec<_> = rules
+        .violations(&outfit(&clothes), "clothes_ids")
+        .into_iter()
+        .map(|error| error.message)
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "no socks with sandals".to_string(),
+            "head and outer cannot be worn together".to_string(),
+        ]
+    );
+    assert!(
+        rules
+            .violations(&outfit(&clothes[1..3]), "clothes_ids")
+            .is_empty()
+    );
+}
+
+#[test]
+fn completeness_counts_required_slots() {
+    let rules = SlotRules::default();
+
+    let partial = rules.completeness([Some(Slot::Top), Some(Slot::Bottom), None]);
+    assert!((partial.score - 0.67).abs() < f64::EPSILON);
+    assert_eq!(partial.missing_slots, vec![Slot::Shoes]);
+
+    let anything = SlotRules {
+        required_slots: Vec::new(),
+        ..SlotRules::default()
+    };
+    assert!((anything.completeness([]).score - 1.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn rule_sets_are_validated() {
+    let rules: SlotRules = serde_json::from_value(serde_json::json!({
+        "required_slots": ["top", "shoes"],
+        "max_per_slot": { "shoes": 0, "outerwear": 2 },
+        "forbidden_combinations": [{ "items": ["sandals"] }],
+    }))
+    .unwrap();
+
+    assert_eq!(rules.max_per_slot.get(&Slot::Outer), Some(&2));
+    assert_eq!(
+        rules.problems(),
+        vec![
+            FieldError::new(
+                "max_per_slot.shoes",
+                "shoes is required and cannot be limited to 0"
+            ),
+            FieldError::new(
+                "forbidden_combinations[0].items",
+                "must list at least two slots or categories"
+            ),
+        ]
+    );
+    assert!(SlotRules::default().problems().is_empty());
+}
+
+#[test]
+fn coordinate_completeness_prefers_the_link_slot() {
+    let now = Utc::now().into();
+    let coordinate = coordinates::Model {
+        id: 1,
+        pid: Uuid::new_v4(),
+        name: "Weekend".to_string(),
+        description: None,
+        occasion: None,
+        style: None,
+        seasons: json!([]),
+        user_id: 1,
+        is_favorite: false,
+        image_url: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    };
+    let link = |clothes_id: i32, slot: Option<&str>| clothes_coordinates::Model {
+        id: clothes_id,
+        clothes_id,
+        coordinate_id: 1,
+        slot: slot.map(ToString::to_string),
+        x: None,
+        y: None,
+        scale: None,
+        rotation: None,
+        z_index: None,
+        notes: None,
+        created_at: now,
+        updated_at: now,
+    };
+    let garments = vec![
+        (link(1, None), garment(1, "shirt")),
+        // An unknown category placed in the bottom slot by the user
+        (link(2, Some("bottom")), garment(2, "kilt")),
+    ];
+
+    let with_clothes = CoordinateWithClothes::new(coordinate, garments, &SlotRules::default());
+
+    assert_eq!(with_clothes.clothes.len(), 2);
+    assert_eq!(with_clothes.completeness.missing_slots, vec![Slot::Shoes]);
+    assert_eq!(
+        serde_json::to_value(&with_clothes).unwrap()["completeness"],
+        serde_json::json!({ "score": 0.67, "missing_slots": ["shoes"] })
+    );
+}
@@ -0,0 +1,157 @@
+use chrono::Utc;
+use myapp::{
+    data::slots::Slot,
+    models::{
+        _entities::{clothes_coordinates, coordinates},
+        coordinate_versions::{self, CoordinateSnapshot, VersionChange},
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+fn coordinate(name: &str, season: Option<&str>) -> coordinates::Model {
+    let now = Utc::now().into();
+    coordinates::Model {
+        id: 1,
+        pid: Uuid::new_v4(),
+        name: name.to_string(),
+        description: None,
+        occasion: Some("work".to_string()),
+        style: None,
+        seasons: json!(season.into_iter().collect::<Vec<_>>()),
+        user_id: 1,
+        is_favorite: false,
+        image_url: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    }
+}
+
+fn link(clothes_id: i32, slot: &str, x: Option<f64>) -> clothes_coordinates::Model {
+    let now = Utc::now().into();
+    clothes_coordinates::Model {
+        id: clothes_id,
+        clothes_id,
+        coordinate_id: 1,
+        slot: Some(slot.to_string()),
+        x,
+        y: x,
+        scale: None,
+        rotation: None,
+        z_index: None,
+        notes: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn version(version: i32, snapshot: &CoordinateSnapshot) -> coordinate_versions::Model {
+    let now = Utc::now().into();
+    coordinate_versions::Model {
+        created_at: now,
+        updated_at: now,
+        id: version,
+        version,
+        change: VersionChange::Updated.as_str().to_string(),
+        snapshot: serde_json::to_value(snapshot).unwrap(),
+        coordinate_id: 1,
+    }
+}
+
+#[test]
+fn snapshot_orders_clothes_by_id() {
+    let snapshot = CoordinateSnapshot::new(
+        &coordinate("Office", None),
+        &[link(3, "shoes", None), link(1, "top", Some(0.5))],
+    );
+
+    let ids: Vec<_> = snapshot
+        .clothes
+        .iter()
+        .map(|item| item.clothes_id)
+        .collect();
+    assert_eq!(ids, vec![1, 3]);
+    assert_eq!(snapshot.clothes[0].layout.slot, Some(Slot::Top));
+}
+
+#[test]
+fn diff_lists_metadata_and_clothes_changes() {
+    let before = CoordinateSnapshot::new(
+        &coordinate("Office", None),
+        &[
+            link(1, "top", Some(0.5)),
+            link(2, "bottom", None),
+            link(3, "shoes", None),
+        ],
+    );
+    let after = CoordinateSnapshot::new(
+        &coordinate("Office, casual", Some("spring")),
+        &[
+            link(1, "top", Some(0.25)),
+            link(3, "shoes", None),
+            link(4, "bag", None),
+        ],
+    );
+
+    let diff = version(1, &before).diff(&version(2, &after)).unwrap();
+
+    assert_eq!((diff.from, diff.to), (1, 2));
+    let fields: Vec<_> = diff
+        .changes
+        .metadata
+        .iter()
+        .map(|change| change.field.as_str())
+        .collect();
+    assert_eq!(fields, vec!["name", "seasons"]);
+    assert_eq!(diff.changes.metadata[1].to, json!(["spring"]));
+    assert_eq!(diff.changes.added[0].clothes_id, 4);
+    assert_eq!(diff.changes.removed[0].clothes_id, 2);
+    assert_eq!(diff.changes.repositioned.len(), 1);
+    assert_eq!(diff.changes.repositioned[0].clothes_id, 1);
+    assert_eq!(diff.changes.repositioned[0].to.x, Some(0.25));
+}
+
+#[test]
+fn diff_of_identical_versions_is_empty() {
+    let snapshot =
+        CoordinateSnapshot::new(&coordinate("Office", None), &[link(1, "top", Some(0.5))]);
+
+    let diff = version(1, &snapshot).diff(&version(2, &snapshot)).unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&diff).unwrap(),
+        json!({
+            "from": 1,
+            "to": 2,
+            "metadata": [],
+            "added": [],
+            "removed": [],
+            "repositioned": [],
+        })
+    );
+}
+
+#[test]
+fn snapshot_round_trips_through_json() {
+    let snapshot = CoordinateSnapshot::new(
+        &coordinate("Office", Some("autumn")),
+        &[link(1, "outer", Some(0.5))],
+    );
+    let stored = version(1, &snapshot);
+
+    assert_eq!(stored.snapshot().unwrap(), snapshot);
+    assert_eq!(
+        stored.snapshot["clothes"][0],
+        json!({
+            "clothes_id": 1,
+            "slot": "outer",
+            "x": 0.5,
+            "y": 0.5,
+            "scale": null,
+            "rotation": null,
+            "z_index": null,
+            "notes": null,
+        })
+    );
+}
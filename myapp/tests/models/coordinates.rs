@@ -0,0 +1,53 @@
+use chrono::Utc;
+use myapp::{
+    data::seasons::Season,
+    models::{
+        _entities::coordinates,
+        coordinates::{CoordinateFilter, PageQuery},
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+#[test]
+fn page_query_clamps_the_limit_and_parses_the_cursor() {
+    assert_eq!(PageQuery::default().limit(), PageQuery::DEFAULT_LIMIT);
+    let query = PageQuery {
+        cursor: Some("42".to_string()),
+        limit: Some(10_000),
+    };
+    assert_eq!(query.limit(), PageQuery::MAX_LIMIT);
+    assert_eq!(query.after().unwrap(), Some(42));
+
+    let zero = PageQuery {
+        cursor: Some("page-2".to_string()),
+        limit: Some(0),
+    };
+    assert_eq!(zero.limit(), 1);
+    assert!(zero.after().is_err());
+}
+
+#[test]
+fn filters_match_seasons_and_favorites() {
+    let now = Utc::now().into();
+    let coordinate = coordinates::Model {
+        id: 1,
+        pid: Uuid::new_v4(),
+        name: "Linen weekend".to_string(),
+        description: None,
+        occasion: None,
+        style: None,
+        seasons: json!(["spring", "summer"]),
+        user_id: 1,
+        is_favorite: false,
+        image_url: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    };
+
+    assert!(CoordinateFilter::All.matches(&coordinate));
+    assert!(CoordinateFilter::Season(Season::Summer).matches(&coordinate));
+    assert!(!CoordinateFilter::Season(Season::Winter).matches(&coordinate));
+    assert!(!CoordinateFilter::Favorites.matches(&coordinate));
+}
@@ -0,0 +1,160 @@
+use myapp::{
+    data::money::{Currency, Money},
+    models::{
+        _entities::clothes,
+        clothes_imports::ImportFormat,
+        exchange_rates::{self, Rates, WardrobeTotals},
+    },
+};
+use sea_orm::prelude::Decimal;
+use serial_test::serial;
+
+use super::support;
+
+fn currency(code: &str) -> Currency {
+    Currency::parse(code).unwrap()
+}
+
+fn rates() -> Rates {
+    Rates::new([
+        (currency("EUR"), Currency::USD, Decimal::new(108, 2)),
+        (currency("EUR"), currency("JPY"), Decimal::new(160, 0)),
+    ])
+}
+
+fn clothes_item(price: Decimal, currency: &str, stock_quantity: i32) -> clothes::Model {
+    clothes::Model {
+        price,
+        in_stock: stock_quantity > 0,
+        stock_quantity,
+        currency: currency.to_string(),
+        ..support::garment(1)
+    }
+}
+
+#[test]
+fn converts_directly_inversely_and_across() {
+    let rates = rates();
+
+    assert_eq!(
+        rates.rate(currency("EUR"), Currency::USD),
+        Some(Decimal::new(108, 2))
+    );
+    assert_eq!(
+        rates.convert(
+            Money::new(Decimal::from(160), currency("JPY")),
+            currency("EUR")
+        ),
+        Some(Money::new(Decimal::ONE, currency("EUR")))
+    );
+    assert_eq!(
+        rates
+            .convert(
+                Money::new(Decimal::from(10), Currency::USD),
+                currency("JPY")
+            )
+            .map(|money| money.to_string()),
+        Some("1481 JPY".to_string())
+    );
+    assert_eq!(rates.rate(currency("GBP"), Currency::USD), None);
+}
+
+#[test]
+fn totals_stock_in_the_home_currency() {
+    let clothes = [
+        clothes_item(Decimal::new(5000, 2), "EUR", 2),
+        clothes_item(Decimal::new(2000, 2), "USD", 1),
+        clothes_item(Decimal::new(9000, 2), "GBP", 1),
+        clothes_item(Decimal::new(1000, 2), "USD", 0),
+    ];
+
+    let totals = WardrobeTotals::new(&clothes, Currency::USD, &rates());
+
+    assert_eq!(totals.total.to_string(), "128.00 USD");
+    assert_eq!(totals.by_currency.len(), 3);
+    assert_eq!(totals.unconverted, vec![currency("GBP")]);
+}
+
+#[test]
+fn leaves_sums_too_large_out_of_the_total() {
+    let clothes = [
+        clothes_item(Decimal::MAX, "USD", 2),
+        clothes_item(Decimal::new(5000, 2), "EUR", 1),
+    ];
+
+    let totals = WardrobeTotals::new(&clothes, Currency::USD, &rates());
+
+    assert_eq!(totals.total.to_string(), "54.00 USD");
+    assert_eq!(totals.unconverted, vec![Currency::USD]);
+}
+
+#[test]
+fn parses_rate_files() {
+    let csv = "base,quote,rate\nEUR,USD,1.0842\neur,jpy,161.5\n";
+    let rates = exchange_rates::parse(ImportFormat::Csv, csv).unwrap();
+    assert_eq!(rates.len(), 2);
+    assert_eq!(rates[1].quote, currency("JPY"));
+
+    let json = r#"[{"base": "GBP", "quote": "EUR", "rate": 1.17}]"#;
+    let rates = exchange_rates::parse(ImportFormat::Json, json).unwrap();
+    assert_eq!(rates[0].rate(), Some(Decimal::new(117, 2)));
+}
+
+#[test]
+fn rejects_invalid_rates_by_row() {
+    let csv = "base,quote,rate\nEUR,USD,1.08\nEUR,EUR,1\n";
+    let error = exchange_rates::parse(ImportFormat::Csv, csv).unwrap_err();
+    assert!(error.to_string().contains("row 2"));
+
+    let csv = "base,quote,rate\nEUR,USD,-1\n";
+    assert!(exchange_rates::parse(ImportFormat::Csv, csv).is_err());
+    let csv = "base,quote,rate\nEUR,XXY,1\n";
+    assert!(exchange_rates::parse(ImportFormat::Csv, csv).is_err());
+}
+
+#[test]
+fn keeps_the_last_rate_of_a_repeated_pair() {
+    let csv = "base,quote,rate\nEUR,USD,1.08\nEUR,JPY,160\nEUR,USD,1.09\n";
+    let rates = exchange_rates::parse(ImportFormat::Csv, csv).unwrap();
+
+    let latest = exchange_rates::latest_per_pair(&rates);
+    assert_eq!(latest.len(), 2);
+    let usd = latest.iter().find(|rate| rate.quote == Currency::USD).unwrap();
+    assert_eq!(usd.rate(), Some(Decimal::new(109, 2)));
+}
+
+#[tokio::test]
+#[serial]
+async fn stores_a_rates_file_that_repeats_a_pair() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let csv = "base,quote,rate\nEUR,USD,1.08\nEUR,USD,1.09\n";
+    let rates = exchange_rates::parse(ImportFormat::Csv, csv).unwrap();
+
+    let stored = exchange_rates::Model::store(db, &rates, "rates.csv")
+        .await
+        .unwrap();
+    assert_eq!(stored, 1);
+    let rates = exchange_rates::Model::list(db).await.unwrap();
+    assert_eq!(rates.len(), 1);
+    assert_eq!(rates[0].rate, Decimal::new(109, 2));
+}
+
+#[tokio::test]
+#[serial]
+async fn totals_only_the_clothes_in_the_users_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, clothes_item(Decimal::new(3000, 2), "USD", 2)).await;
+    support::insert_garment(db, clothes_item(Decimal::new(9000, 2), "USD", 1)).await;
+    support::insert_coordinate(db, &[shirt.id]).await;
+
+    let totals = WardrobeTotals::for_user(db, 1, Some(Currency::USD))
+        .await
+        .unwrap();
+    assert_eq!(totals.total.to_string(), "60.00 USD");
+    let other = WardrobeTotals::for_user(db, 2, Some(Currency::USD))
+        .await
+        .unwrap();
+    assert_eq!(other.total.to_string(), "0.00 USD");
+}
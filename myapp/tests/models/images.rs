@@ -0,0 +1,45 @@
+use myapp::common::{images::ImageFetcher, settings::ImageSettings};
+
+#[test]
+fn fetches_remote_images_only_from_allowed_hosts() {
+    let fetcher = ImageFetcher::new(&ImageSettings {
+        allowed_hosts: vec!["Images.Example.com".to_string()],
+        ..ImageSettings::default()
+    });
+
+    assert!(
+        fetcher
+            .allowed_url("https://images.example.com/shirt.png")
+            .is_ok()
+    );
+    assert!(
+        fetcher
+            .allowed_url("http://IMAGES.example.com/shirt.png")
+            .is_ok()
+    );
+    assert_eq!(
+        fetcher.allowed_url("http://169.254.169.254/latest/meta-data"),
+        Err("images from '169.254.169.254' are not allowed".to_string())
+    );
+    assert!(fetcher.allowed_url("file:///etc/passwd").is_err());
+    assert!(
+        ImageFetcher::new(&ImageSettings::default())
+            .allowed_url("https://images.example.com/shirt.png")
+            .is_err()
+    );
+}
+
+#[test]
+fn reads_stored_images_only_from_the_outfit_image_prefix() {
+    assert_eq!(
+        ImageFetcher::allowed_storage_key("/coordinates/0f8e/outfit.png"),
+        Ok("coordinates/0f8e/outfit.png")
+    );
+    assert_eq!(
+        ImageFetcher::allowed_storage_key("exports/0f8e.zip"),
+        Err("images can only be read from 'coordinates/' in storage".to_string())
+    );
+    assert!(ImageFetcher::allowed_storage_key("receipts/0f8e.pdf").is_err());
+    assert!(ImageFetcher::allowed_storage_key("coordinates/../exports/0f8e.zip").is_err());
+    assert!(ImageFetcher::allowed_storage_key("coordinates/..\\exports/0f8e.zip").is_err());
+}
@@ -0,0 +1,57 @@
+use myapp::models::inventory_movements::{MovementParams, MovementReason};
+
+fn movement(reason: MovementReason, quantity: i32) -> MovementParams {
+    MovementParams {
+        reason,
+        quantity,
+        note: None,
+    }
+}
+
+#[test]
+fn reasons_round_trip_through_their_names() {
+    for reason in [
+        MovementReason::Purchase,
+        MovementReason::Lost,
+        MovementReason::Donated,
+        MovementReason::Adjustment,
+    ] {
+        assert_eq!(MovementReason::parse(reason.as_str()), Some(reason));
+    }
+    assert_eq!(MovementReason::parse("stolen"), None);
+}
+
+#[test]
+fn signs_the_stock_change_by_reason() {
+    assert_eq!(MovementReason::Purchase.change(2), 2);
+    assert_eq!(MovementReason::Lost.change(1), -1);
+    assert_eq!(MovementReason::Donated.change(3), -3);
+    assert_eq!(MovementReason::Adjustment.change(-2), -2);
+}
+
+#[test]
+fn accepts_movements_within_the_stock() {
+    assert!(movement(MovementReason::Purchase, 1).problems(0).is_empty());
+    assert!(movement(MovementReason::Donated, 2).problems(2).is_empty());
+    assert!(movement(MovementReason::Adjustment, -1).problems(1).is_empty());
+}
+
+#[test]
+fn rejects_empty_and_negative_movements() {
+    let empty = movement(MovementReason::Purchase, 0).problems(1);
+    assert_eq!(empty.len(), 1);
+    assert_eq!(empty[0].message, "must not be zero");
+
+    let negative = movement(MovementReason::Lost, -1).problems(1);
+    assert_eq!(negative.len(), 1);
+    assert_eq!(negative[0].field, "quantity");
+}
+
+#[test]
+fn rejects_taking_more_than_the_stock() {
+    let problems = movement(MovementReason::Lost, 3).problems(2);
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].message, "exceeds the 2 in stock");
+    assert!(!movement(MovementReason::Adjustment, -3).problems(2).is_empty());
+}
@@ -0,0 +1,91 @@
+use myapp::{
+    data::lifecycle::{Condition, LifecycleState},
+    models::{_entities::clothes, clothes_state_changes::TransitionParams},
+};
+
+use super::support;
+
+fn clothes_item(lifecycle_state: LifecycleState, lent_to: Option<&str>) -> clothes::Model {
+    clothes::Model {
+        lent_to: lent_to.map(str::to_string),
+        lifecycle_state: lifecycle_state.as_str().to_string(),
+        ..support::garment(1)
+    }
+}
+
+fn transition(state: LifecycleState, lent_to: Option<&str>) -> TransitionParams {
+    TransitionParams {
+        state,
+        condition: None,
+        note: None,
+        lent_to: lent_to.map(str::to_string),
+    }
+}
+
+#[test]
+fn states_round_trip_through_their_names() {
+    for state in LifecycleState::ALL {
+        assert_eq!(LifecycleState::parse(state.as_str()), Some(state));
+    }
+    assert_eq!(LifecycleState::parse("washing"), None);
+    assert_eq!(Condition::parse("like_new"), Some(Condition::LikeNew));
+}
+
+#[test]
+fn only_active_garments_are_available() {
+    for state in LifecycleState::ALL {
+        assert_eq!(state.is_available(), state == LifecycleState::Active);
+    }
+}
+
+#[test]
+fn validates_transitions() {
+    assert!(LifecycleState::Active.can_become(LifecycleState::InLaundry));
+    assert!(LifecycleState::InLaundry.can_become(LifecycleState::Active));
+    assert!(LifecycleState::Stored.can_become(LifecycleState::Donated));
+
+    assert!(!LifecycleState::Active.can_become(LifecycleState::Active));
+    assert!(!LifecycleState::InLaundry.can_become(LifecycleState::Sold));
+    assert!(!LifecycleState::Sold.can_become(LifecycleState::Active));
+    assert!(!LifecycleState::Donated.can_become(LifecycleState::Stored));
+}
+
+#[test]
+fn rejects_forbidden_transition() {
+    let problems = transition(LifecycleState::Active, None).problems(LifecycleState::Sold);
+
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].field, "state");
+    assert_eq!(problems[0].message, "cannot change from sold to active");
+}
+
+#[test]
+fn lending_requires_a_borrower() {
+    let missing = transition(LifecycleState::Lent, Some("  ")).problems(LifecycleState::Active);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].field, "lent_to");
+
+    let lent = transition(LifecycleState::Lent, Some("Sam")).problems(LifecycleState::Active);
+    assert!(lent.is_empty());
+
+    let misplaced =
+        transition(LifecycleState::Stored, Some("Sam")).problems(LifecycleState::Active);
+    assert_eq!(misplaced.len(), 1);
+    assert_eq!(misplaced[0].field, "lent_to");
+}
+
+#[test]
+fn describes_why_a_garment_is_unavailable() {
+    assert_eq!(
+        clothes_item(LifecycleState::Active, None).unavailable_reason(),
+        None
+    );
+    assert_eq!(
+        clothes_item(LifecycleState::Lent, Some("Sam")).unavailable_reason(),
+        Some("lent to Sam".to_string())
+    );
+    assert_eq!(
+        clothes_item(LifecycleState::AtCleaner, None).unavailable_reason(),
+        Some("at the cleaner".to_string())
+    );
+}
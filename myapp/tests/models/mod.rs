@@ -1,3 +1,30 @@
+mod calendar_feeds;
+mod capsules;
+mod clothes;
+mod clothes_coordinates;
+mod clothes_imports;
+mod colors;
+mod coordinate_rules;
+mod coordinate_versions;
+mod coordinates;
+mod exchange_rates;
+mod images;
+mod inventory_movements;
+mod lifecycle;
+mod money;
+mod outfit_feedback;
+mod outfit_images;
+mod outfit_plans;
+mod purchases;
+mod sizes;
+mod suggestions;
+mod support;
+mod trash;
+mod trips;
 mod users;
+mod vocabularies;
+mod wardrobe_exports;
+mod weather;
+mod wear_events;
 
 mod passkeys;
\ No newline at end of file
@@ -0,0 +1,71 @@
+use myapp::data::money::{Currency, Money};
+use sea_orm::prelude::Decimal;
+
+fn currency(code: &str) -> Currency {
+    Currency::parse(code).unwrap()
+}
+
+#[test]
+fn looks_up_iso_codes() {
+    assert_eq!(currency(" eur ").code(), "EUR");
+    assert_eq!(currency("JPY").minor_units(), 0);
+    assert_eq!(currency("KWD").minor_units(), 3);
+    assert_eq!(Currency::default(), Currency::USD);
+    assert_eq!(Currency::parse("EURO"), None);
+    assert_eq!(Currency::parse("XYZ"), None);
+}
+
+#[test]
+fn parses_decimal_strings_exactly() {
+    let price = Money::parse("12.5", currency("EUR")).unwrap();
+    assert_eq!(price.amount, Decimal::new(1250, 2));
+    assert_eq!(price.to_string(), "12.50 EUR");
+
+    let yen = Money::parse("3000", currency("JPY")).unwrap();
+    assert_eq!(yen.to_string(), "3000 JPY");
+    assert_eq!(
+        Money::parse("0.1", Currency::USD).unwrap().amount,
+        Decimal::new(1, 1)
+    );
+}
+
+#[test]
+fn rejects_malformed_amounts() {
+    for text in ["", "abc", "-5", "+5", "1e3", "1,000", "12.", ".5", "NaN"] {
+        assert!(Money::parse(text, Currency::USD).is_err(), "{text}");
+    }
+    assert_eq!(
+        Money::parse("12.345", Currency::USD),
+        Err("'12.345' has more decimals than USD allows (2)".to_string())
+    );
+    assert!(Money::parse("100.5", currency("JPY")).is_err());
+}
+
+#[test]
+fn serializes_amount_as_string() {
+    let price = Money::new(Decimal::new(499, 1), currency("EUR"));
+
+    let json = serde_json::to_value(price).unwrap();
+
+    assert_eq!(
+        json,
+        serde_json::json!({"amount": "49.90", "currency": "EUR"})
+    );
+    assert_eq!(serde_json::from_value::<Money>(json).unwrap(), price);
+}
+
+#[test]
+fn converts_and_rounds_to_target_currency() {
+    let price = Money::parse("10.00", Currency::USD).unwrap();
+
+    let yen = price
+        .convert(currency("JPY"), Decimal::new(14_955, 2))
+        .unwrap();
+
+    assert_eq!(yen.to_string(), "1496 JPY");
+    assert!(
+        Money::new(Decimal::MAX, Currency::USD)
+            .convert(currency("JPY"), Decimal::TEN)
+            .is_none()
+    );
+}
@@ -0,0 +1,163 @@
+use chrono::{NaiveDate, Utc};
+use myapp::models::{
+    _entities::{clothes, coordinates, outfit_feedback},
+    coordinate_rules::Completeness,
+    coordinates::CoordinateWithClothes,
+    outfit_feedback::{CreateFeedbackParams, FeedbackRatings, RatingSummary},
+    suggestions::{HarmonyScorer, OutfitScorer, SuggestionContext},
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+use super::support;
+
+fn garment(id: i32, category: &str) -> clothes::Model {
+    clothes::Model {
+        name: format!("{category} {id}"),
+        category: category.to_string(),
+        color: "black".to_string(),
+        ..support::garment(id)
+    }
+}
+
+fn feedback(
+    comfort: Option<i16>,
+    weather_fit: Option<i16>,
+    compliments: Option<i16>,
+) -> outfit_feedback::Model {
+    let now = Utc::now().into();
+    outfit_feedback::Model {
+        id: 1,
+        pid: Uuid::new_v4(),
+        user_id: 1,
+        coordinate_id: 1,
+        wear_event_id: None,
+        worn_on: NaiveDate::from_ymd_opt(2025, 6, 1).unwrap(),
+        comfort,
+        weather_fit,
+        compliments,
+        note: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn rated(id: i32, clothes: Vec<clothes::Model>, ratings: RatingSummary) -> CoordinateWithClothes {
+    let now = Utc::now().into();
+    CoordinateWithClothes {
+        coordinate: coordinates::Model {
+            id,
+            pid: Uuid::new_v4(),
+            name: format!("Outfit {id}"),
+            description: None,
+            occasion: None,
+            style: None,
+            seasons: json!([]),
+            user_id: 1,
+            is_favorite: false,
+            image_url: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        },
+        clothes,
+        placements: Vec::new(),
+        completeness: Completeness {
+            score: 1.0,
+            missing_slots: Vec::new(),
+        },
+        ratings,
+        removed_clothes: Vec::new(),
+    }
+}
+
+fn params(ratings: FeedbackRatings) -> CreateFeedbackParams {
+    CreateFeedbackParams {
+        user_id: 1,
+        coordinate_pid: Uuid::new_v4().to_string(),
+        wear_event_pid: None,
+        worn_on: None,
+        ratings,
+    }
+}
+
+#[test]
+fn feedback_needs_ratings_within_range_or_a_note() {
+    let valid = params(FeedbackRatings {
+        comfort: Some(5),
+        ..FeedbackRatings::default()
+    });
+    assert!(valid.problems().is_empty());
+
+    let note_only = params(FeedbackRatings {
+        note: Some("Too warm by noon".to_string()),
+        ..FeedbackRatings::default()
+    });
+    assert!(note_only.problems().is_empty());
+
+    let empty = params(FeedbackRatings {
+        note: Some("  ".to_string()),
+        ..FeedbackRatings::default()
+    });
+    let fields: Vec<String> = empty.problems().into_iter().map(|e| e.field).collect();
+    assert_eq!(fields, vec!["ratings"]);
+
+    let out_of_range = params(FeedbackRatings {
+        comfort: Some(0),
+        weather_fit: Some(3),
+        compliments: Some(6),
+        note: None,
+    });
+    let fields: Vec<String> = out_of_range
+        .problems()
+        .into_iter()
+        .map(|e| e.field)
+        .collect();
+    assert_eq!(fields, vec!["comfort", "compliments"]);
+}
+
+#[test]
+fn summary_averages_each_rating_and_overall() {
+    let feedback = [
+        feedback(Some(4), Some(5), None),
+        feedback(Some(3), None, Some(2)),
+        feedback(Some(4), Some(2), None),
+    ];
+    let summary = RatingSummary::from_feedback(&feedback);
+
+    assert_eq!(summary.count, 3);
+    assert_eq!(summary.comfort, Some(3.67));
+    assert_eq!(summary.weather_fit, Some(3.5));
+    assert_eq!(summary.compliments, Some(2.0));
+    assert_eq!(summary.overall, Some(3.33));
+
+    assert_eq!(RatingSummary::from_feedback(&[]), RatingSummary::default());
+}
+
+#[test]
+fn well_rated_garments_rank_higher() {
+    let loved = garment(1, "shirt");
+    let disliked = garment(2, "shirt");
+    let summary = |overall: f64| RatingSummary {
+        count: 2,
+        overall: Some(overall),
+        ..RatingSummary::default()
+    };
+    let coordinates = [
+        rated(1, vec![loved.clone()], summary(4.5)),
+        rated(2, vec![disliked.clone()], summary(1.5)),
+    ];
+    let context = SuggestionContext::new(None, None, &coordinates, &[]);
+    assert_eq!(context.ratings.get(&1), Some(&4.5));
+    assert_eq!(context.ratings.get(&2), Some(&1.5));
+
+    let scorer = HarmonyScorer::default();
+    let high = scorer.score(&[&loved], &context);
+    let low = scorer.score(&[&disliked], &context);
+    assert!(high.value > low.value);
+    assert!(high.reasons.iter().any(|r| r.starts_with("Rated well")));
+    assert!(low.reasons.iter().any(|r| r.starts_with("Rated poorly")));
+
+    let unrated = scorer.score(&[&garment(3, "shirt")], &context);
+    assert!(!unrated.reasons.iter().any(|r| r.starts_with("Rated")));
+}
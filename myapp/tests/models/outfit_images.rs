@@ -0,0 +1,190 @@
+use std::io::Cursor;
+
+use chrono::Utc;
+use image::{ImageFormat, Rgba, RgbaImage};
+use myapp::{
+    common::settings::OutfitImageSettings,
+    data::slots::Slot,
+    models::{
+        _entities::coordinates,
+        clothes_coordinates::ClothesLayout,
+        outfit_images::{self, OutfitCanvas, OutfitImageFormat, OutfitImageItem},
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+const RED: [u8; 3] = [220, 20, 20];
+const BLUE: [u8; 3] = [20, 20, 220];
+
+fn canvas() -> OutfitCanvas {
+    OutfitCanvas {
+        width: 200,
+        height: 100,
+        background: [255, 255, 255],
+    }
+}
+
+fn garment(color: [u8; 3], layout: ClothesLayout) -> OutfitImageItem {
+    let [r, g, b] = color;
+    let mut data = Vec::new();
+    RgbaImage::from_pixel(40, 40, Rgba([r, g, b, 255]))
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .unwrap();
+    OutfitImageItem { layout, data }
+}
+
+fn in_slot(slot: Slot) -> ClothesLayout {
+    ClothesLayout {
+        slot: Some(slot),
+        ..Default::default()
+    }
+}
+
+fn at(x: f64, y: f64, z_index: i32) -> ClothesLayout {
+    ClothesLayout {
+        x: Some(x),
+        y: Some(y),
+        z_index: Some(z_index),
+        ..Default::default()
+    }
+}
+
+fn pixel(data: &[u8], x: u32, y: u32) -> [u8; 3] {
+    image::load_from_memory(data)
+        .unwrap()
+        .to_rgb8()
+        .get_pixel(x, y)
+        .0
+}
+
+#[test]
+fn garments_without_layout_are_arranged_in_a_grid_by_slot() {
+    let placements = canvas().placements(&[
+        in_slot(Slot::Shoes),
+        ClothesLayout::default(),
+        in_slot(Slot::Top),
+    ]);
+
+    // Two columns and two rows: top, shoes, then the garment without a slot
+    let centers: Vec<_> = placements
+        .iter()
+        .map(|placement| (placement.center_x, placement.center_y))
+        .collect();
+    assert_eq!(centers, vec![(150.0, 25.0), (50.0, 75.0), (50.0, 25.0)]);
+    assert!((placements[0].width - 90.0).abs() < 1e-9);
+}
+
+#[test]
+fn canvas_layout_is_relative_to_the_canvas_size() {
+    let layout = ClothesLayout {
+        scale: Some(1.5),
+        rotation: Some(15.0),
+        ..at(0.5, 0.25, 0)
+    };
+
+    let placement = canvas().placements(&[layout])[0];
+
+    assert!((placement.center_x - 100.0).abs() < 1e-9);
+    assert!((placement.center_y - 25.0).abs() < 1e-9);
+    assert!((placement.width - 100.0).abs() < 1e-9);
+    assert!((placement.height - 50.0).abs() < 1e-9);
+    assert!((placement.rotation - 15.0).abs() < 1e-9);
+}
+
+#[test]
+fn renders_garments_on_the_background() {
+    let items = [
+        garment(RED, in_slot(Slot::Top)),
+        garment(BLUE, in_slot(Slot::Bottom)),
+    ];
+
+    let data = canvas().render(&items, OutfitImageFormat::Png).unwrap();
+
+    assert!(data.starts_with(b"\x89PNG"));
+    let image = image::load_from_memory(&data).unwrap();
+    assert_eq!((image.width(), image.height()), (200, 100));
+    assert_eq!(pixel(&data, 50, 50), RED);
+    assert_eq!(pixel(&data, 150, 50), BLUE);
+    assert_eq!(pixel(&data, 100, 2), [255, 255, 255]);
+}
+
+#[test]
+fn higher_z_index_is_drawn_on_top() {
+    let items = [
+        garment(RED, at(0.5, 0.5, 2)),
+        garment(BLUE, at(0.5, 0.5, 1)),
+    ];
+
+    let data = canvas().render(&items, OutfitImageFormat::Png).unwrap();
+
+    assert_eq!(pixel(&data, 100, 50), RED);
+}
+
+#[test]
+fn undecodable_images_are_left_out() {
+    let broken = OutfitImageItem {
+        layout: ClothesLayout::default(),
+        data: b"not an image".to_vec(),
+    };
+
+    let data = canvas()
+        .render(
+            &[broken.clone(), garment(RED, in_slot(Slot::Top))],
+            OutfitImageFormat::Webp,
+        )
+        .unwrap();
+    assert_eq!(&data[..4], b"RIFF");
+    assert_eq!(&data[8..12], b"WEBP");
+
+    assert!(canvas().render(&[broken], OutfitImageFormat::Png).is_err());
+}
+
+#[test]
+fn canvas_settings_are_validated() {
+    let settings = OutfitImageSettings {
+        background: "#FFFFFF".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        OutfitCanvas::from_settings(&settings).unwrap().background,
+        [255, 255, 255]
+    );
+
+    let settings = OutfitImageSettings {
+        background: "white-ish".to_string(),
+        ..Default::default()
+    };
+    assert!(OutfitCanvas::from_settings(&settings).is_err());
+}
+
+#[test]
+fn only_rendered_images_are_replaced() {
+    let now = Utc::now().into();
+    let pid = Uuid::new_v4();
+    let mut coordinate = coordinates::Model {
+        id: 1,
+        pid,
+        name: "Weekend".to_string(),
+        description: None,
+        occasion: None,
+        style: None,
+        seasons: json!([]),
+        user_id: 1,
+        is_favorite: false,
+        image_url: Some(outfit_images::storage_path(&pid, OutfitImageFormat::Webp)),
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    };
+    assert_eq!(
+        coordinate.image_url.as_deref(),
+        Some(format!("coordinates/{pid}/outfit.webp").as_str())
+    );
+    assert!(outfit_images::is_rendered(&coordinate));
+
+    coordinate.image_url = Some("https://example.com/my-outfit.png".to_string());
+    assert!(!outfit_images::is_rendered(&coordinate));
+    coordinate.image_url = Some(format!("coordinates/{}/outfit.png", Uuid::new_v4()));
+    assert!(!outfit_images::is_rendered(&coordinate));
+}
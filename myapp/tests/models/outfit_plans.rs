@@ -0,0 +1,233 @@
+use chrono::{NaiveDate, Utc};
+use myapp::models::{
+    _entities::{clothes, coordinates, outfit_plans},
+    coordinate_rules::Completeness,
+    coordinates::CoordinateWithClothes,
+    outfit_feedback::RatingSummary,
+    outfit_plans::{
+        CalendarQuery, CalendarView, CreatePlanParams, Model, PlannedOutfit, find_conflicts,
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+use serial_test::serial;
+
+use super::support;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn clothes_item(id: i32, name: &str) -> clothes::Model {
+    clothes::Model {
+        name: name.to_string(),
+        ..support::garment(id)
+    }
+}
+
+fn planned(id: i32, planned_on: NaiveDate, clothes: Vec<clothes::Model>) -> PlannedOutfit {
+    let now = Utc::now().into();
+    let coordinate = coordinates::Model {
+        id,
+        pid: Uuid::new_v4(),
+        name: format!("Outfit {id}"),
+        description: None,
+        occasion: None,
+        style: None,
+        seasons: json!([]),
+        user_id: 1,
+        is_favorite: false,
+        image_url: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    };
+    PlannedOutfit {
+        plan: outfit_plans::Model {
+            created_at: now,
+            updated_at: now,
+            id,
+            pid: Uuid::new_v4(),
+            planned_on,
+            occasion: None,
+            note: None,
+            done_at: None,
+            user_id: 1,
+            coordinate_id: id,
+            trip_id: None,
+        },
+        coordinate: CoordinateWithClothes {
+            coordinate,
+            clothes,
+            placements: Vec::new(),
+            completeness: Completeness {
+                score: 1.0,
+                missing_slots: Vec::new(),
+            },
+            ratings: RatingSummary::default(),
+            removed_clothes: Vec::new(),
+        },
+    }
+}
+
+#[test]
+fn week_view_starts_on_monday() {
+    let query = CalendarQuery {
+        view: Some(CalendarView::Week),
+        date: Some(date(2025, 6, 19)),
+        ..Default::default()
+    };
+    assert_eq!(
+        query.range(date(2025, 1, 1)).unwrap(),
+        (date(2025, 6, 16), date(2025, 6, 22))
+    );
+}
+
+#[test]
+fn month_view_defaults_to_current_month() {
+    let query = CalendarQuery::default();
+    assert_eq!(
+        query.range(date(2024, 2, 10)).unwrap(),
+        (date(2024, 2, 1), date(2024, 2, 29))
+    );
+}
+
+#[test]
+fn explicit_range_must_be_ordered() {
+    let query = CalendarQuery {
+        from: Some(date(2025, 6, 10)),
+        to: Some(date(2025, 6, 1)),
+        ..Default::default()
+    };
+    assert!(query.range(date(2025, 6, 1)).is_err());
+}
+
+#[test]
+fn detects_garments_planned_twice_on_one_day() {
+    let shirt = clothes_item(1, "Oxford Shirt");
+    let plans = vec![
+        planned(
+            1,
+            date(2025, 6, 16),
+            vec![shirt.clone(), clothes_item(2, "Chinos")],
+        ),
+        planned(
+            2,
+            date(2025, 6, 16),
+            vec![shirt.clone(), clothes_item(3, "Jeans")],
+        ),
+        planned(3, date(2025, 6, 17), vec![shirt]),
+    ];
+
+    let conflicts = find_conflicts(&plans);
+
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].clothes_name, "Oxford Shirt");
+    assert_eq!(conflicts[0].date, date(2025, 6, 16));
+    assert_eq!(
+        conflicts[0].plans,
+        vec![plans[0].plan.pid, plans[1].plan.pid]
+    );
+}
+
+#[test]
+fn upcoming_plans_drop_unavailable_garments() {
+    let mut laundry = clothes_item(2, "Chinos");
+    laundry.lifecycle_state = "in_laundry".to_string();
+    let upcoming = planned(
+        1,
+        date(2025, 6, 16),
+        vec![clothes_item(1, "Oxford Shirt"), laundry.clone()],
+    );
+    let mut worn = planned(
+        2,
+        date(2025, 6, 15),
+        vec![clothes_item(1, "Oxford Shirt"), laundry],
+    );
+    worn.plan.done_at = Some(Utc::now().into());
+
+    let upcoming = upcoming.without_unavailable();
+    let worn = worn.without_unavailable();
+
+    assert_eq!(upcoming.coordinate.clothes.len(), 1);
+    assert_eq!(upcoming.coordinate.clothes[0].name, "Oxford Shirt");
+    assert_eq!(worn.coordinate.clothes.len(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn marks_a_plan_done_only_once() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    let coordinate = support::insert_coordinate(db, &[shirt.id]).await;
+    let plan = plan(db, &coordinate).await;
+
+    let (done, worn) = plan.clone().mark_done(db).await.unwrap().unwrap();
+    assert!(done.done_at.is_some());
+    assert_eq!(worn.len(), 1);
+    assert_eq!(worn[0].worn_on, date(2025, 6, 2));
+
+    // A second request that loaded the plan before it was done
+    assert!(plan.mark_done(db).await.unwrap().is_none());
+}
+
+/// Plan the first fixture user's `coordinate` on 2 June 2025
+async fn plan(db: &sea_orm::DatabaseConnection, coordinate: &coordinates::Model) -> Model {
+    Model::create(
+        db,
+        &CreatePlanParams {
+            user_id: 1,
+            coordinate_pid: coordinate.pid.to_string(),
+            planned_on: date(2025, 6, 2),
+            occasion: None,
+            note: None,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn marking_a_plan_done_logs_only_available_garments() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    let chinos = support::insert_garment(
+        db,
+        clothes::Model {
+            name: "Chinos".to_string(),
+            lifecycle_state: "in_laundry".to_string(),
+            ..support::garment(0)
+        },
+    )
+    .await;
+    let coordinate = support::insert_coordinate(db, &[shirt.id, chinos.id]).await;
+
+    let (_, worn) = plan(db, &coordinate)
+        .await
+        .mark_done(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(worn.len(), 1);
+    assert_eq!(worn[0].clothes_id, shirt.id);
+}
+
+#[tokio::test]
+#[serial]
+async fn marks_a_plan_of_an_empty_coordinate_done() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let coordinate = support::insert_coordinate(db, &[]).await;
+
+    let (done, worn) = plan(db, &coordinate)
+        .await
+        .mark_done(db)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(done.done_at.is_some());
+    assert!(worn.is_empty());
+}
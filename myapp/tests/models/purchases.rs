@@ -0,0 +1,250 @@
+use chrono::{NaiveDate, Utc};
+use myapp::{
+    data::money::{Currency, Money},
+    models::{
+        _entities::{budgets, clothes},
+        budgets::BudgetParams,
+        exchange_rates::Rates,
+        purchases::{self, Purchase, ReceiptType, SpendingReport},
+    },
+};
+use sea_orm::prelude::{Decimal, Uuid};
+use serial_test::serial;
+
+use super::support;
+
+fn currency(code: &str) -> Currency {
+    Currency::parse(code).unwrap()
+}
+
+fn rates() -> Rates {
+    Rates::new([(currency("EUR"), Currency::USD, Decimal::new(110, 2))])
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn purchase(
+    brand: &str,
+    category: &str,
+    price: Decimal,
+    currency: &str,
+    bought_on: NaiveDate,
+    store: Option<&str>,
+) -> Purchase {
+    Purchase {
+        clothes: clothes::Model {
+            brand: brand.to_string(),
+            category: category.to_string(),
+            price,
+            currency: currency.to_string(),
+            purchase_date: Some(bought_on),
+            store: store.map(ToString::to_string),
+            ..support::garment(1)
+        },
+        units: 1,
+    }
+}
+
+fn budget(category: Option<&str>, amount: Decimal, currency: &str) -> budgets::Model {
+    let now = Utc::now().into();
+    budgets::Model {
+        id: 1,
+        pid: Uuid::new_v4(),
+        user_id: 1,
+        category: category.map(ToString::to_string),
+        amount,
+        currency: currency.to_string(),
+        alerted_for: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+#[test]
+fn groups_spending_by_month_brand_category_and_store() {
+    let purchases = [
+        purchase(
+            "Acme",
+            "tops",
+            Decimal::new(5000, 2),
+            "USD",
+            date(2025, 5, 30),
+            Some("Outlet"),
+        ),
+        purchase(
+            "Acme",
+            "tops",
+            Decimal::new(2000, 2),
+            "EUR",
+            date(2025, 6, 2),
+            None,
+        ),
+        purchase(
+            "Zenith",
+            "shoes",
+            Decimal::new(12000, 2),
+            "USD",
+            date(2025, 6, 14),
+            Some("Web"),
+        ),
+        purchase(
+            "Acme",
+            "tops",
+            Decimal::new(3000, 2),
+            "GBP",
+            date(2025, 6, 20),
+            None,
+        ),
+    ];
+
+    let report = SpendingReport::new(&purchases, Currency::USD, &rates(), &[]);
+
+    assert_eq!(report.purchases, 4);
+    assert_eq!(report.total.to_string(), "192.00 USD");
+    assert_eq!(report.unconverted, vec![currency("GBP")]);
+    let keys = |groups: &[purchases::SpendingGroup]| -> Vec<String> {
+        groups.iter().map(|group| group.key.clone()).collect()
+    };
+    assert_eq!(keys(&report.by_month), ["2025-05", "2025-06"]);
+    assert_eq!(report.by_month[1].total.to_string(), "142.00 USD");
+    assert_eq!(keys(&report.by_brand), ["Zenith", "Acme"]);
+    assert_eq!(keys(&report.by_category), ["shoes", "tops"]);
+    assert_eq!(keys(&report.by_store), ["Web", "Outlet", "unknown"]);
+    assert_eq!(report.by_store[2].total.to_string(), "22.00 USD");
+}
+
+#[test]
+fn checks_budgets_per_month_and_category() {
+    let purchases = [
+        purchase(
+            "Acme",
+            "tops",
+            Decimal::new(9000, 2),
+            "USD",
+            date(2025, 6, 2),
+            None,
+        ),
+        purchase(
+            "Acme",
+            "Tops",
+            Decimal::new(5000, 2),
+            "EUR",
+            date(2025, 6, 9),
+            None,
+        ),
+        purchase(
+            "Zenith",
+            "shoes",
+            Decimal::new(8000, 2),
+            "USD",
+            date(2025, 6, 14),
+            None,
+        ),
+        purchase(
+            "Acme",
+            "tops",
+            Decimal::new(9000, 2),
+            "USD",
+            date(2025, 7, 1),
+            None,
+        ),
+    ];
+    let tops = budget(Some("tops"), Decimal::new(12000, 2), "USD");
+    let overall = budget(None, Decimal::new(50000, 2), "USD");
+
+    let june = purchases::budget_status(&tops, date(2025, 6, 30), &purchases, &rates());
+    assert_eq!(june.month, date(2025, 6, 1));
+    assert_eq!(june.spent.to_string(), "145.00 USD");
+    assert!(june.exceeded);
+    assert_eq!(
+        june.message(),
+        "Spent 145.00 USD on tops in 2025-06, over the 120.00 USD budget"
+    );
+
+    let july = purchases::budget_status(&tops, date(2025, 7, 15), &purchases, &rates());
+    assert_eq!(july.spent.to_string(), "90.00 USD");
+    assert!(!july.exceeded);
+
+    let all = purchases::budget_status(&overall, date(2025, 6, 1), &purchases, &rates());
+    assert_eq!(all.spent.to_string(), "225.00 USD");
+    assert!(!all.exceeded);
+
+    let report = SpendingReport::new(&purchases, Currency::USD, &rates(), &[tops, overall]);
+    assert_eq!(report.budgets.len(), 4);
+    assert_eq!(
+        report
+            .budgets
+            .iter()
+            .filter(|status| status.exceeded)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn validates_budget_amounts() {
+    let params = |amount: &str| BudgetParams {
+        category: Some(" tops ".to_string()),
+        amount: amount.to_string(),
+        currency: None,
+    };
+
+    assert!(params("150.00").problems(currency("EUR")).is_empty());
+    assert_eq!(params("150").category().as_deref(), Some("tops"));
+    assert_eq!(
+        params("150").limit(currency("JPY")),
+        Ok(Money::new(Decimal::from(150), currency("JPY")))
+    );
+    assert_eq!(
+        params("0").problems(Currency::USD)[0].message,
+        "must be more than zero"
+    );
+    assert_eq!(params("12.5.0").problems(Currency::USD)[0].field, "amount");
+}
+
+#[test]
+fn accepts_receipt_types() {
+    assert_eq!(
+        ReceiptType::from_content_type("application/pdf"),
+        Some(ReceiptType::Pdf)
+    );
+    assert_eq!(
+        ReceiptType::from_content_type("Image/JPEG; charset=binary"),
+        Some(ReceiptType::Jpeg)
+    );
+    assert_eq!(ReceiptType::from_content_type("text/plain"), None);
+
+    let pid = Uuid::new_v4();
+    assert_eq!(
+        purchases::receipt_path(&pid, ReceiptType::Jpeg),
+        format!("receipts/{pid}.jpg")
+    );
+}
+
+#[test]
+fn spans_calendar_months() {
+    assert_eq!(purchases::month_start(date(2024, 2, 17)), date(2024, 2, 1));
+    assert_eq!(purchases::month_end(date(2024, 2, 17)), date(2024, 2, 29));
+    assert_eq!(purchases::month_end(date(2025, 12, 31)), date(2025, 12, 31));
+}
+
+#[tokio::test]
+#[serial]
+async fn finds_only_the_purchases_in_the_users_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let bought = clothes::Model {
+        purchase_date: Some(date(2025, 3, 2)),
+        ..support::garment(0)
+    };
+    let shirt = support::insert_garment(db, bought.clone()).await;
+    support::insert_garment(db, bought).await;
+    support::insert_coordinate(db, &[shirt.id]).await;
+
+    let mine = Purchase::find(db, 1, None, None).await.unwrap();
+    assert_eq!(mine.len(), 1);
+    assert_eq!(mine[0].clothes.pid, shirt.pid);
+    assert!(Purchase::find(db, 2, None, None).await.unwrap().is_empty());
+}
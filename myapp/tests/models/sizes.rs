@@ -0,0 +1,70 @@
+use myapp::data::sizes::{self, LetterSize, SizeGroup, SizeSystem};
+
+#[test]
+fn can_map_categories_to_size_groups() {
+    assert_eq!(SizeGroup::from_category("T-Shirts"), SizeGroup::Apparel);
+    assert_eq!(SizeGroup::from_category("Jeans"), SizeGroup::Bottoms);
+    assert_eq!(SizeGroup::from_category("スニーカー"), SizeGroup::Shoes);
+}
+
+#[test]
+fn maps_categories_by_whole_words() {
+    assert_eq!(SizeGroup::from_category("bootcut jeans"), SizeGroup::Bottoms);
+    assert_eq!(SizeGroup::from_category("short sleeve shirt"), SizeGroup::Apparel);
+    assert_eq!(SizeGroup::from_category("Shorts"), SizeGroup::Bottoms);
+    assert_eq!(SizeGroup::from_category("ankle boots"), SizeGroup::Shoes);
+    assert_eq!(SizeGroup::from_category("ブーツカットジーンズ"), SizeGroup::Bottoms);
+    assert_eq!(SizeGroup::from_category("ショートパンツ"), SizeGroup::Bottoms);
+    assert_eq!(SizeGroup::from_category("靴下"), SizeGroup::Apparel);
+}
+
+#[test]
+fn can_parse_apparel_sizes() {
+    for value in ["M", "m", "9号", "JP 9", "EU 40", "US 8", "UK 12", "40"] {
+        let size = sizes::parse(value, SizeGroup::Apparel).unwrap();
+        assert_eq!(size.letter, Some(LetterSize::M), "{value}");
+    }
+    assert_eq!(
+        sizes::parse("LL", SizeGroup::Apparel).unwrap().letter,
+        Some(LetterSize::Xl)
+    );
+    assert!(sizes::parse("FREE", SizeGroup::Apparel).is_none());
+}
+
+#[test]
+fn can_parse_shoe_sizes() {
+    let cm = sizes::parse("27cm", SizeGroup::Shoes).unwrap();
+    let us = sizes::parse("US 9", SizeGroup::Shoes).unwrap();
+    let eu = sizes::parse("EU 42", SizeGroup::Shoes).unwrap();
+
+    assert_eq!(cm.cm, Some(27.0));
+    assert_eq!(us.system, SizeSystem::Us);
+    assert!(cm.fits(&us));
+    assert_eq!(eu.cm, Some(26.5));
+    assert_eq!(cm.display(SizeSystem::Eu).as_deref(), Some("EU 43"));
+}
+
+#[test]
+fn can_parse_waist_and_inseam() {
+    let jeans = sizes::parse("W32 L30", SizeGroup::Bottoms).unwrap();
+    assert_eq!(jeans.system, SizeSystem::Inch);
+    assert_eq!(jeans.letter, Some(LetterSize::L));
+    assert_eq!(jeans.display(SizeSystem::Inch).as_deref(), Some("W32 L30"));
+
+    let jp = sizes::parse("76", SizeGroup::Bottoms).unwrap();
+    assert_eq!(jp.system, SizeSystem::Cm);
+    assert_eq!(jp.letter, Some(LetterSize::M));
+    assert_eq!(
+        sizes::parse("32x34", SizeGroup::Bottoms).unwrap().display(SizeSystem::Inch).as_deref(),
+        Some("W32 L34")
+    );
+}
+
+#[test]
+fn can_display_in_other_systems() {
+    let size = sizes::parse("EU 38", SizeGroup::Apparel).unwrap();
+    assert_eq!(size.display(SizeSystem::Letter).as_deref(), Some("S"));
+    assert_eq!(size.display(SizeSystem::Jp).as_deref(), Some("7号"));
+    assert_eq!(size.display(SizeSystem::Us).as_deref(), Some("US 4"));
+    assert_eq!(size.display(SizeSystem::Cm), None);
+}
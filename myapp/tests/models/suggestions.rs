@@ -0,0 +1,390 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::{NaiveDate, Utc};
+use myapp::{
+    common::weather::Weather,
+    data::{colors::ColorFamily, seasons::Season, slots::Slot},
+    models::{
+        _entities::{clothes, coordinates, wear_events},
+        coordinate_rules::Completeness,
+        coordinates::CoordinateWithClothes,
+        outfit_feedback::RatingSummary,
+        suggestions::{
+            HarmonyScorer, OutfitScorer, Score, SuggestionContext, SuggestionQuery, color_harmony,
+            suggest,
+        },
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+use super::support;
+
+fn garment(id: i32, category: &str, color: &str, material: Option<&str>) -> clothes::Model {
+    clothes::Model {
+        name: format!("{color} {category}"),
+        category: category.to_string(),
+        color: color.to_string(),
+        material: material.map(ToString::to_string),
+        ..support::garment(id)
+    }
+}
+
+fn coordinate(
+    season: Option<&str>,
+    occasion: Option<&str>,
+    clothes: Vec<clothes::Model>,
+) -> CoordinateWithClothes {
+    let now = Utc::now().into();
+    CoordinateWithClothes {
+        coordinate: coordinates::Model {
+            id: 1,
+            pid: Uuid::new_v4(),
+            name: "Existing".to_string(),
+            description: None,
+            occasion: occasion.map(ToString::to_string),
+            style: None,
+            seasons: json!(season.into_iter().collect::<Vec<_>>()),
+            user_id: 1,
+            is_favorite: false,
+            image_url: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        },
+        clothes,
+        placements: Vec::new(),
+        completeness: Completeness {
+            score: 1.0,
+            missing_slots: Vec::new(),
+        },
+        ratings: RatingSummary::default(),
+        removed_clothes: Vec::new(),
+    }
+}
+
+fn worn(clothes_id: i32, worn_on: NaiveDate) -> wear_events::Model {
+    let now = Utc::now().into();
+    wear_events::Model {
+        created_at: now,
+        updated_at: now,
+        id: clothes_id,
+        pid: Uuid::new_v4(),
+        worn_on,
+        user_id: 1,
+        clothes_id,
+        coordinate_id: None,
+    }
+}
+
+fn query(season: Option<&str>, slots: Option<&str>) -> SuggestionQuery {
+    SuggestionQuery {
+        user_id: 1,
+        season: season.map(ToString::to_string),
+        occasion: None,
+        location: None,
+        date: None,
+        slots: slots.map(ToString::to_string),
+        recent_days: None,
+        limit: None,
+    }
+}
+
+#[test]
+fn maps_categories_to_slots() {
+    assert_eq!(Slot::from_category("T-Shirt"), Some(Slot::Top));
+    assert_eq!(Slot::from_category("Denim jeans"), Some(Slot::Bottom));
+    assert_eq!(Slot::from_category("Trench coat"), Some(Slot::Outer));
+    assert_eq!(Slot::from_category("スニーカー"), Some(Slot::Shoes));
+    assert_eq!(Slot::from_category("Necklace"), Some(Slot::Accessory));
+    assert_eq!(Slot::from_category("Dress"), None);
+}
+
+#[test]
+fn default_slots_add_outerwear_when_needed() {
+    let default = query(None, None);
+    assert_eq!(
+        default.slots(false).unwrap(),
+        vec![Slot::Top, Slot::Bottom, Slot::Shoes]
+    );
+    assert_eq!(
+        default.slots(true).unwrap(),
+        vec![Slot::Top, Slot::Outer, Slot::Bottom, Slot::Shoes]
+    );
+
+    let custom = query(None, Some("shoes, top"));
+    assert_eq!(custom.slots(true).unwrap(), vec![Slot::Top, Slot::Shoes]);
+
+    assert!(query(Some("monsoon"), None).season().is_err());
+    assert!(query(None, Some("hat")).slots(false).is_err());
+}
+
+#[test]
+fn infers_season_from_date_and_hemisphere() {
+    let mut december = query(None, None);
+    december.date = NaiveDate::from_ymd_opt(2025, 12, 24);
+    assert_eq!(december.season_or_date().unwrap(), Some(Season::Winter));
+
+    december.location = Some("-33.87,151.21".to_string());
+    assert_eq!(december.season_or_date().unwrap(), Some(Season::Summer));
+
+    december.season = Some("spring".to_string());
+    assert_eq!(december.season_or_date().unwrap(), Some(Season::Spring));
+}
+
+#[test]
+fn scores_color_harmony() {
+    let neutral = color_harmony(&[ColorFamily::Neutral, ColorFamily::Brown]);
+    let accent = color_harmony(&[ColorFamily::Neutral, ColorFamily::Blue]);
+    let analogous = color_harmony(&[ColorFamily::Blue, ColorFamily::Purple]);
+    let clash = color_harmony(&[ColorFamily::Red, ColorFamily::Yellow, ColorFamily::Blue]);
+
+    assert!(accent.value > neutral.value);
+    assert!(neutral.value > clash.value);
+    assert!(analogous.value > clash.value);
+    assert_eq!(analogous.reasons, vec!["Analogous blue and purple"]);
+}
+
+#[test]
+fn suggests_outfits_filling_every_slot() {
+    let clothes = vec![
+        garment(1, "shirt", "white", None),
+        garment(2, "shirt", "red", None),
+        garment(3, "pants", "navy", None),
+        garment(4, "sneakers", "white", None),
+    ];
+    let context = SuggestionContext::default();
+    let result = suggest(
+        &clothes,
+        &[Slot::Top, Slot::Bottom, Slot::Shoes],
+        &context,
+        &HarmonyScorer::default(),
+        5,
+    );
+
+    assert!(result.missing_slots.is_empty());
+    assert_eq!(result.suggestions.len(), 2);
+    for suggestion in &result.suggestions {
+        let slots: Vec<Slot> = suggestion.clothes.iter().map(|item| item.slot).collect();
+        assert_eq!(slots, vec![Slot::Top, Slot::Bottom, Slot::Shoes]);
+        assert!(!suggestion.reasons.is_empty());
+    }
+}
+
+/// Counts the outfits scored by `HarmonyScorer`
+#[derive(Default)]
+struct CountingScorer {
+    scored: AtomicUsize,
+}
+
+impl OutfitScorer for CountingScorer {
+    fn score(&self, outfit: &[&clothes::Model], context: &SuggestionContext) -> Score {
+        self.scored.fetch_add(1, Ordering::Relaxed);
+        HarmonyScorer::default().score(outfit, context)
+    }
+}
+
+#[test]
+fn bounds_the_outfits_scored_for_many_slots() {
+    let categories = [
+        "hat", "shirt", "coat", "pants", "sneakers", "scarf", "tote bag",
+    ];
+    let colors = ["white", "black", "navy", "red", "beige", "green"];
+    let mut clothes = Vec::new();
+    for category in categories {
+        for color in colors {
+            clothes.push(garment(
+                i32::try_from(clothes.len()).unwrap(),
+                category,
+                color,
+                None,
+            ));
+        }
+    }
+    let scorer = CountingScorer::default();
+    let result = suggest(
+        &clothes,
+        &Slot::ALL,
+        &SuggestionContext::default(),
+        &scorer,
+        20,
+    );
+
+    assert_eq!(result.suggestions.len(), 20);
+    // Every combination would be 6^7 = 279,936 outfits
+    assert!(scorer.scored.load(Ordering::Relaxed) < 5_000);
+}
+
+#[test]
+fn caps_recently_worn_days() {
+    let days = |recent_days: Option<i64>| {
+        SuggestionQuery {
+            recent_days,
+            ..query(None, None)
+        }
+        .recent_days()
+    };
+    assert_eq!(days(None), SuggestionQuery::DEFAULT_RECENT_DAYS);
+    assert_eq!(days(Some(-3)), 0);
+    assert_eq!(days(Some(i64::MAX)), SuggestionQuery::MAX_RECENT_DAYS);
+}
+
+#[test]
+fn reports_missing_slots() {
+    let clothes = vec![garment(1, "shirt", "white", None)];
+    let result = suggest(
+        &clothes,
+        &[Slot::Top, Slot::Shoes],
+        &SuggestionContext::default(),
+        &HarmonyScorer::default(),
+        5,
+    );
+
+    assert!(result.suggestions.is_empty());
+    assert_eq!(result.missing_slots, vec![Slot::Shoes]);
+}
+
+#[test]
+fn skips_existing_coordinates_and_avoids_recent_wear() {
+    let shirt = garment(1, "shirt", "white", None);
+    let tee = garment(2, "tee", "gray", None);
+    let pants = garment(3, "pants", "black", None);
+    let clothes = vec![shirt.clone(), tee.clone(), pants.clone()];
+    let today = Utc::now().date_naive();
+    let context = SuggestionContext::new(
+        None,
+        None,
+        &[coordinate(None, None, vec![shirt, pants.clone()])],
+        &[worn(2, today)],
+    );
+
+    let result = suggest(
+        &clothes,
+        &[Slot::Top, Slot::Bottom],
+        &context,
+        &HarmonyScorer::default(),
+        5,
+    );
+
+    assert_eq!(result.suggestions.len(), 1);
+    let suggestion = &result.suggestions[0];
+    assert_eq!(suggestion.clothes[0].clothes.id, tee.id);
+    assert!(
+        suggestion
+            .reasons
+            .iter()
+            .any(|reason| reason.starts_with("Recently worn"))
+    );
+}
+
+#[test]
+fn prefers_garments_suited_to_the_season() {
+    let linen = garment(1, "shirt", "white", Some("linen"));
+    let wool = garment(2, "sweater", "white", Some("wool"));
+    let context = SuggestionContext::new(Some(Season::Winter), None, &[], &[]);
+    let scorer = HarmonyScorer::default();
+
+    assert!(scorer.score(&[&wool], &context).value > scorer.score(&[&linen], &context).value);
+}
+
+struct FirstSeen;
+
+impl OutfitScorer for FirstSeen {
+    fn score(&self, outfit: &[&clothes::Model], _context: &SuggestionContext) -> Score {
+        Score {
+            value: -f64::from(outfit.iter().map(|item| item.id).sum::<i32>()),
+            reasons: vec!["Lowest ids first".to_string()],
+        }
+    }
+}
+
+#[test]
+fn uses_the_given_scorer() {
+    let clothes = vec![
+        garment(1, "shirt", "red", None),
+        garment(2, "shirt", "white", None),
+    ];
+    let result = suggest(
+        &clothes,
+        &[Slot::Top],
+        &SuggestionContext::default(),
+        &FirstSeen,
+        1,
+    );
+
+    assert_eq!(result.suggestions[0].clothes[0].clothes.id, 1);
+    assert_eq!(result.suggestions[0].reasons, vec!["Lowest ids first"]);
+}
+
+fn weather(temperature_c: f64, precipitation_mm: f64) -> Weather {
+    Weather {
+        temperature_c,
+        precipitation_mm,
+        precipitation_probability: None,
+    }
+}
+
+#[test]
+fn leaves_out_light_garments_in_the_cold() {
+    let clothes = vec![
+        garment(1, "shirt", "white", Some("linen")),
+        garment(2, "sweater", "gray", Some("wool")),
+        garment(3, "shorts", "navy", None),
+        garment(4, "pants", "black", None),
+    ];
+    let context = SuggestionContext {
+        weather: Some(weather(5.0, 0.0)),
+        ..SuggestionContext::default()
+    };
+    let result = suggest(
+        &clothes,
+        &[Slot::Top, Slot::Bottom],
+        &context,
+        &HarmonyScorer::default(),
+        5,
+    );
+
+    assert_eq!(result.weather, Some(weather(5.0, 0.0)));
+    assert_eq!(result.suggestions.len(), 1);
+    let ids: Vec<i32> = result.suggestions[0]
+        .clothes
+        .iter()
+        .map(|item| item.clothes.id)
+        .collect();
+    assert_eq!(ids, vec![2, 4]);
+}
+
+#[test]
+fn prefers_rain_proof_shoes_in_the_rain() {
+    let suede = garment(1, "loafers", "brown", Some("suede"));
+    let boots = garment(2, "rain boots", "black", Some("rubber"));
+    let sneakers = garment(3, "sneakers", "white", None);
+    let context = SuggestionContext {
+        weather: Some(weather(18.0, 4.0)),
+        ..SuggestionContext::default()
+    };
+
+    assert_eq!(context.weather_fit(&suede), Some(false));
+    assert_eq!(context.weather_fit(&boots), Some(true));
+    assert_eq!(context.weather_fit(&sneakers), None);
+
+    let result = suggest(
+        &[suede, boots, sneakers],
+        &[Slot::Shoes],
+        &context,
+        &HarmonyScorer::default(),
+        5,
+    );
+    let ids: Vec<i32> = result
+        .suggestions
+        .iter()
+        .map(|suggestion| suggestion.clothes[0].clothes.id)
+        .collect();
+    assert_eq!(ids, vec![2, 3]);
+    assert!(
+        result.suggestions[0]
+            .reasons
+            .iter()
+            .any(|reason| reason.starts_with("Made for 18°C and rain"))
+    );
+}
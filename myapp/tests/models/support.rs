@@ -0,0 +1,95 @@
+use chrono::Utc;
+use loco_rs::{boot::BootResult, testing::prelude::*};
+use myapp::{
+    app::App,
+    models::{
+        _entities::clothes,
+        coordinates::{self, CreateCoordinateParams},
+    },
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, DatabaseConnection, IntoActiveModel,
+    prelude::{Decimal, Uuid},
+};
+
+/// A white, medium-sized, active top costing 30.00 USD. Tests override the
+/// fields they care about with struct update syntax.
+pub fn garment(id: i32) -> clothes::Model {
+    let now = Utc::now().into();
+    clothes::Model {
+        id,
+        pid: Uuid::new_v4(),
+        name: "Oxford Shirt".to_string(),
+        description: None,
+        brand: "Acme".to_string(),
+        category: "tops".to_string(),
+        size: "M".to_string(),
+        color: "white".to_string(),
+        material: None,
+        price: Decimal::new(3000, 2),
+        in_stock: true,
+        stock_quantity: 1,
+        image_url: None,
+        primary_color_hex: None,
+        primary_color_family: None,
+        secondary_color_hex: None,
+        secondary_color_family: None,
+        size_system: None,
+        size_letter: None,
+        size_cm: None,
+        inseam_cm: None,
+        lent_to: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+        lifecycle_state: "active".to_string(),
+        condition: None,
+        state_changed_at: None,
+        currency: "USD".to_string(),
+        purchase_date: None,
+        store: None,
+        order_number: None,
+        receipt_path: None,
+        receipt_content_type: None,
+    }
+}
+
+/// Boot the app on a fresh test database seeded with the user fixtures
+pub async fn boot() -> BootResult {
+    let boot = boot_test::<App>()
+        .await
+        .expect("Failed to boot test application");
+    seed::<App>(&boot.app_context)
+        .await
+        .expect("Failed to seed database");
+    boot
+}
+
+/// Store a garment, which gets a new id and pid
+pub async fn insert_garment(db: &DatabaseConnection, garment: clothes::Model) -> clothes::Model {
+    let mut garment = garment.into_active_model();
+    garment.id = ActiveValue::NotSet;
+    garment.pid = ActiveValue::NotSet;
+    garment.insert(db).await.expect("Failed to insert garment")
+}
+
+/// Store a coordinate of the first fixture user wearing `clothes_ids`
+pub async fn insert_coordinate(db: &DatabaseConnection, clothes_ids: &[i32]) -> coordinates::Model {
+    coordinates::Model::create_with_clothes(
+        db,
+        &CreateCoordinateParams {
+            name: "Weekday".to_string(),
+            description: None,
+            occasion: None,
+            seasons: Vec::new(),
+            style: None,
+            user_id: 1,
+            is_favorite: None,
+            image_url: None,
+            clothes_ids: clothes_ids.to_vec(),
+        },
+    )
+    .await
+    .expect("Failed to insert coordinate")
+    .coordinate
+}
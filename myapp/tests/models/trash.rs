@@ -0,0 +1,35 @@
+use chrono::{FixedOffset, TimeZone};
+use myapp::{common::settings::Settings, models::trash::TrashEntry};
+use serde_json::json;
+
+#[test]
+fn trash_entries_expire_after_the_retention_period() {
+    let deleted_at = FixedOffset::east_opt(9 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2025, 6, 1, 12, 0, 0)
+        .unwrap();
+    let entry = TrashEntry::new("Linen shirt", deleted_at, 30);
+    assert_eq!(
+        entry.purge_after,
+        FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 7, 1, 12, 0, 0)
+            .unwrap()
+    );
+
+    let value = serde_json::to_value(TrashEntry::new(
+        json!({"name": "Linen shirt"}),
+        deleted_at,
+        0,
+    ))
+    .unwrap();
+    assert_eq!(value["name"], "Linen shirt");
+    assert_eq!(value["purge_after"], "2025-06-01T12:00:00+09:00");
+}
+
+#[test]
+fn retention_defaults_to_thirty_days() {
+    assert_eq!(Settings::default().trash.retention_days, 30);
+    let settings = Settings::from_json(&json!({"trash": {"retention_days": 7}})).unwrap();
+    assert_eq!(settings.trash.retention_days, 7);
+}
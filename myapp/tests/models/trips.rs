@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+
+use chrono::{NaiveDate, Utc};
+use myapp::{
+    data::{lifecycle::LifecycleState, seasons::Season, vocabularies::Occasion},
+    models::{
+        _entities::{clothes, coordinates, outfit_plans, trips},
+        coordinate_rules::Completeness,
+        coordinates::CoordinateWithClothes,
+        outfit_feedback::RatingSummary,
+        outfit_plans::PlannedOutfit,
+        trips::{
+            ChecklistFormat, TripActivity, TripDetails, TripOutfit, packing_list, pick_coordinates,
+            trip_outfits,
+        },
+    },
+};
+use sea_orm::prelude::Uuid;
+use serde_json::json;
+
+use super::support;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn garment(id: i32) -> clothes::Model {
+    clothes::Model {
+        name: format!("Garment {id}"),
+        ..support::garment(id)
+    }
+}
+
+fn coordinate(
+    id: i32,
+    occasion: Option<&str>,
+    clothes: Vec<clothes::Model>,
+) -> CoordinateWithClothes {
+    let now = Utc::now().into();
+    CoordinateWithClothes {
+        coordinate: coordinates::Model {
+            id,
+            pid: Uuid::new_v4(),
+            name: format!("Outfit {id}"),
+            description: None,
+            occasion: occasion.map(ToString::to_string),
+            style: None,
+            seasons: json!([]),
+            user_id: 1,
+            is_favorite: false,
+            image_url: None,
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+        },
+        clothes,
+        placements: Vec::new(),
+        completeness: Completeness {
+            score: 1.0,
+            missing_slots: Vec::new(),
+        },
+        ratings: RatingSummary::default(),
+        removed_clothes: Vec::new(),
+    }
+}
+
+fn planned(id: i32, planned_on: NaiveDate, coordinate: CoordinateWithClothes) -> PlannedOutfit {
+    let now = Utc::now().into();
+    PlannedOutfit {
+        plan: outfit_plans::Model {
+            created_at: now,
+            updated_at: now,
+            id,
+            pid: Uuid::new_v4(),
+            planned_on,
+            occasion: None,
+            note: None,
+            done_at: None,
+            user_id: 1,
+            coordinate_id: coordinate.coordinate.id,
+            trip_id: Some(1),
+        },
+        coordinate,
+    }
+}
+
+fn outfit(on: NaiveDate, occasion: Option<Occasion>) -> TripOutfit {
+    TripOutfit {
+        on,
+        occasion,
+        season: Season::Summer,
+        note: None,
+    }
+}
+
+#[test]
+fn trip_needs_an_outfit_per_day_and_occasion() {
+    let activities = vec![
+        TripActivity {
+            on: None,
+            occasion: Occasion::Outdoor,
+            note: Some("Hiking".to_string()),
+        },
+        TripActivity {
+            on: Some(date(2025, 7, 2)),
+            occasion: Occasion::Formal,
+            note: Some("Wedding".to_string()),
+        },
+        TripActivity {
+            on: Some(date(2025, 7, 2)),
+            occasion: Occasion::Outdoor,
+            note: Some("Lake".to_string()),
+        },
+    ];
+    let outfits = trip_outfits(date(2025, 7, 1), date(2025, 7, 3), &activities, false);
+
+    let summary: Vec<(NaiveDate, Option<Occasion>)> =
+        outfits.iter().map(|o| (o.on, o.occasion)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (date(2025, 7, 1), Some(Occasion::Outdoor)),
+            (date(2025, 7, 2), Some(Occasion::Outdoor)),
+            (date(2025, 7, 2), Some(Occasion::Formal)),
+            (date(2025, 7, 3), Some(Occasion::Outdoor)),
+        ]
+    );
+    assert_eq!(outfits[1].note.as_deref(), Some("Hiking; Lake"));
+    assert!(outfits.iter().all(|o| o.season == Season::Summer));
+
+    let southern = trip_outfits(date(2025, 7, 1), date(2025, 7, 2), &[], true);
+    assert_eq!(southern.len(), 2);
+    assert!(
+        southern
+            .iter()
+            .all(|o| o.occasion.is_none() && o.season == Season::Winter)
+    );
+}
+
+#[test]
+fn planner_reuses_packed_garments() {
+    let coordinates = vec![
+        coordinate(1, Some("casual"), vec![garment(1), garment(2), garment(3)]),
+        coordinate(2, Some("work"), vec![garment(4), garment(5), garment(6)]),
+        coordinate(3, Some("work"), vec![garment(1), garment(2), garment(7)]),
+    ];
+    let outfits = vec![
+        outfit(date(2025, 7, 1), Some(Occasion::Casual)),
+        outfit(date(2025, 7, 2), Some(Occasion::Work)),
+    ];
+
+    let picks: Vec<i32> = pick_coordinates(&outfits, &coordinates)
+        .into_iter()
+        .map(|pick| pick.unwrap().coordinate.id)
+        .collect();
+    assert_eq!(picks, vec![1, 3]);
+}
+
+#[test]
+fn planner_avoids_unavailable_garments() {
+    let mut washing = garment(1);
+    washing.lifecycle_state = "in_laundry".to_string();
+    let coordinates = vec![
+        coordinate(1, None, vec![washing, garment(2), garment(3)]),
+        coordinate(2, None, vec![garment(4), garment(5), garment(6)]),
+        coordinate(3, None, Vec::new()),
+    ];
+    let picks = pick_coordinates(&[outfit(date(2025, 7, 1), None)], &coordinates);
+    assert_eq!(picks[0].unwrap().coordinate.id, 2);
+
+    assert!(pick_coordinates(&[outfit(date(2025, 7, 1), None)], &[])[0].is_none());
+}
+
+#[test]
+fn packing_list_lists_each_garment_once_with_flags() {
+    let mut lent = garment(3);
+    lent.lifecycle_state = "lent".to_string();
+    lent.lent_to = Some("Sam".to_string());
+    let plans = vec![
+        planned(
+            1,
+            date(2025, 7, 2),
+            coordinate(1, None, vec![garment(1), lent]),
+        ),
+        planned(
+            2,
+            date(2025, 7, 1),
+            coordinate(2, None, vec![garment(1), garment(2)]),
+        ),
+        planned(3, date(2025, 7, 1), coordinate(3, None, vec![garment(2)])),
+    ];
+    let items = packing_list(&plans, &HashSet::from([1]));
+
+    let ids: Vec<i32> = items.iter().map(|item| item.clothes.id).collect();
+    assert_eq!(ids, vec![1, 3, 2]);
+    assert_eq!(items[0].days, vec![date(2025, 7, 1), date(2025, 7, 2)]);
+    assert!(items[0].packed);
+    assert_eq!(items[1].flag, Some(LifecycleState::Lent));
+    assert_eq!(items[2].days, vec![date(2025, 7, 1)]);
+    assert!(!items[2].packed);
+}
+
+#[test]
+fn checklist_shows_packed_state() {
+    let now = Utc::now().into();
+    let mut washing = garment(2);
+    washing.lifecycle_state = "in_laundry".to_string();
+    let plans = vec![planned(
+        1,
+        date(2025, 7, 1),
+        coordinate(1, None, vec![garment(1), washing]),
+    )];
+    let details = TripDetails {
+        trip: trips::Model {
+            id: 1,
+            pid: Uuid::new_v4(),
+            user_id: 1,
+            name: "Summer in Kyoto".to_string(),
+            destination: Some("Kyoto".to_string()),
+            location: None,
+            starts_on: date(2025, 7, 1),
+            ends_on: date(2025, 7, 3),
+            activities: json!([]),
+            created_at: now,
+            updated_at: now,
+        },
+        packing_list: packing_list(&plans, &HashSet::from([1])),
+        outfits: plans,
+    };
+
+    let markdown =
+        String::from_utf8(details.checklist(ChecklistFormat::Markdown).unwrap()).unwrap();
+    assert_eq!(
+        markdown,
+        "# Summer in Kyoto\nKyoto, 2025-07-01 to 2025-07-03\n\n\
+         - [x] Garment 1 (1 day)\n\
+         - [ ] Garment 2 (1 day) - in the laundry\n"
+    );
+
+    let csv = String::from_utf8(details.checklist(ChecklistFormat::Csv).unwrap()).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "clothes_pid,name,category,days,packed,flag");
+    assert!(lines[2].ends_with(",Garment 2,tops,2025-07-01,false,in the laundry"));
+}
@@ -0,0 +1,114 @@
+use myapp::{
+    common::i18n::Labels,
+    data::{
+        seasons::Season,
+        vocabularies::{Occasion, Style, Vocabularies, Vocabulary},
+    },
+    models::wardrobe_exports::ExportCoordinate,
+};
+use serde_json::json;
+
+fn labels() -> Labels {
+    Labels::load("assets/i18n", "assets/i18n/shared.ftl").unwrap()
+}
+
+#[test]
+fn parses_keys_and_known_names() {
+    assert_eq!(Occasion::parse("Work"), Some(Occasion::Work));
+    assert_eq!(Occasion::parse("オフィス"), Some(Occasion::Work));
+    assert_eq!(Style::parse("Smart Casual"), Some(Style::SmartCasual));
+    assert_eq!(Style::parse("smart-casual"), Some(Style::SmartCasual));
+    assert_eq!(Style::parse("きれいめ"), Some(Style::SmartCasual));
+    assert_eq!(Style::parse("avant-garde"), None);
+
+    assert_eq!(Style::from_text("avant-garde"), Some(Style::Other));
+    assert_eq!(Occasion::from_text("  "), None);
+
+    assert_eq!(
+        serde_json::from_value::<Occasion>(json!("Date")).unwrap(),
+        Occasion::Date
+    );
+    assert!(serde_json::from_value::<Occasion>(json!("brunch")).is_err());
+    assert_eq!(json!(Style::SmartCasual), json!("smart_casual"));
+}
+
+#[test]
+fn parses_season_lists() {
+    assert_eq!(
+        Season::parse_list("Summer/Spring"),
+        vec![Season::Spring, Season::Summer]
+    );
+    assert_eq!(
+        Season::parse_list("秋冬"),
+        vec![Season::Autumn, Season::Winter]
+    );
+    assert_eq!(
+        Season::parse_list("fall, winter, fall"),
+        vec![Season::Autumn, Season::Winter]
+    );
+    assert_eq!(Season::parse_list("All Season"), Season::ALL.to_vec());
+    assert!(Season::parse_list("monsoon").is_empty());
+}
+
+#[test]
+fn reads_single_season_of_older_exports() {
+    let coordinate = |season: serde_json::Value| -> ExportCoordinate {
+        serde_json::from_value(json!({
+            "pid": "2b1b6a3e-6a53-4bd8-9a3c-5d1a1c0e9f10",
+            "name": "Layers",
+            "description": null,
+            "occasion": null,
+            "style": null,
+            "image_url": null,
+            "season": season,
+        }))
+        .unwrap()
+    };
+    assert_eq!(
+        coordinate(json!("Autumn/Winter")).seasons,
+        vec![Season::Autumn, Season::Winter]
+    );
+    assert!(coordinate(json!(null)).seasons.is_empty());
+    assert_eq!(
+        coordinate(json!(["winter", "spring"])).seasons,
+        vec![Season::Spring, Season::Winter]
+    );
+}
+
+#[test]
+fn every_value_has_a_label_in_every_language() {
+    fn assert_labelled<T: Vocabulary>(labels: &Labels, lang: &str) {
+        let lang = labels.language(Some(lang));
+        for item in T::all() {
+            let id = item.label_id();
+            assert_ne!(labels.label(&lang, &id), id, "missing {lang} label");
+        }
+    }
+
+    let labels = labels();
+    for lang in ["en-US", "de-DE", "ja-JP"] {
+        assert_labelled::<Season>(&labels, lang);
+        assert_labelled::<Occasion>(&labels, lang);
+        assert_labelled::<Style>(&labels, lang);
+    }
+
+    let german = Vocabularies::new("de-DE", |id| labels.label(&labels.language(Some("de")), id));
+    assert_eq!(german.seasons[0].key, "spring");
+    assert_eq!(german.seasons[0].label, "Frühling");
+}
+
+#[test]
+fn labels_fall_back_to_english() {
+    let labels = labels();
+    let japanese = labels.language(Some("ja"));
+    assert_eq!(japanese.to_string(), "ja-JP");
+    assert_eq!(
+        labels.label(&japanese, &Style::SmartCasual.label_id()),
+        "きれいめ"
+    );
+
+    let unknown = labels.language(Some("fr-FR"));
+    assert_eq!(unknown.to_string(), "en-US");
+    assert_eq!(labels.label(&unknown, &Season::Autumn.label_id()), "Autumn");
+    assert_eq!(labels.language(None).to_string(), "en-US");
+}
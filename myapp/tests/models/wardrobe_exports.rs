@@ -0,0 +1,214 @@
+use std::{collections::HashMap, io::Cursor};
+
+use chrono::{Duration, Utc};
+use myapp::{
+    data::slots::Slot,
+    models::{
+        _entities::{clothes, clothes_coordinates, coordinates, wardrobe_exports},
+        clothes_imports::{self, ImportFormat},
+        wardrobe_exports::{ExportImage, WardrobeDocument},
+    },
+};
+use sea_orm::prelude::{Decimal, Uuid};
+use serde_json::json;
+use serial_test::serial;
+
+use super::support;
+
+fn clothes_item(id: i32, name: &str, image_url: Option<&str>) -> clothes::Model {
+    clothes::Model {
+        name: name.to_string(),
+        color: "navy".to_string(),
+        material: Some("cotton".to_string()),
+        price: Decimal::new(4990, 2),
+        image_url: image_url.map(ToString::to_string),
+        primary_color_hex: Some("#1F2A44".to_string()),
+        primary_color_family: Some("blue".to_string()),
+        size_system: Some("letter".to_string()),
+        size_letter: Some("M".to_string()),
+        ..support::garment(id)
+    }
+}
+
+fn wardrobe() -> WardrobeDocument {
+    let now = Utc::now().into();
+    let clothes = vec![
+        clothes_item(
+            1,
+            "Oxford Shirt",
+            Some("https://example.com/shirt.JPG?size=large"),
+        ),
+        clothes_item(2, "Chinos", None),
+    ];
+    let coordinate = coordinates::Model {
+        id: 10,
+        pid: Uuid::new_v4(),
+        name: "Office, casual".to_string(),
+        description: None,
+        occasion: Some("work".to_string()),
+        style: None,
+        seasons: json!(["spring"]),
+        user_id: 1,
+        is_favorite: true,
+        image_url: None,
+        created_at: now,
+        updated_at: now,
+        deleted_at: None,
+    };
+    let link = clothes_coordinates::Model {
+        id: 100,
+        clothes_id: 2,
+        coordinate_id: 10,
+        slot: Some("bottom".to_string()),
+        x: Some(0.25),
+        y: Some(0.5),
+        scale: None,
+        rotation: None,
+        z_index: Some(1),
+        notes: Some("rolled hem".to_string()),
+        created_at: now,
+        updated_at: now,
+    };
+    WardrobeDocument::new(&clothes, &[coordinate], &[link])
+}
+
+#[test]
+fn json_export_round_trips_through_import() {
+    let document = wardrobe();
+    let json = String::from_utf8(document.to_json().unwrap()).unwrap();
+
+    let parsed = clothes_imports::parse(ImportFormat::Json, &json, &HashMap::new()).unwrap();
+
+    assert!(parsed.errors().is_empty());
+    assert_eq!(parsed.valid_rows(), 2);
+    let shirt = parsed.rows[0].as_ref().unwrap();
+    assert_eq!(shirt.name, "Oxford Shirt");
+    assert_eq!(shirt.price, "49.90");
+    assert_eq!(shirt.currency.code(), "USD");
+    assert_eq!(shirt.primary_color.as_deref(), Some("#1F2A44"));
+
+    assert_eq!(parsed.coordinates.len(), 1);
+    let coordinate = &parsed.coordinates[0];
+    assert_eq!(coordinate.coordinate.name, "Office, casual");
+    assert!(coordinate.coordinate.is_favorite);
+    assert_eq!(coordinate.rows, vec![1]);
+    assert_eq!(
+        coordinate.coordinate.clothes[0].notes.as_deref(),
+        Some("rolled hem")
+    );
+    let layout = &coordinate.coordinate.clothes[0].layout;
+    assert_eq!(layout.slot, Some(Slot::Bottom));
+    assert_eq!(layout.x, Some(0.25));
+    assert_eq!(layout.z_index, Some(1));
+}
+
+#[test]
+fn csv_export_is_accepted_by_import() {
+    let csv = String::from_utf8(wardrobe().clothes_csv().unwrap()).unwrap();
+
+    assert!(csv.lines().nth(2).unwrap().ends_with(",\"Office, casual\""));
+    let parsed = clothes_imports::parse(ImportFormat::Csv, &csv, &HashMap::new()).unwrap();
+    assert!(parsed.errors().is_empty());
+    assert_eq!(parsed.valid_rows(), 2);
+}
+
+#[test]
+fn import_reports_unknown_clothes_in_coordinates() {
+    let mut document = wardrobe();
+    document.clothes.remove(1);
+    let json = String::from_utf8(document.to_json().unwrap()).unwrap();
+
+    let parsed = clothes_imports::parse(ImportFormat::Json, &json, &HashMap::new()).unwrap();
+    let errors = parsed.errors();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field.as_deref(), Some("coordinates[0].clothes"));
+}
+
+#[test]
+fn zip_export_contains_manifest_and_images() {
+    let document = wardrobe();
+    let mut images = document.images();
+    assert_eq!(images.len(), 1);
+    images[0].file = Some(format!("images/clothes/{}.jpg", images[0].pid));
+    images[0].data = vec![0xFF, 0xD8, 0xFF];
+    let missing = ExportImage {
+        owner: "coordinates".to_string(),
+        pid: Uuid::new_v4(),
+        source: "missing.png".to_string(),
+        file: None,
+        error: Some("not found".to_string()),
+        data: Vec::new(),
+    };
+    images.push(missing);
+
+    let data = document.to_zip(&images).unwrap();
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).unwrap();
+
+    let mut names: Vec<_> = archive.file_names().map(ToString::to_string).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "clothes.csv".to_string(),
+            "coordinate_clothes.csv".to_string(),
+            "coordinates.csv".to_string(),
+            format!("images/clothes/{}.jpg", images[0].pid),
+            "manifest.json".to_string(),
+            "wardrobe.json".to_string(),
+        ]
+    );
+    let manifest: serde_json::Value =
+        serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+    assert_eq!(manifest["clothes"], 2);
+    assert_eq!(manifest["images"][1]["error"], "not found");
+}
+
+#[test]
+fn download_link_requires_token_and_expires() {
+    let now = Utc::now();
+    let export = wardrobe_exports::Model {
+        created_at: now.into(),
+        updated_at: now.into(),
+        id: 1,
+        pid: Uuid::new_v4(),
+        format: "zip".to_string(),
+        status: "completed".to_string(),
+        file_path: Some("exports/wardrobe.zip".to_string()),
+        download_token: Some("secret".to_string()),
+        expires_at: Some((now + Duration::hours(24)).into()),
+        error: None,
+        user_id: 1,
+    };
+
+    assert!(export.is_downloadable("secret", now));
+    assert!(!export.is_downloadable("guess", now));
+    assert!(!export.is_downloadable("secret", now + Duration::hours(25)));
+}
+
+#[tokio::test]
+#[serial]
+async fn exports_only_the_clothes_in_the_users_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    support::insert_garment(
+        db,
+        clothes::Model {
+            name: "Unworn coat".to_string(),
+            ..support::garment(0)
+        },
+    )
+    .await;
+    support::insert_coordinate(db, &[shirt.id]).await;
+
+    let document = WardrobeDocument::collect(db, 1).await.unwrap();
+    assert_eq!(document.clothes.len(), 1);
+    assert_eq!(document.clothes[0].pid, shirt.pid);
+    assert_eq!(document.coordinates.len(), 1);
+    assert_eq!(WardrobeDocument::count(db, 1).await.unwrap(), 2);
+
+    let other = WardrobeDocument::collect(db, 2).await.unwrap();
+    assert!(other.clothes.is_empty());
+    assert_eq!(WardrobeDocument::count(db, 2).await.unwrap(), 0);
+}
@@ -0,0 +1,135 @@
+use chrono::{NaiveDate, TimeZone, Utc};
+use myapp::{
+    data::money::{Currency, Money},
+    models::{
+        _entities::clothes,
+        wear_events::{self, ClothesWithWear, LogWearParams},
+    },
+};
+use sea_orm::prelude::Decimal;
+use serial_test::serial;
+
+use super::support;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn clothes_item(price: Decimal, created_on: NaiveDate) -> clothes::Model {
+    let created_at = Utc
+        .from_utc_datetime(&created_on.and_hms_opt(9, 0, 0).unwrap())
+        .into();
+    clothes::Model {
+        name: "Trench Coat".to_string(),
+        category: "outerwear".to_string(),
+        color: "beige".to_string(),
+        price,
+        created_at,
+        updated_at: created_at,
+        ..support::garment(1)
+    }
+}
+
+#[test]
+fn cost_per_wear_divides_price_by_wears() {
+    let price = Money::new(Decimal::new(10000, 2), Currency::USD);
+    assert_eq!(
+        wear_events::cost_per_wear(price, 3).map(|cost| cost.amount),
+        Some(Decimal::new(3333, 2))
+    );
+    assert_eq!(wear_events::cost_per_wear(price, 0), None);
+
+    let yen = Money::new(Decimal::from(10000), Currency::parse("JPY").unwrap());
+    assert_eq!(
+        wear_events::cost_per_wear(yen, 3).map(|cost| cost.to_string()),
+        Some("3333 JPY".to_string())
+    );
+}
+
+#[test]
+fn computes_stats_for_worn_items() {
+    let item = ClothesWithWear::new(
+        clothes_item(Decimal::new(12000, 2), date(2024, 1, 1)),
+        4,
+        Some(date(2024, 5, 1)),
+    );
+
+    assert_eq!(
+        item.cost_per_wear.map(|cost| cost.amount),
+        Some(Decimal::new(3000, 2))
+    );
+    assert!(item.not_worn_since(date(2024, 6, 1)));
+    assert!(!item.not_worn_since(date(2024, 4, 1)));
+}
+
+#[test]
+fn never_worn_items_are_stale_once_owned_before_cutoff() {
+    let item = ClothesWithWear::new(
+        clothes_item(Decimal::new(5000, 2), date(2024, 3, 1)),
+        0,
+        None,
+    );
+
+    assert_eq!(item.cost_per_wear, None);
+    assert!(item.not_worn_since(date(2024, 9, 1)));
+    assert!(!item.not_worn_since(date(2024, 2, 1)));
+}
+
+#[tokio::test]
+#[serial]
+async fn reports_only_the_clothes_in_the_users_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    support::insert_garment(db, support::garment(0)).await;
+    support::insert_coordinate(db, &[shirt.id]).await;
+
+    let stats = wear_events::Model::clothes_stats(db, 1).await.unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].clothes.pid, shirt.pid);
+    assert!(
+        wear_events::Model::never_worn(db, 2)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn logs_wears_only_of_the_users_clothes_and_coordinates() {
+    let boot = support::boot().await;
+    let db = &boot.app_context.db;
+    let shirt = support::insert_garment(db, support::garment(0)).await;
+    let coat = support::insert_garment(db, support::garment(0)).await;
+    let coordinate = support::insert_coordinate(db, &[shirt.id]).await;
+    let params =
+        |user_id: i32, clothes_id: Option<i32>, coordinate_pid: Option<String>| LogWearParams {
+            user_id,
+            clothes_id,
+            coordinate_pid,
+            worn_on: None,
+        };
+
+    let logged = wear_events::Model::log(db, &params(1, Some(shirt.id), None))
+        .await
+        .unwrap();
+    assert_eq!(logged.map(|events| events.len()), Some(1));
+    assert!(
+        wear_events::Model::log(db, &params(1, Some(coat.id), None))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let pid = Some(coordinate.pid.to_string());
+    assert!(
+        wear_events::Model::log(db, &params(2, None, pid.clone()))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        params(1, Some(shirt.id), pid).problems()[0].field,
+        "clothes_id"
+    );
+}
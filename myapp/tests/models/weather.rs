@@ -0,0 +1,117 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use loco_rs::{
+    Result,
+    cache::{self, Cache},
+};
+use myapp::common::weather::{
+    self, FixtureEntry, FixtureProvider, Location, Weather, WeatherProvider,
+};
+
+fn tokyo() -> Location {
+    Location::parse("35.6812, 139.7671").unwrap()
+}
+
+fn rainy() -> Weather {
+    Weather {
+        temperature_c: 8.0,
+        precipitation_mm: 2.5,
+        precipitation_probability: Some(80),
+    }
+}
+
+#[test]
+fn parses_locations() {
+    assert_eq!(tokyo().key(), "35.68,139.77");
+    assert!(Location::parse("35.68").is_err());
+    assert!(Location::parse("north,east").is_err());
+    assert!(Location::parse("95,10").is_err());
+}
+
+#[test]
+fn fixture_prefers_dated_entries() {
+    let christmas = NaiveDate::from_ymd_opt(2025, 12, 25).unwrap();
+    let snow = Weather {
+        temperature_c: -2.0,
+        precipitation_mm: 5.0,
+        precipitation_probability: Some(90),
+    };
+    let provider = FixtureProvider {
+        default: Weather::default(),
+        entries: vec![
+            FixtureEntry {
+                location: "35.68,139.77".to_string(),
+                date: None,
+                weather: rainy(),
+            },
+            FixtureEntry {
+                location: "35.68,139.77".to_string(),
+                date: Some(christmas),
+                weather: snow,
+            },
+        ],
+    };
+
+    assert_eq!(provider.lookup(tokyo(), christmas), snow);
+    assert_eq!(
+        provider.lookup(tokyo(), NaiveDate::from_ymd_opt(2025, 12, 26).unwrap()),
+        rainy()
+    );
+    assert_eq!(
+        provider.lookup(Location::parse("0,0").unwrap(), christmas),
+        Weather::default()
+    );
+}
+
+#[test]
+fn looks_up_current_hour_today_and_noon_otherwise() {
+    let now = NaiveDate::from_ymd_opt(2025, 6, 1)
+        .unwrap()
+        .and_hms_opt(9, 41, 12)
+        .unwrap();
+    assert_eq!(
+        weather::lookup_hour(None, now),
+        now.date().and_hms_opt(9, 0, 0).unwrap()
+    );
+    let tomorrow = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+    assert_eq!(
+        weather::lookup_hour(Some(tomorrow), now),
+        tomorrow.and_hms_opt(12, 0, 0).unwrap()
+    );
+}
+
+struct CountingProvider(AtomicUsize);
+
+#[async_trait]
+impl WeatherProvider for CountingProvider {
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+
+    async fn weather(&self, _location: Location, _at: NaiveDateTime) -> Result<Weather> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(rainy())
+    }
+}
+
+#[tokio::test]
+async fn caches_lookups_per_location_and_hour() {
+    let cache = Cache::new(cache::drivers::inmem::new());
+    let provider = CountingProvider(AtomicUsize::new(0));
+    let date = Some(NaiveDate::from_ymd_opt(2030, 1, 1).unwrap());
+
+    for _ in 0..3 {
+        let weather = weather::cached_weather(&cache, &provider, tokyo(), date)
+            .await
+            .unwrap();
+        assert_eq!(weather, rainy());
+    }
+    assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+
+    weather::cached_weather(&cache, &provider, Location::parse("0,0").unwrap(), date)
+        .await
+        .unwrap();
+    assert_eq!(provider.0.load(Ordering::SeqCst), 2);
+}